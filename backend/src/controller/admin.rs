@@ -0,0 +1,1055 @@
+use actix_web::{web, HttpResponse, Responder};
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use utoipa::ToSchema;
+
+use crate::services::api_key::{self, ApiKeyInfo, ApiScope, CreatedApiKey};
+use crate::services::drift::{self as drift_service, SchemaDriftOverview};
+use crate::services::stats;
+use crate::services::time_table::{self, NewTimeTableVersion, TimeTableVersionError};
+use crate::utils::{
+    cache::{self, CacheEntrySummary},
+    config::AppConfig,
+    drift::{self, SchemaDriftEvent, UpstreamEndpoint},
+    holiday::{self, HolidayCalendar},
+    http::{self, DnsCacheInfo},
+    location::{self, LocationConfig},
+    rate_limit,
+    response::ApiResponse,
+    schedule::{self, TimeTableConfig, TimeTableVersion},
+    simulator,
+    toggles::{self, RuntimeToggles, RuntimeTogglesPatch},
+};
+
+/// 缓存总览
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CacheOverview {
+    /// 缓存条目总数（含已过期但尚未淘汰的）
+    pub total: usize,
+    /// 有效条目数
+    pub valid: usize,
+    /// 条目列表（按缓存时间倒序）
+    pub entries: Vec<CacheEntrySummary>,
+}
+
+/// 缓存总览 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CacheOverviewApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// 缓存总览数据
+    pub data: CacheOverview,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 清理结果
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct EvictionResult {
+    /// 删除的条目数
+    #[schema(example = 3)]
+    pub removed: usize,
+}
+
+/// 清理结果 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct EvictionApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// 清理结果
+    pub data: EvictionResult,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 按 UCode 清除缓存的请求体
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct PurgeCacheRequest {
+    /// 学生 UCode
+    #[schema(example = "ABC123DEF456GHI789JKL012MNO345PQR678")]
+    pub ucode: String,
+}
+
+/// DNS 缓存 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DnsCacheApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// DNS 缓存条目
+    pub data: Vec<DnsCacheInfo>,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 模拟器测试请求体
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct SimulatorTestRequest {
+    /// 用于测试的 UCode（不传则使用 TEST_STUDENT_UCODE）
+    #[serde(default)]
+    pub ucode: Option<String>,
+}
+
+/// 模拟器测试结果（只返回是否捕获到凭据，不返回凭据本身）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SimulatorTestResult {
+    /// 模拟器是否运行成功
+    pub success: bool,
+    /// 是否捕获到 Basic Auth
+    pub basic_auth_found: bool,
+    /// 是否捕获到 Bearer Token
+    pub bearer_auth_found: bool,
+    /// 运行耗时（毫秒）
+    #[schema(example = 6500)]
+    pub duration_ms: i64,
+    /// 失败原因
+    pub error: Option<String>,
+}
+
+/// 模拟器测试 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SimulatorTestApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// 模拟器测试结果
+    pub data: SimulatorTestResult,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 日志查询参数
+#[derive(Debug, Deserialize)]
+pub struct LogsQuery {
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+/// 请求日志 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RequestLogsApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// 日志分页数据
+    pub data: stats::RequestLogPage,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 管理统计信息
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AdminStats {
+    /// 访问统计
+    pub access: stats::StatsResponse,
+    /// 课表缓存条目总数
+    pub cache_total: usize,
+    /// 课表缓存有效条目数
+    pub cache_valid: usize,
+    /// DNS 缓存条目数
+    pub dns_entries: usize,
+    /// 当前运行时开关
+    pub toggles: RuntimeToggles,
+    /// 本次运行以来的上游结构漂移（最近出现的在前）
+    pub recent_drift: Vec<SchemaDriftEvent>,
+}
+
+/// 管理统计 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AdminStatsApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// 管理统计数据
+    pub data: AdminStats,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 运行时开关 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TogglesApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// 运行时开关
+    pub data: RuntimeToggles,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 查看课表缓存
+///
+/// 返回缓存条目数量和每个条目的摘要（UCode 以哈希形式展示）。
+#[utoipa::path(
+    get,
+    path = "/api/admin/cache",
+    tag = "Admin",
+    params(("X-Admin-Token" = String, Header, description = "管理令牌")),
+    responses(
+        (status = 200, description = "成功获取缓存信息", body = CacheOverviewApiResponse),
+        (status = 401, description = "管理令牌无效")
+    )
+)]
+pub async fn get_cache() -> impl Responder {
+    let (total, valid) = cache::get_cache_stats();
+    let data = CacheOverview {
+        total,
+        valid,
+        entries: cache::list_cache_entries(),
+    };
+    HttpResponse::Ok().json(ApiResponse::success(200, data, "OK"))
+}
+
+/// 清空全部课表缓存
+#[utoipa::path(
+    delete,
+    path = "/api/admin/cache",
+    tag = "Admin",
+    params(("X-Admin-Token" = String, Header, description = "管理令牌")),
+    responses(
+        (status = 200, description = "缓存已清空", body = EvictionApiResponse),
+        (status = 401, description = "管理令牌无效")
+    )
+)]
+pub async fn clear_cache() -> impl Responder {
+    let removed = cache::clear_all_cache();
+    tracing::warn!("Admin cleared schedule cache ({} entries)", removed);
+    HttpResponse::Ok().json(ApiResponse::success(200, EvictionResult { removed }, "OK"))
+}
+
+/// 淘汰已过期的课表缓存
+#[utoipa::path(
+    post,
+    path = "/api/admin/cache/evict-expired",
+    tag = "Admin",
+    params(("X-Admin-Token" = String, Header, description = "管理令牌")),
+    responses(
+        (status = 200, description = "过期缓存已淘汰", body = EvictionApiResponse),
+        (status = 401, description = "管理令牌无效")
+    )
+)]
+pub async fn evict_expired_cache() -> impl Responder {
+    let removed = cache::evict_expired_cache();
+    HttpResponse::Ok().json(ApiResponse::success(200, EvictionResult { removed }, "OK"))
+}
+
+/// 按 UCode 清除单个用户的课表缓存
+///
+/// UCode 放在请求体里，避免出现在访问日志中。
+#[utoipa::path(
+    post,
+    path = "/api/admin/cache/purge",
+    tag = "Admin",
+    params(("X-Admin-Token" = String, Header, description = "管理令牌")),
+    request_body = PurgeCacheRequest,
+    responses(
+        (status = 200, description = "缓存已清除（removed 为 0 表示该用户没有缓存）", body = EvictionApiResponse),
+        (status = 400, description = "缺少 ucode"),
+        (status = 401, description = "管理令牌无效")
+    )
+)]
+pub async fn purge_user_cache(payload: web::Json<PurgeCacheRequest>) -> impl Responder {
+    if payload.ucode.trim().is_empty() {
+        let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), "Missing ucode");
+        return HttpResponse::BadRequest().json(resp);
+    }
+
+    let removed = usize::from(cache::clear_cache(payload.ucode.trim()));
+    HttpResponse::Ok().json(ApiResponse::success(200, EvictionResult { removed }, "OK"))
+}
+
+/// 查看 DNS 缓存
+#[utoipa::path(
+    get,
+    path = "/api/admin/dns-cache",
+    tag = "Admin",
+    params(("X-Admin-Token" = String, Header, description = "管理令牌")),
+    responses(
+        (status = 200, description = "成功获取 DNS 缓存", body = DnsCacheApiResponse),
+        (status = 401, description = "管理令牌无效")
+    )
+)]
+pub async fn get_dns_cache() -> impl Responder {
+    HttpResponse::Ok().json(ApiResponse::success(200, http::get_dns_cache_entries(), "OK"))
+}
+
+/// 清空 DNS 缓存
+///
+/// 学校服务器换 IP 后调用，下次请求会重新解析 A 记录。
+#[utoipa::path(
+    delete,
+    path = "/api/admin/dns-cache",
+    tag = "Admin",
+    params(("X-Admin-Token" = String, Header, description = "管理令牌")),
+    responses(
+        (status = 200, description = "DNS 缓存已清空", body = EvictionApiResponse),
+        (status = 401, description = "管理令牌无效")
+    )
+)]
+pub async fn flush_dns_cache() -> impl Responder {
+    let removed = http::clear_dns_cache();
+    HttpResponse::Ok().json(ApiResponse::success(200, EvictionResult { removed }, "OK"))
+}
+
+/// 重置限流计数器
+///
+/// 误伤正常用户时使用，所有 IP 和 UCode 的配额立即恢复。
+#[utoipa::path(
+    delete,
+    path = "/api/admin/rate-limits",
+    tag = "Admin",
+    params(("X-Admin-Token" = String, Header, description = "管理令牌")),
+    responses(
+        (status = 200, description = "限流计数器已重置", body = EvictionApiResponse),
+        (status = 401, description = "管理令牌无效")
+    )
+)]
+pub async fn reset_rate_limits() -> impl Responder {
+    let removed = rate_limit::reset_all();
+    HttpResponse::Ok().json(ApiResponse::success(200, EvictionResult { removed }, "OK"))
+}
+
+/// 试运行浏览器模拟器
+///
+/// 用于确认学校的验证方式是否变化，只返回是否捕获到凭据。
+#[utoipa::path(
+    post,
+    path = "/api/admin/simulator",
+    tag = "Admin",
+    params(("X-Admin-Token" = String, Header, description = "管理令牌")),
+    request_body = SimulatorTestRequest,
+    responses(
+        (status = 200, description = "模拟器已运行（是否成功见 success 字段）", body = SimulatorTestApiResponse),
+        (status = 401, description = "管理令牌无效")
+    )
+)]
+pub async fn run_simulator(
+    config: web::Data<AppConfig>,
+    payload: Option<web::Json<SimulatorTestRequest>>,
+) -> impl Responder {
+    let ucode = payload.and_then(|p| p.into_inner().ucode);
+    let start_time = Instant::now();
+
+    let data = match simulator::start_simulator(ucode, &config).await {
+        Ok(result) => SimulatorTestResult {
+            success: true,
+            basic_auth_found: result.basic_auth_value.is_some(),
+            bearer_auth_found: result.bearer_auth_value.is_some(),
+            duration_ms: start_time.elapsed().as_millis() as i64,
+            error: None,
+        },
+        Err(e) => SimulatorTestResult {
+            success: false,
+            basic_auth_found: false,
+            bearer_auth_found: false,
+            duration_ms: start_time.elapsed().as_millis() as i64,
+            error: Some(e.to_string()),
+        },
+    };
+
+    HttpResponse::Ok().json(ApiResponse::success(200, data, "OK"))
+}
+
+/// 查询最近的请求日志
+#[utoipa::path(
+    get,
+    path = "/api/admin/logs",
+    tag = "Admin",
+    params(
+        ("X-Admin-Token" = String, Header, description = "管理令牌"),
+        ("limit" = Option<u64>, Query, description = "每页条数，默认 50，最大 500"),
+        ("offset" = Option<u64>, Query, description = "偏移量，默认 0")
+    ),
+    responses(
+        (status = 200, description = "成功获取日志", body = RequestLogsApiResponse),
+        (status = 401, description = "管理令牌无效"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn get_logs(
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<LogsQuery>,
+) -> impl Responder {
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    let offset = query.offset.unwrap_or(0);
+
+    match stats::get_recent_logs(db.get_ref(), limit, offset).await {
+        Ok(page) => HttpResponse::Ok().json(ApiResponse::success(200, page, "OK")),
+        Err(e) => {
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(500, serde_json::json!({}), format!("Failed to get logs: {}", e));
+            HttpResponse::InternalServerError().json(resp)
+        }
+    }
+}
+
+/// 获取运维统计信息
+///
+/// 汇总访问统计、缓存状态、运行时开关和本次运行以来的上游结构漂移。
+#[utoipa::path(
+    get,
+    path = "/api/admin/stats",
+    tag = "Admin",
+    params(("X-Admin-Token" = String, Header, description = "管理令牌")),
+    responses(
+        (status = 200, description = "成功获取统计信息", body = AdminStatsApiResponse),
+        (status = 401, description = "管理令牌无效"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn get_admin_stats(db: web::Data<DatabaseConnection>) -> impl Responder {
+    let access = match stats::get_stats(db.get_ref()).await {
+        Ok(s) => s,
+        Err(e) => {
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(500, serde_json::json!({}), format!("Failed to get stats: {}", e));
+            return HttpResponse::InternalServerError().json(resp);
+        }
+    };
+
+    let (cache_total, cache_valid) = cache::get_cache_stats();
+    let data = AdminStats {
+        access,
+        cache_total,
+        cache_valid,
+        dns_entries: http::get_dns_cache_entries().len(),
+        toggles: toggles::get_toggles(),
+        recent_drift: drift::recent_drift(),
+    };
+    HttpResponse::Ok().json(ApiResponse::success(200, data, "OK"))
+}
+
+/// 查看运行时开关
+#[utoipa::path(
+    get,
+    path = "/api/admin/toggles",
+    tag = "Admin",
+    params(("X-Admin-Token" = String, Header, description = "管理令牌")),
+    responses(
+        (status = 200, description = "成功获取运行时开关", body = TogglesApiResponse),
+        (status = 401, description = "管理令牌无效")
+    )
+)]
+pub async fn get_toggles() -> impl Responder {
+    HttpResponse::Ok().json(ApiResponse::success(200, toggles::get_toggles(), "OK"))
+}
+
+/// 修改运行时开关
+///
+/// 只修改请求体中传入的字段，重启后恢复默认值。
+#[utoipa::path(
+    patch,
+    path = "/api/admin/toggles",
+    tag = "Admin",
+    params(("X-Admin-Token" = String, Header, description = "管理令牌")),
+    request_body = RuntimeTogglesPatch,
+    responses(
+        (status = 200, description = "运行时开关已更新", body = TogglesApiResponse),
+        (status = 401, description = "管理令牌无效")
+    )
+)]
+pub async fn update_toggles(payload: web::Json<RuntimeTogglesPatch>) -> impl Responder {
+    let updated = toggles::update_toggles(&payload);
+    tracing::warn!("Admin updated runtime toggles: {:?}", updated);
+    HttpResponse::Ok().json(ApiResponse::success(200, updated, "OK"))
+}
+
+/// 新建 API Key 的请求体
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateApiKeyRequest {
+    /// 接入方名称
+    #[schema(example = "课表小程序")]
+    pub name: String,
+    /// 授权范围
+    #[schema(example = json!(["schedule", "meta"]))]
+    pub scopes: Vec<ApiScope>,
+    /// 每日配额（不传表示不限）
+    #[serde(default)]
+    #[schema(example = 1000)]
+    pub quota_per_day: Option<i32>,
+    /// 过期时间（毫秒时间戳，不传表示永不过期）
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+}
+
+/// 新建 API Key API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreatedApiKeyApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// 新建的 API Key（明文只返回这一次）
+    pub data: CreatedApiKey,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// API Key 列表 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyListApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// API Key 列表
+    pub data: Vec<ApiKeyInfo>,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 创建 API Key
+///
+/// 明文只在本次响应中返回，数据库仅保存哈希。
+#[utoipa::path(
+    post,
+    path = "/api/admin/api-keys",
+    tag = "Admin",
+    params(("X-Admin-Token" = String, Header, description = "管理令牌")),
+    request_body = CreateApiKeyRequest,
+    responses(
+        (status = 200, description = "API Key 已创建", body = CreatedApiKeyApiResponse),
+        (status = 400, description = "请求参数错误"),
+        (status = 401, description = "管理令牌无效"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn create_api_key(
+    db: web::Data<DatabaseConnection>,
+    payload: web::Json<CreateApiKeyRequest>,
+) -> impl Responder {
+    let name = payload.name.trim();
+    if name.is_empty() || payload.scopes.is_empty() {
+        let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), "Name and at least one scope are required");
+        return HttpResponse::BadRequest().json(resp);
+    }
+    if payload.quota_per_day.is_some_and(|q| q <= 0) {
+        let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), "quota_per_day must be positive");
+        return HttpResponse::BadRequest().json(resp);
+    }
+
+    match api_key::create_api_key(db.get_ref(), name, &payload.scopes, payload.quota_per_day, payload.expires_at).await {
+        Ok(created) => {
+            tracing::warn!("Admin created API key #{} ({})", created.info.id, created.info.name);
+            HttpResponse::Ok().json(ApiResponse::success(200, created, "OK"))
+        }
+        Err(e) => {
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(500, serde_json::json!({}), format!("Failed to create API key: {}", e));
+            HttpResponse::InternalServerError().json(resp)
+        }
+    }
+}
+
+/// 列出 API Key
+///
+/// 返回每个 Key 的授权范围、配额、今日用量和日志中的总请求数。
+#[utoipa::path(
+    get,
+    path = "/api/admin/api-keys",
+    tag = "Admin",
+    params(("X-Admin-Token" = String, Header, description = "管理令牌")),
+    responses(
+        (status = 200, description = "成功获取 API Key 列表", body = ApiKeyListApiResponse),
+        (status = 401, description = "管理令牌无效"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn list_api_keys(db: web::Data<DatabaseConnection>) -> impl Responder {
+    match api_key::list_api_keys(db.get_ref()).await {
+        Ok(keys) => HttpResponse::Ok().json(ApiResponse::success(200, keys, "OK")),
+        Err(e) => {
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(500, serde_json::json!({}), format!("Failed to list API keys: {}", e));
+            HttpResponse::InternalServerError().json(resp)
+        }
+    }
+}
+
+/// 吊销 API Key
+#[utoipa::path(
+    delete,
+    path = "/api/admin/api-keys/{id}",
+    tag = "Admin",
+    params(
+        ("X-Admin-Token" = String, Header, description = "管理令牌"),
+        ("id" = i32, Path, description = "API Key ID")
+    ),
+    responses(
+        (status = 200, description = "API Key 已吊销"),
+        (status = 401, description = "管理令牌无效"),
+        (status = 404, description = "API Key 不存在"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn revoke_api_key(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let id = path.into_inner();
+    match api_key::revoke_api_key(db.get_ref(), id).await {
+        Ok(true) => {
+            tracing::warn!("Admin revoked API key #{}", id);
+            HttpResponse::Ok().json(ApiResponse::success(200, serde_json::json!({ "id": id, "revoked": true }), "OK"))
+        }
+        Ok(false) => {
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(404, serde_json::json!({}), "API key not found");
+            HttpResponse::NotFound().json(resp)
+        }
+        Err(e) => {
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(500, serde_json::json!({}), format!("Failed to revoke API key: {}", e));
+            HttpResponse::InternalServerError().json(resp)
+        }
+    }
+}
+
+/// 作息时间配置 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TimeTableConfigApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// 当前生效的作息时间配置
+    pub data: TimeTableConfig,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 查看当前生效的作息时间配置
+#[utoipa::path(
+    get,
+    path = "/api/admin/time-tables",
+    tag = "Admin",
+    params(("X-Admin-Token" = String, Header, description = "管理令牌")),
+    responses(
+        (status = 200, description = "成功获取作息时间配置", body = TimeTableConfigApiResponse),
+        (status = 401, description = "管理令牌无效")
+    )
+)]
+pub async fn get_time_tables() -> impl Responder {
+    let config = schedule::current_time_tables();
+    HttpResponse::Ok().json(ApiResponse::success(200, config.as_ref().clone(), "OK"))
+}
+
+/// 重新加载作息时间配置
+///
+/// 重新读取 `TIME_TABLE_CONFIG` 指向的文件；文件不合法时保留原配置并返回 400。
+/// 已缓存的课表响应中的时间表在下次请求时按新配置计算。
+#[utoipa::path(
+    post,
+    path = "/api/admin/time-tables/reload",
+    tag = "Admin",
+    params(("X-Admin-Token" = String, Header, description = "管理令牌")),
+    responses(
+        (status = 200, description = "作息时间配置已重新加载", body = TimeTableConfigApiResponse),
+        (status = 400, description = "配置文件不合法，原配置保持不变"),
+        (status = 401, description = "管理令牌无效")
+    )
+)]
+pub async fn reload_time_tables(config: web::Data<AppConfig>) -> impl Responder {
+    match schedule::reload_time_tables(config.time_table_path.as_deref()) {
+        Ok(updated) => {
+            tracing::warn!(
+                "Admin reloaded time tables from {}",
+                config.time_table_path.as_deref().unwrap_or("<builtin>")
+            );
+            HttpResponse::Ok().json(ApiResponse::success(200, updated.as_ref().clone(), "OK"))
+        }
+        Err(e) => {
+            tracing::error!("Failed to reload time tables: {}", e);
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), e.to_string());
+            HttpResponse::BadRequest().json(resp)
+        }
+    }
+}
+
+/// 时间表版本 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TimeTableVersionApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// 时间表版本
+    pub data: TimeTableVersion,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 时间表版本列表 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TimeTableVersionListApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// 时间表版本列表（按生效起始日期排序）
+    pub data: Vec<TimeTableVersion>,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 列出时间表版本
+#[utoipa::path(
+    get,
+    path = "/api/admin/time-table-versions",
+    tag = "Admin",
+    params(("X-Admin-Token" = String, Header, description = "管理令牌")),
+    responses(
+        (status = 200, description = "成功获取时间表版本列表", body = TimeTableVersionListApiResponse),
+        (status = 401, description = "管理令牌无效"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn list_time_table_versions(db: web::Data<DatabaseConnection>) -> impl Responder {
+    match time_table::list_versions(db.get_ref()).await {
+        Ok(versions) => HttpResponse::Ok().json(ApiResponse::success(200, versions, "OK")),
+        Err(e) => {
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(500, serde_json::json!({}), format!("Failed to list time table versions: {}", e));
+            HttpResponse::InternalServerError().json(resp)
+        }
+    }
+}
+
+/// 新建时间表版本
+///
+/// 生效范围内的日期改用该版本的时间表（对所有校区生效）；多个版本重叠时范围最短的优先。
+#[utoipa::path(
+    post,
+    path = "/api/admin/time-table-versions",
+    tag = "Admin",
+    params(("X-Admin-Token" = String, Header, description = "管理令牌")),
+    request_body = NewTimeTableVersion,
+    responses(
+        (status = 200, description = "时间表版本已创建", body = TimeTableVersionApiResponse),
+        (status = 400, description = "日期或时间不合法"),
+        (status = 401, description = "管理令牌无效"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn create_time_table_version(
+    db: web::Data<DatabaseConnection>,
+    payload: web::Json<NewTimeTableVersion>,
+) -> impl Responder {
+    match time_table::create_version(db.get_ref(), &payload).await {
+        Ok(version) => {
+            tracing::warn!(
+                "Admin created time table version #{} '{}' ({} ~ {})",
+                version.id, version.name, version.effective_from, version.effective_to
+            );
+            HttpResponse::Ok().json(ApiResponse::success(200, version, "OK"))
+        }
+        Err(e @ TimeTableVersionError::Invalid(_)) => {
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), e.to_string());
+            HttpResponse::BadRequest().json(resp)
+        }
+        Err(e) => {
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(500, serde_json::json!({}), format!("Failed to create time table version: {}", e));
+            HttpResponse::InternalServerError().json(resp)
+        }
+    }
+}
+
+/// 删除时间表版本
+#[utoipa::path(
+    delete,
+    path = "/api/admin/time-table-versions/{id}",
+    tag = "Admin",
+    params(
+        ("X-Admin-Token" = String, Header, description = "管理令牌"),
+        ("id" = i32, Path, description = "时间表版本 ID")
+    ),
+    responses(
+        (status = 200, description = "时间表版本已删除"),
+        (status = 401, description = "管理令牌无效"),
+        (status = 404, description = "时间表版本不存在"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn delete_time_table_version(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let id = path.into_inner();
+    match time_table::delete_version(db.get_ref(), id).await {
+        Ok(()) => {
+            tracing::warn!("Admin deleted time table version #{}", id);
+            HttpResponse::Ok().json(ApiResponse::success(200, serde_json::json!({ "id": id, "deleted": true }), "OK"))
+        }
+        Err(TimeTableVersionError::NotFound) => {
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(404, serde_json::json!({}), "Time table version not found");
+            HttpResponse::NotFound().json(resp)
+        }
+        Err(e) => {
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(500, serde_json::json!({}), format!("Failed to delete time table version: {}", e));
+            HttpResponse::InternalServerError().json(resp)
+        }
+    }
+}
+
+/// 节假日配置 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct HolidayCalendarApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// 当前生效的节假日与调休配置
+    pub data: HolidayCalendar,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 查看当前生效的节假日与调休配置
+#[utoipa::path(
+    get,
+    path = "/api/admin/holidays",
+    tag = "Admin",
+    params(("X-Admin-Token" = String, Header, description = "管理令牌")),
+    responses(
+        (status = 200, description = "成功获取节假日配置", body = HolidayCalendarApiResponse),
+        (status = 401, description = "管理令牌无效")
+    )
+)]
+pub async fn get_holidays() -> impl Responder {
+    let calendar = holiday::current_holidays();
+    HttpResponse::Ok().json(ApiResponse::success(200, calendar.as_ref().clone(), "OK"))
+}
+
+/// 重新加载节假日与调休配置
+///
+/// 重新读取 `HOLIDAY_CONFIG` 指向的文件；文件不合法时保留原配置并返回 400。
+#[utoipa::path(
+    post,
+    path = "/api/admin/holidays/reload",
+    tag = "Admin",
+    params(("X-Admin-Token" = String, Header, description = "管理令牌")),
+    responses(
+        (status = 200, description = "节假日配置已重新加载", body = HolidayCalendarApiResponse),
+        (status = 400, description = "配置文件不合法，原配置保持不变"),
+        (status = 401, description = "管理令牌无效")
+    )
+)]
+pub async fn reload_holidays(config: web::Data<AppConfig>) -> impl Responder {
+    match holiday::reload_holidays(config.holiday_path.as_deref()) {
+        Ok(updated) => {
+            tracing::warn!(
+                "Admin reloaded holidays from {}",
+                config.holiday_path.as_deref().unwrap_or("<builtin>")
+            );
+            HttpResponse::Ok().json(ApiResponse::success(200, updated.as_ref().clone(), "OK"))
+        }
+        Err(e) => {
+            tracing::error!("Failed to reload holidays: {}", e);
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), e.to_string());
+            HttpResponse::BadRequest().json(resp)
+        }
+    }
+}
+
+/// 教室解析配置 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LocationConfigApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// 当前生效的教室解析规则
+    pub data: LocationConfig,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 查看当前生效的教室解析规则
+#[utoipa::path(
+    get,
+    path = "/api/admin/locations",
+    tag = "Admin",
+    params(("X-Admin-Token" = String, Header, description = "管理令牌")),
+    responses(
+        (status = 200, description = "成功获取教室解析规则", body = LocationConfigApiResponse),
+        (status = 401, description = "管理令牌无效")
+    )
+)]
+pub async fn get_locations() -> impl Responder {
+    let parser = location::current_locations();
+    HttpResponse::Ok().json(ApiResponse::success(200, parser.config().clone(), "OK"))
+}
+
+/// 重新加载教室解析规则
+///
+/// 重新读取 `LOCATION_CONFIG` 指向的文件；文件不合法（如正则无法编译）时保留原规则并返回 400。
+#[utoipa::path(
+    post,
+    path = "/api/admin/locations/reload",
+    tag = "Admin",
+    params(("X-Admin-Token" = String, Header, description = "管理令牌")),
+    responses(
+        (status = 200, description = "教室解析规则已重新加载", body = LocationConfigApiResponse),
+        (status = 400, description = "配置文件不合法，原规则保持不变"),
+        (status = 401, description = "管理令牌无效")
+    )
+)]
+pub async fn reload_locations(config: web::Data<AppConfig>) -> impl Responder {
+    match location::reload_locations(config.location_path.as_deref()) {
+        Ok(updated) => {
+            tracing::warn!(
+                "Admin reloaded location rules from {}",
+                config.location_path.as_deref().unwrap_or("<builtin>")
+            );
+            HttpResponse::Ok().json(ApiResponse::success(200, updated.config().clone(), "OK"))
+        }
+        Err(e) => {
+            tracing::error!("Failed to reload location rules: {}", e);
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), e.to_string());
+            HttpResponse::BadRequest().json(resp)
+        }
+    }
+}
+
+/// 上游结构监测查询参数
+#[derive(Debug, Deserialize)]
+pub struct SchemaDriftQuery {
+    pub limit: Option<u64>,
+}
+
+/// 上游结构监测 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SchemaDriftApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// 结构指纹与漂移事件
+    pub data: SchemaDriftOverview,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 查看上游结构指纹与漂移事件
+///
+/// 每个学校接口第一次响应时记录字段名、类型（课程字符串还记录字段数）作为指纹，
+/// 之后的响应与指纹不一致时记录漂移事件（附脱敏样本），同一新结构只记一条并累加次数。
+#[utoipa::path(
+    get,
+    path = "/api/admin/schema-drift",
+    tag = "Admin",
+    params(
+        ("X-Admin-Token" = String, Header, description = "管理令牌"),
+        ("limit" = Option<u64>, Query, description = "返回事件条数，默认 20，最大 200")
+    ),
+    responses(
+        (status = 200, description = "成功获取结构监测信息", body = SchemaDriftApiResponse),
+        (status = 401, description = "管理令牌无效"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn get_schema_drift(
+    db: web::Data<DatabaseConnection>,
+    query: web::Query<SchemaDriftQuery>,
+) -> impl Responder {
+    let limit = query.limit.unwrap_or(20).clamp(1, 200);
+    match drift_service::overview(db.get_ref(), limit).await {
+        Ok(overview) => HttpResponse::Ok().json(ApiResponse::success(200, overview, "OK")),
+        Err(e) => {
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(500, serde_json::json!({}), format!("Failed to get schema drift: {}", e));
+            HttpResponse::InternalServerError().json(resp)
+        }
+    }
+}
+
+/// 重置接口的结构指纹
+///
+/// 确认上游变化符合预期后使用：删除指纹，下一次响应重新学习。已记录的事件保留。
+#[utoipa::path(
+    delete,
+    path = "/api/admin/schema-drift/baselines/{endpoint}",
+    tag = "Admin",
+    params(
+        ("X-Admin-Token" = String, Header, description = "管理令牌"),
+        ("endpoint" = String, Path, description = "接口名：oauth/token、getXn、getSemesterbyXn、getListByNoWeek2")
+    ),
+    responses(
+        (status = 200, description = "指纹已重置"),
+        (status = 401, description = "管理令牌无效"),
+        (status = 404, description = "未知接口或尚无指纹"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn reset_schema_baseline(
+    db: web::Data<DatabaseConnection>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let name = path.into_inner();
+    let Some(endpoint) = UpstreamEndpoint::from_name(&name) else {
+        let resp: ApiResponse<serde_json::Value> = ApiResponse::error(404, serde_json::json!({}), format!("Unknown upstream endpoint: {}", name));
+        return HttpResponse::NotFound().json(resp);
+    };
+    match drift_service::reset_baseline(db.get_ref(), endpoint).await {
+        Ok(true) => {
+            tracing::warn!("Admin reset upstream schema fingerprint for {}", endpoint.name());
+            HttpResponse::Ok().json(ApiResponse::success(200, serde_json::json!({ "endpoint": endpoint.name(), "reset": true }), "OK"))
+        }
+        Ok(false) => {
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(404, serde_json::json!({}), "No fingerprint recorded for this endpoint");
+            HttpResponse::NotFound().json(resp)
+        }
+        Err(e) => {
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(500, serde_json::json!({}), format!("Failed to reset fingerprint: {}", e));
+            HttpResponse::InternalServerError().json(resp)
+        }
+    }
+}
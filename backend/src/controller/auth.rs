@@ -0,0 +1,173 @@
+use actix_web::{
+    dev::Payload, http::header, http::StatusCode, web, FromRequest, HttpRequest, HttpResponse,
+    Responder, ResponseError,
+};
+use futures::future::LocalBoxFuture;
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::services::session::{self, LoginResponse, SessionContext, SessionError};
+use crate::utils::{
+    config::AppConfig, http::create_http_client, response::ApiResponse, token::bearer_token,
+};
+
+impl ResponseError for SessionError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            SessionError::Db(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::UNAUTHORIZED,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let message = match self {
+            SessionError::Db(e) => {
+                tracing::error!("Failed to load session: {}", e);
+                "Failed to load session".to_string()
+            }
+            other => other.to_string(),
+        };
+        let resp: ApiResponse<serde_json::Value> =
+            ApiResponse::error(self.status_code().as_u16(), serde_json::json!({}), message);
+        HttpResponse::build(self.status_code()).json(resp)
+    }
+}
+
+/// 可选的会话提取器
+///
+/// 请求没有 `Authorization: Bearer` 头时为 `None`（兼容直接传 UCode 的旧调用方式）；
+/// 带了令牌但令牌无效、过期或已注销时直接返回 401
+pub struct AuthSession(pub Option<SessionContext>);
+
+impl AuthSession {
+    /// 优先使用会话中的 UCode，没有会话时回退到请求里显式传入的 UCode
+    pub fn ucode_or(&self, explicit: Option<String>) -> Option<String> {
+        self.0
+            .as_ref()
+            .map(|s| s.ucode.clone())
+            .or_else(|| explicit.map(|u| u.trim().to_string()).filter(|u| !u.is_empty()))
+    }
+}
+
+impl FromRequest for AuthSession {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let token = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(bearer_token)
+            .map(|t| t.to_string());
+        let db = req.app_data::<web::Data<DatabaseConnection>>().cloned();
+        let config = req.app_data::<web::Data<AppConfig>>().cloned();
+
+        Box::pin(async move {
+            let (Some(token), Some(db), Some(config)) = (token, db, config) else {
+                return Ok(AuthSession(None));
+            };
+            let context = session::resolve(db.get_ref(), &token, &config).await?;
+            Ok(AuthSession(Some(context)))
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LoginRequest {
+    /// 学生 UCode
+    #[schema(example = "ABC123DEF456GHI789JKL012MNO345PQR678")]
+    pub ucode: String,
+}
+
+/// 登录 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LoginApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// 会话令牌
+    pub data: LoginResponse,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 登录（换取会话令牌）
+///
+/// 传入 UCode，服务端通过学校接口校验一次后签发会话令牌。
+///
+/// **功能说明：**
+/// - UCode 加密保存在服务端，客户端只需保存令牌
+/// - 之后的接口在 `Authorization: Bearer <token>` 头中携带令牌即可，无需再传 UCode
+#[utoipa::path(
+    post,
+    path = "/api/auth/login",
+    tag = "Auth",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "登录成功", body = LoginApiResponse),
+        (status = 400, description = "缺少 ucode"),
+        (status = 401, description = "UCode 无效"),
+        (status = 429, description = "请求过于频繁，见 Retry-After 响应头"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn login(
+    config: web::Data<AppConfig>,
+    db: web::Data<DatabaseConnection>,
+    payload: web::Json<LoginRequest>,
+) -> impl Responder {
+    let ucode = payload.ucode.trim();
+    if ucode.is_empty() {
+        let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), "Missing ucode");
+        return HttpResponse::BadRequest().json(resp);
+    }
+
+    let client = match create_http_client().await {
+        Ok(c) => c,
+        Err(e) => {
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(500, serde_json::json!({}), format!("Create client failed: {}", e));
+            return HttpResponse::InternalServerError().json(resp);
+        }
+    };
+
+    match session::login(db.get_ref(), ucode, &client, &config).await {
+        Ok(data) => HttpResponse::Ok().json(ApiResponse::success(200, data, "OK")),
+        Err(e) => {
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(401, serde_json::json!({}), format!("Login failed: {}", e));
+            HttpResponse::Unauthorized().json(resp)
+        }
+    }
+}
+
+/// 注销当前会话
+#[utoipa::path(
+    post,
+    path = "/api/auth/logout",
+    tag = "Auth",
+    params(("Authorization" = String, Header, description = "Bearer 会话令牌")),
+    responses(
+        (status = 200, description = "已注销"),
+        (status = 401, description = "缺少或无效的会话令牌"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn logout(db: web::Data<DatabaseConnection>, session: AuthSession) -> impl Responder {
+    let Some(context) = session.0 else {
+        let resp: ApiResponse<serde_json::Value> = ApiResponse::error(401, serde_json::json!({}), "Missing session token");
+        return HttpResponse::Unauthorized().json(resp);
+    };
+
+    match session::logout(db.get_ref(), &context.session_id).await {
+        Ok(_) => HttpResponse::Ok().json(ApiResponse::success(200, serde_json::json!({ "logged_out": true }), "OK")),
+        Err(e) => {
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(500, serde_json::json!({}), format!("Logout failed: {}", e));
+            HttpResponse::InternalServerError().json(resp)
+        }
+    }
+}
@@ -0,0 +1,521 @@
+use actix_web::{web, HttpResponse, Responder};
+use chrono::{Duration, NaiveDate};
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use crate::controller::auth::AuthSession;
+use crate::controller::schedule::{fetch_error_response, load_schedule};
+use crate::services::api_key::ApiKeyIdentity;
+use crate::services::calendar::{self, CurrentWeek, DaySchedule, NextLesson, WeekSchedule};
+use crate::services::occurrence::{self, LessonOccurrence, OccurrenceRange};
+use crate::services::search::{self, SearchResults, MAX_QUERY_CHARS};
+use crate::utils::{config::AppConfig, response::ApiResponse};
+
+/// 单日课表 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DayScheduleApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// 当天课表
+    pub data: DaySchedule,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 下一节课 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct NextLessonApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// 下一节课（本学期没有更多课时为空）
+    pub data: Option<NextLesson>,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 缺少 UCode 时的响应
+fn missing_ucode() -> HttpResponse {
+    let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), "Missing ucode or session token");
+    HttpResponse::BadRequest().json(resp)
+}
+
+/// 400 响应
+fn bad_request(message: &str) -> HttpResponse {
+    let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), message);
+    HttpResponse::BadRequest().json(resp)
+}
+
+/// 返回指定日期的课表
+async fn respond_day(
+    config: &AppConfig,
+    db: &DatabaseConnection,
+    ucode: &str,
+    api_key_id: Option<i32>,
+    date: NaiveDate,
+) -> HttpResponse {
+    match load_schedule(config, db, ucode, api_key_id).await {
+        Ok((weeks, semester_weeks)) => {
+            let day = calendar::day_schedule(&weeks, &semester_weeks, date);
+            HttpResponse::Ok().json(ApiResponse::success(200, day, "OK"))
+        }
+        Err(e) => fetch_error_response(&e),
+    }
+}
+
+/// 获取指定日期的课表
+///
+/// 把日期映射到对应的教学周和星期，只返回当天的课，并按当天的作息给出具体上下课时间。
+///
+/// **返回数据：**
+/// - week: 教学周（日期不在学期内时为空，lessons 也为空）
+/// - lessons: 当天的课（连续节次已合并），含 start_time / end_time
+#[utoipa::path(
+    get,
+    path = "/api/schedule/day",
+    tag = "Schedule",
+    params(
+        ("date" = String, Query, description = "日期（YYYY-MM-DD）", example = "2024-10-08"),
+        ("Authorization" = Option<String>, Header, description = "Bearer 会话令牌"),
+        ("ucode" = Option<String>, Query, description = "学生 UCode（已弃用，未携带会话令牌时使用）")
+    ),
+    responses(
+        (status = 200, description = "成功获取当天课表", body = DayScheduleApiResponse),
+        (status = 400, description = "缺少或错误的 date 参数，或缺少 ucode / 会话令牌"),
+        (status = 401, description = "会话令牌无效或已过期"),
+        (status = 429, description = "请求过于频繁，见 Retry-After 响应头"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn get_day(
+    config: web::Data<AppConfig>,
+    db: web::Data<DatabaseConnection>,
+    api_key: Option<web::ReqData<ApiKeyIdentity>>,
+    session: AuthSession,
+    query: web::Query<HashMap<String, String>>,
+) -> impl Responder {
+    let Some(ucode) = session.ucode_or(query.get("ucode").cloned()) else {
+        return missing_ucode();
+    };
+    let Some(date) = query.get("date").and_then(|d| calendar::parse_date(d)) else {
+        let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), "Missing or invalid date, expected YYYY-MM-DD");
+        return HttpResponse::BadRequest().json(resp);
+    };
+
+    respond_day(&config, db.get_ref(), &ucode, api_key.map(|k| k.id), date).await
+}
+
+/// 获取今天的课表（东八区）
+#[utoipa::path(
+    get,
+    path = "/api/schedule/today",
+    tag = "Schedule",
+    params(
+        ("Authorization" = Option<String>, Header, description = "Bearer 会话令牌"),
+        ("ucode" = Option<String>, Query, description = "学生 UCode（已弃用，未携带会话令牌时使用）")
+    ),
+    responses(
+        (status = 200, description = "成功获取今天的课表", body = DayScheduleApiResponse),
+        (status = 400, description = "缺少 ucode 参数或会话令牌"),
+        (status = 401, description = "会话令牌无效或已过期"),
+        (status = 429, description = "请求过于频繁，见 Retry-After 响应头"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn get_today(
+    config: web::Data<AppConfig>,
+    db: web::Data<DatabaseConnection>,
+    api_key: Option<web::ReqData<ApiKeyIdentity>>,
+    session: AuthSession,
+    query: web::Query<HashMap<String, String>>,
+) -> impl Responder {
+    let Some(ucode) = session.ucode_or(query.get("ucode").cloned()) else {
+        return missing_ucode();
+    };
+    let today = calendar::east8_now().date();
+    respond_day(&config, db.get_ref(), &ucode, api_key.map(|k| k.id), today).await
+}
+
+/// 获取明天的课表（东八区）
+#[utoipa::path(
+    get,
+    path = "/api/schedule/tomorrow",
+    tag = "Schedule",
+    params(
+        ("Authorization" = Option<String>, Header, description = "Bearer 会话令牌"),
+        ("ucode" = Option<String>, Query, description = "学生 UCode（已弃用，未携带会话令牌时使用）")
+    ),
+    responses(
+        (status = 200, description = "成功获取明天的课表", body = DayScheduleApiResponse),
+        (status = 400, description = "缺少 ucode 参数或会话令牌"),
+        (status = 401, description = "会话令牌无效或已过期"),
+        (status = 429, description = "请求过于频繁，见 Retry-After 响应头"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn get_tomorrow(
+    config: web::Data<AppConfig>,
+    db: web::Data<DatabaseConnection>,
+    api_key: Option<web::ReqData<ApiKeyIdentity>>,
+    session: AuthSession,
+    query: web::Query<HashMap<String, String>>,
+) -> impl Responder {
+    let Some(ucode) = session.ucode_or(query.get("ucode").cloned()) else {
+        return missing_ucode();
+    };
+    let tomorrow = calendar::east8_now().date() + Duration::days(1);
+    respond_day(&config, db.get_ref(), &ucode, api_key.map(|k| k.id), tomorrow).await
+}
+
+/// 获取下一节课
+///
+/// 从当前时刻（东八区）起查找本学期内下一节还没开始的课，并给出距离上课的分钟数。
+#[utoipa::path(
+    get,
+    path = "/api/schedule/next",
+    tag = "Schedule",
+    params(
+        ("Authorization" = Option<String>, Header, description = "Bearer 会话令牌"),
+        ("ucode" = Option<String>, Query, description = "学生 UCode（已弃用，未携带会话令牌时使用）")
+    ),
+    responses(
+        (status = 200, description = "成功获取下一节课（本学期没有更多课时 data 为空）", body = NextLessonApiResponse),
+        (status = 400, description = "缺少 ucode 参数或会话令牌"),
+        (status = 401, description = "会话令牌无效或已过期"),
+        (status = 429, description = "请求过于频繁，见 Retry-After 响应头"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn get_next(
+    config: web::Data<AppConfig>,
+    db: web::Data<DatabaseConnection>,
+    api_key: Option<web::ReqData<ApiKeyIdentity>>,
+    session: AuthSession,
+    query: web::Query<HashMap<String, String>>,
+) -> impl Responder {
+    let Some(ucode) = session.ucode_or(query.get("ucode").cloned()) else {
+        return missing_ucode();
+    };
+
+    match load_schedule(&config, db.get_ref(), &ucode, api_key.map(|k| k.id)).await {
+        Ok((weeks, semester_weeks)) => {
+            let next = calendar::next_lesson(&weeks, &semester_weeks, calendar::east8_now());
+            let message = if next.is_some() { "OK" } else { "No upcoming lessons this semester" };
+            HttpResponse::Ok().json(ApiResponse::success(200, next, message))
+        }
+        Err(e) => fetch_error_response(&e),
+    }
+}
+
+/// 当前周 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CurrentWeekApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// 当前周解析结果
+    pub data: CurrentWeek,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 单周课表 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WeekScheduleApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// 该周课表
+    pub data: WeekSchedule,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 获取当前教学周
+///
+/// 按东八区日期和学期周列表计算教学周，只返回这一周的课表。
+///
+/// **status 取值：**
+/// - in_semester：日期在某个教学周内
+/// - before_semester：开学前，display 为第一周，并给出 days_until_start
+/// - after_semester：最后一周之后（放假或学期之间），display 为最后一周，并给出 days_since_end
+/// - gap：学期内但不属于任何教学周，display 为下一个教学周
+/// - no_semester：没有学期周信息
+#[utoipa::path(
+    get,
+    path = "/api/schedule/week/current",
+    tag = "Schedule",
+    params(
+        ("date" = Option<String>, Query, description = "按该日期计算（YYYY-MM-DD，默认东八区今天）", example = "2024-10-08"),
+        ("Authorization" = Option<String>, Header, description = "Bearer 会话令牌"),
+        ("ucode" = Option<String>, Query, description = "学生 UCode（已弃用，未携带会话令牌时使用）")
+    ),
+    responses(
+        (status = 200, description = "成功解析当前周", body = CurrentWeekApiResponse),
+        (status = 400, description = "date 格式错误，或缺少 ucode / 会话令牌"),
+        (status = 401, description = "会话令牌无效或已过期"),
+        (status = 429, description = "请求过于频繁，见 Retry-After 响应头"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn get_current_week(
+    config: web::Data<AppConfig>,
+    db: web::Data<DatabaseConnection>,
+    api_key: Option<web::ReqData<ApiKeyIdentity>>,
+    session: AuthSession,
+    query: web::Query<HashMap<String, String>>,
+) -> impl Responder {
+    let Some(ucode) = session.ucode_or(query.get("ucode").cloned()) else {
+        return missing_ucode();
+    };
+    let date = match query.get("date") {
+        Some(raw) => match calendar::parse_date(raw) {
+            Some(d) => d,
+            None => {
+                let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), "Invalid date, expected YYYY-MM-DD");
+                return HttpResponse::BadRequest().json(resp);
+            }
+        },
+        None => calendar::east8_now().date(),
+    };
+
+    match load_schedule(&config, db.get_ref(), &ucode, api_key.map(|k| k.id)).await {
+        Ok((weeks, semester_weeks)) => {
+            let current = calendar::resolve_week(&weeks, &semester_weeks, date);
+            HttpResponse::Ok().json(ApiResponse::success(200, current, "OK"))
+        }
+        Err(e) => fetch_error_response(&e),
+    }
+}
+
+/// 获取指定教学周的课表
+#[utoipa::path(
+    get,
+    path = "/api/schedule/week/{n}",
+    tag = "Schedule",
+    params(
+        ("n" = u32, Path, description = "教学周", example = 6),
+        ("Authorization" = Option<String>, Header, description = "Bearer 会话令牌"),
+        ("ucode" = Option<String>, Query, description = "学生 UCode（已弃用，未携带会话令牌时使用）")
+    ),
+    responses(
+        (status = 200, description = "成功获取该周课表", body = WeekScheduleApiResponse),
+        (status = 400, description = "缺少 ucode 参数或会话令牌"),
+        (status = 401, description = "会话令牌无效或已过期"),
+        (status = 404, description = "本学期没有该教学周"),
+        (status = 429, description = "请求过于频繁，见 Retry-After 响应头"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn get_week(
+    config: web::Data<AppConfig>,
+    db: web::Data<DatabaseConnection>,
+    api_key: Option<web::ReqData<ApiKeyIdentity>>,
+    session: AuthSession,
+    path: web::Path<u32>,
+    query: web::Query<HashMap<String, String>>,
+) -> impl Responder {
+    let Some(ucode) = session.ucode_or(query.get("ucode").cloned()) else {
+        return missing_ucode();
+    };
+    let n = path.into_inner();
+
+    match load_schedule(&config, db.get_ref(), &ucode, api_key.map(|k| k.id)).await {
+        Ok((weeks, semester_weeks)) => {
+            let Some(info) = semester_weeks.iter().find(|w| w.week == n) else {
+                let resp: ApiResponse<serde_json::Value> = ApiResponse::error(404, serde_json::json!({}), format!("Week {} not found in current semester", n));
+                return HttpResponse::NotFound().json(resp);
+            };
+            HttpResponse::Ok().json(ApiResponse::success(200, calendar::week_schedule(&weeks, &semester_weeks, info), "OK"))
+        }
+        Err(e) => fetch_error_response(&e),
+    }
+}
+
+/// 上课列表 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct OccurrencesApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// 按上课时刻排序的上课列表
+    pub data: Vec<LessonOccurrence>,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 从查询参数解析 from / to / include_cancelled
+fn occurrence_range(query: &HashMap<String, String>) -> Result<OccurrenceRange, &'static str> {
+    let parse = |key: &str| query.get(key).map(|d| calendar::parse_date(d).ok_or(()));
+    let (Ok(from), Ok(to)) = (parse("from").transpose(), parse("to").transpose()) else {
+        return Err("Invalid from / to, expected YYYY-MM-DD");
+    };
+    if matches!((from, to), (Some(f), Some(t)) if f > t) {
+        return Err("from must not be after to");
+    }
+    let include_cancelled = query
+        .get("include_cancelled")
+        .is_some_and(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes"));
+    Ok(OccurrenceRange { from, to, include_cancelled })
+}
+
+/// 获取展开后的上课列表
+///
+/// 把按周聚合的课表展开为一次次具体的上课，已合并连续节次，带东八区的 ISO 8601 起止时刻，
+/// 并已应用节假日、调休与时间表版本。导出日历、上课提醒等功能都基于这份数据。
+///
+/// **返回数据：**
+/// - id: 稳定 ID（`课程代码-周次-星期-起始节次`），可用作日历事件的 UID
+/// - start / end: 上下课时刻
+/// - cancelled: 是否因放假停上（仅 include_cancelled=true 时出现 true）
+#[utoipa::path(
+    get,
+    path = "/api/schedule/occurrences",
+    tag = "Schedule",
+    params(
+        ("from" = Option<String>, Query, description = "起始日期（YYYY-MM-DD，含），不传则从学期第一天开始", example = "2024-10-08"),
+        ("to" = Option<String>, Query, description = "结束日期（YYYY-MM-DD，含），不传则到学期最后一天", example = "2024-10-14"),
+        ("include_cancelled" = Option<bool>, Query, description = "是否包含因放假停上的课，默认 false"),
+        ("Authorization" = Option<String>, Header, description = "Bearer 会话令牌"),
+        ("ucode" = Option<String>, Query, description = "学生 UCode（已弃用，未携带会话令牌时使用）")
+    ),
+    responses(
+        (status = 200, description = "成功获取上课列表", body = OccurrencesApiResponse),
+        (status = 400, description = "日期格式错误或 from 晚于 to，或缺少 ucode / 会话令牌"),
+        (status = 401, description = "会话令牌无效或已过期"),
+        (status = 429, description = "请求过于频繁，见 Retry-After 响应头"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn get_occurrences(
+    config: web::Data<AppConfig>,
+    db: web::Data<DatabaseConnection>,
+    api_key: Option<web::ReqData<ApiKeyIdentity>>,
+    session: AuthSession,
+    query: web::Query<HashMap<String, String>>,
+) -> impl Responder {
+    let Some(ucode) = session.ucode_or(query.get("ucode").cloned()) else {
+        return missing_ucode();
+    };
+
+    let range = match occurrence_range(&query) {
+        Ok(range) => range,
+        Err(message) => return bad_request(message),
+    };
+
+    match load_schedule(&config, db.get_ref(), &ucode, api_key.map(|k| k.id)).await {
+        Ok((weeks, semester_weeks)) => {
+            let occurrences = occurrence::expand_occurrences(&weeks, &semester_weeks, range);
+            HttpResponse::Ok().json(ApiResponse::success(200, occurrences, "OK"))
+        }
+        Err(e) => fetch_error_response(&e),
+    }
+}
+
+/// 搜索结果 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SearchApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// 命中的上课
+    pub data: SearchResults,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 搜索课表
+///
+/// 按课程名、课程代码、教师、教室和班级搜索，返回命中课程的每一次上课（格式同上课列表）。
+///
+/// **匹配方式（按相关度从高到低）：**
+/// - exact / substring: 完全相同、包含关键字（忽略大小写和空白）
+/// - pinyin: 拼音全拼，如 `shuxue` 匹配 “高等数学”
+/// - pinyin_initials: 拼音首字母或简拼，如 `gdsx`、`gaodsx` 匹配 “高等数学”
+/// - fuzzy: 按顺序包含关键字的每个字（如 “高数”）、拼音简称（如 `gaoshu`），或有一两个字输错
+///
+/// 同一相关度内按上课时刻排序。
+#[utoipa::path(
+    get,
+    path = "/api/schedule/search",
+    tag = "Schedule",
+    params(
+        ("q" = String, Query, description = "搜索关键字（最多 32 个字）", example = "gdsx"),
+        ("from" = Option<String>, Query, description = "起始日期（YYYY-MM-DD，含），不传则从学期第一天开始", example = "2024-10-08"),
+        ("to" = Option<String>, Query, description = "结束日期（YYYY-MM-DD，含），不传则到学期最后一天", example = "2024-10-14"),
+        ("include_cancelled" = Option<bool>, Query, description = "是否包含因放假停上的课，默认 false"),
+        ("limit" = Option<usize>, Query, description = "最多返回的上课数，默认 50，最大 500"),
+        ("Authorization" = Option<String>, Header, description = "Bearer 会话令牌"),
+        ("ucode" = Option<String>, Query, description = "学生 UCode（已弃用，未携带会话令牌时使用）")
+    ),
+    responses(
+        (status = 200, description = "成功搜索课表", body = SearchApiResponse),
+        (status = 400, description = "缺少关键字或关键字过长、日期格式错误，或缺少 ucode / 会话令牌"),
+        (status = 401, description = "会话令牌无效或已过期"),
+        (status = 429, description = "请求过于频繁，见 Retry-After 响应头"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn search_schedule(
+    config: web::Data<AppConfig>,
+    db: web::Data<DatabaseConnection>,
+    api_key: Option<web::ReqData<ApiKeyIdentity>>,
+    session: AuthSession,
+    query: web::Query<HashMap<String, String>>,
+) -> impl Responder {
+    let Some(ucode) = session.ucode_or(query.get("ucode").cloned()) else {
+        return missing_ucode();
+    };
+
+    let q = query.get("q").map(|q| q.trim()).unwrap_or_default();
+    if q.is_empty() {
+        return bad_request("Missing q");
+    }
+    if q.chars().count() > MAX_QUERY_CHARS {
+        return bad_request("q is too long");
+    }
+    let range = match occurrence_range(&query) {
+        Ok(range) => range,
+        Err(message) => return bad_request(message),
+    };
+    let limit = query
+        .get("limit")
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(50)
+        .clamp(1, 500);
+
+    match load_schedule(&config, db.get_ref(), &ucode, api_key.map(|k| k.id)).await {
+        Ok((weeks, semester_weeks)) => {
+            let results = search::search(&weeks, &semester_weeks, q, range, limit);
+            HttpResponse::Ok().json(ApiResponse::success(200, results, "OK"))
+        }
+        Err(e) => fetch_error_response(&e),
+    }
+}
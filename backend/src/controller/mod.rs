@@ -1,2 +1,5 @@
+pub mod admin;
+pub mod auth;
+pub mod calendar;
 pub mod schedule;
-
+pub mod webhook;
@@ -1,34 +1,55 @@
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{http::header, web, HttpRequest, HttpResponse, Responder};
+use futures::{channel::mpsc, StreamExt};
 use sea_orm::DatabaseConnection;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::time::Instant;
 use utoipa::ToSchema;
 
+use crate::controller::auth::AuthSession;
 use crate::parser::{
     auth::{self, UserInfo},
-    schedule::{DayCourse, SchoolYear, WeekInfo},
+    schedule::{CourseParseDiagnostic, DayCourse, SchoolYear, WeekInfo},
+};
+use crate::services::{
+    api_key::ApiKeyIdentity,
+    calendar,
+    compact::{self, CompactSchedule},
+    course::{self as course_service, LessonConflict},
+    diff::{self, ScheduleDiff},
+    schedule::{self as schedule_service, FetchedSchedule, ScheduleEvent, ScheduleFetchError},
+    snapshot::{self, ScheduleSnapshot, SnapshotInfo},
+    stats,
+    summary::{self, ScheduleSummary},
+    teacher::{self, TeacherSchedule},
+    webhook,
 };
-use crate::services::{course as course_service, stats};
 use crate::utils::{
-    cache, config::AppConfig, http::create_http_client, response::ApiResponse,
-    schedule as schedule_utils,
+    cache, config::AppConfig, http::create_http_client, location::Location, response::ApiResponse,
+    schedule::{self as schedule_utils, TimeTableVersion},
+    toggles,
 };
 
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ScheduleRequest {
-    /// 学生 UCode
+    /// 学生 UCode（已在 Authorization 头中携带会话令牌时可省略）
     ///
     /// 示例：`"ABC123DEF456GHI789JKL012MNO345PQR678"`
+    #[serde(default)]
     #[schema(example = "ABC123DEF456GHI789JKL012MNO345PQR678")]
-    pub ucode: String,
+    pub ucode: Option<String>,
     /// 是否并行请求所有周（默认 true）
     #[serde(default)]
     pub parallel: Option<bool>,
     /// 是否使用缓存（默认 true；若命中直接返回缓存）
     #[serde(default)]
     pub use_cache: Option<bool>,
+    /// 是否返回紧凑格式（默认 false）：`weeks` 为空，课表改由 `compact` 给出（每周模板 + 各周差异）
+    ///
+    /// 仅对普通 JSON 响应生效，NDJSON 流式响应不受影响
+    #[serde(default)]
+    pub compact: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -38,10 +59,63 @@ pub struct ScheduleResponse {
     /// Key: 周号（1-22）
     /// Value: 该周的每日课程列表（周一到周日）
     pub weeks: HashMap<u32, Vec<DayCourse>>,
-    /// 课程时间表
+    /// 课程时间表（今天使用的作息，保留用于兼容）
     pub time_table: Vec<(String, String)>,
-    /// 明确的时令字段："winter" | "summer"
+    /// 明确的时令字段："winter" | "summer"（今天使用的作息）
     pub season: String,
+    /// 各时间表（"winter" / "summer" / "version:<id>" -> 时间表）
+    #[serde(default)]
+    pub time_tables: HashMap<String, Vec<(String, String)>>,
+    /// 每周每天使用的时间表键（周号 -> 周一到周日，对应 `time_tables` 的键）
+    ///
+    /// 按该周的实际日期判断，跨作息切换日（如 6 月 1 日）或有临时作息的周会混用多张时间表
+    #[serde(default)]
+    pub week_seasons: HashMap<u32, Vec<String>>,
+    /// 上课冲突（同一节次排了两门课，或连续节次延伸到另一门课）
+    #[serde(default)]
+    pub conflicts: Vec<LessonConflict>,
+    /// 解析后的上课地点（原始教室字符串 -> 校区、楼宇、楼层、房间）
+    #[serde(default)]
+    pub locations: HashMap<String, Location>,
+    /// 与上一次获取的课表相比的变动（命中缓存或首次获取时为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub changes: Option<ScheduleDiff>,
+    /// 调试信息（本次从学校服务器获取时有课程记录解析失败才返回）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub debug: Option<ScheduleDebug>,
+    /// 紧凑格式的课表（请求 `compact: true` 时返回，此时 `weeks` 为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compact: Option<CompactSchedule>,
+}
+
+impl ScheduleResponse {
+    /// 按请求转为紧凑格式
+    fn compacted(mut self, compact: bool) -> Self {
+        if compact {
+            self.compact = Some(compact::encode(&self.weeks));
+            self.weeks = HashMap::new();
+        }
+        self
+    }
+}
+
+/// 课表调试信息
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ScheduleDebug {
+    /// 解析有问题的课程记录（周号 -> 记录列表）；字段数不足的时段按无课处理，数字字段出错的课程仍保留
+    pub parse_errors: BTreeMap<u32, Vec<CourseParseDiagnostic>>,
+}
+
+impl ScheduleDebug {
+    /// 没有解析失败时返回 None
+    fn from_diagnostics(parse_errors: BTreeMap<u32, Vec<CourseParseDiagnostic>>) -> Option<Self> {
+        (!parse_errors.is_empty()).then_some(Self { parse_errors })
+    }
+
+    /// 解析失败的记录总数
+    fn failure_count(&self) -> usize {
+        self.parse_errors.values().map(Vec::len).sum()
+    }
 }
 
 /// 课表 API 响应（具体类型，用于 OpenAPI 文档）
@@ -78,155 +152,478 @@ pub struct UserInfoApiResponse {
 
 /// 获取学生课表
 ///
-/// 传入学生的 UCode，返回当前学期的完整课表数据（按周聚合）。
+/// 在 `Authorization: Bearer` 头中携带会话令牌（或在请求体中传入 UCode），
+/// 返回当前学期的完整课表数据（按周聚合）。
 ///
 /// **功能说明：**
 /// - 自动识别当前学期
 /// - 并发获取所有周的课程数据
 /// - 返回按周号聚合的课表
+/// - 与上一次获取的课表比较，返回变动明细并推送给已注册的 Webhook
 ///
 /// **返回数据：**
 /// - 每周包含 7 天的课程（周一到周日）
 /// - 每天包含多个课程时段
 /// - 课程信息包括课程名称、教室、教师等详细信息
+///
+/// **紧凑格式：**
+/// - 请求体传 `compact: true` 时 `weeks` 为空，改为返回 `compact`：去重后的课程详情、每周模板和各周差异
+/// - 各周与模板的差异分为 added（新增）、removed（停上）、changed（换课），可用库中的 `compact::decode` 还原完整课表
+///
+/// **流式模式：**
+/// - 请求头带 `Accept: application/x-ndjson` 时改为逐行返回（见 ScheduleStreamLine）
+/// - 每获取完一周立即写出一行 `{"type":"week",...}`，最后一行为 `done` 或 `error`
+/// - 连接中断时已收到的周数据仍然可用
 #[utoipa::path(
     post,
     path = "/api/schedule",
     tag = "Schedule",
     request_body = ScheduleRequest,
+    params(
+        ("Authorization" = Option<String>, Header, description = "Bearer 会话令牌（可替代请求体中的 ucode）"),
+        ("Accept" = Option<String>, Header, description = "传 application/x-ndjson 时逐周流式返回")
+    ),
     responses(
-        (status = 200, description = "成功获取课表数据", body = ScheduleApiResponse),
+        (status = 200, description = "成功获取课表数据（NDJSON 模式下每行为一个 ScheduleStreamLine）", body = ScheduleApiResponse),
         (status = 400, description = "请求参数错误"),
+        (status = 401, description = "会话令牌无效或已过期"),
         (status = 404, description = "未找到当前学期"),
+        (status = 429, description = "请求过于频繁，见 Retry-After 响应头"),
         (status = 500, description = "服务器内部错误")
     )
 )]
 pub async fn post_schedule(
+    req: HttpRequest,
     config: web::Data<AppConfig>,
     db: web::Data<DatabaseConnection>,
+    api_key: Option<web::ReqData<ApiKeyIdentity>>,
+    session: AuthSession,
     payload: web::Json<ScheduleRequest>,
 ) -> impl Responder {
     let start_time = Instant::now();
-    let ucode = payload.ucode.clone();
+    let Some(ucode) = session.ucode_or(payload.ucode.clone()) else {
+        let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), "Missing ucode or session token");
+        return HttpResponse::BadRequest().json(resp);
+    };
 
-    // 是否使用缓存（默认 true）
-    let use_cache = payload.use_cache.unwrap_or(true);
+    // 是否使用缓存（默认 true；管理员可通过运行时开关整体关闭）
+    let cache_enabled = toggles::get_toggles().schedule_cache;
+    let use_cache = payload.use_cache.unwrap_or(true) && cache_enabled;
+    let parallel = payload.parallel.unwrap_or(true);
+    let api_key_id = api_key.map(|k| k.id);
+
+    let wants_ndjson = req
+        .headers()
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|accept| accept.contains(NDJSON_CONTENT_TYPE));
+    if wants_ndjson {
+        let options = FetchOptions { use_cache, cache_enabled, parallel, api_key_id };
+        return stream_schedule_ndjson(config.get_ref().clone(), db.get_ref().clone(), ucode, options, start_time);
+    }
+
+    let compact = payload.compact.unwrap_or(false);
     if use_cache {
-        if let Some(cached_data) = cache::get_cached_schedule(&ucode) {
-            tracing::info!("Cache hit for ucode: {}", ucode);
-            // 东八区当前日期
-            let today = schedule_utils::east8_today_ymd();
-            let season = if schedule_utils::is_summer_schedule(&today) { "summer".to_string() } else { "winter".to_string() };
-            let time_table = if season == "summer" { schedule_utils::get_summer_course_time_table().times } else { schedule_utils::get_winter_course_time_table().times };
-            let data = ScheduleResponse { weeks: cached_data, time_table, season };
-            let resp = ApiResponse::success(200, data, "OK (from cache)");
-            return HttpResponse::Ok().json(resp);
+        if let Some(data) = cached_schedule_response(&ucode) {
+            return HttpResponse::Ok().json(ApiResponse::success(200, data.compacted(compact), "OK (from cache)"));
         }
     }
 
-    let client = match create_http_client().await {
-        Ok(c) => c,
-        Err(e) => {
-            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(500, serde_json::json!({}), format!("Create client failed: {}", e));
-            return HttpResponse::InternalServerError().json(resp);
-        }
+    let fetched = match schedule_service::fetch_schedule(&ucode, &config, parallel, |_| {}).await {
+        Ok(f) => f,
+        Err(e) => return fetch_error_response(&e),
     };
 
-    // 1) 获取用户信息
-    let user = match auth::get_user_info(&ucode, &client, &config).await {
-        Ok(u) => u,
-        Err(e) => {
-            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(500, serde_json::json!({}), format!("Get user info failed: {}", e));
-            return HttpResponse::InternalServerError().json(resp);
-        }
-    };
+    let data = complete_schedule_fetch(&config, db.get_ref(), &ucode, fetched, api_key_id, start_time, cache_enabled).await;
+    HttpResponse::Ok().json(ApiResponse::success(200, data.compacted(compact), "OK"))
+}
 
-    // 2) 获取学年，定位当前学期
-    let years = match crate::parser::schedule::get_school_year(&user.access_token, &client, &config).await {
-        Ok(v) => v,
-        Err(e) => {
-            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(500, serde_json::json!({}), format!("Get school year failed: {}", e));
-            return HttpResponse::InternalServerError().json(resp);
-        }
-    };
+/// 确定时令与时间表（东八区当前日期）
+fn current_season_and_time_table() -> (String, Vec<(String, String)>) {
+    let today = schedule_utils::east8_today_ymd();
+    let season = schedule_utils::season_for_date(&today);
+    let time_table = schedule_utils::get_season_course_time_table(&season).times;
+    (season, time_table)
+}
 
-    let current = years.iter().rfind(|y| y.is_current_semester);
-    let Some(current_semester) = current else {
-        let resp: ApiResponse<serde_json::Value> = ApiResponse::error(404, serde_json::json!({}), "No current semester found");
-        return HttpResponse::NotFound().json(resp);
-    };
+/// 构造课表响应，附带今天的作息与每周按日期确定的作息
+fn schedule_response(
+    weeks: HashMap<u32, Vec<DayCourse>>,
+    semester_weeks: &[WeekInfo],
+    changes: Option<ScheduleDiff>,
+) -> ScheduleResponse {
+    let (season, time_table) = current_season_and_time_table();
+    ScheduleResponse {
+        time_table,
+        season,
+        time_tables: calendar::season_time_tables(),
+        week_seasons: calendar::week_seasons(semester_weeks),
+        conflicts: course_service::detect_conflicts(&weeks),
+        locations: course_service::collect_locations(&weeks),
+        weeks,
+        changes,
+        debug: None,
+        compact: None,
+    }
+}
 
-    // 3) 获取学期周信息
-    let semester_str = current_semester.semester.to_string();
-    let semester_weeks: Vec<WeekInfo> = match crate::parser::schedule::get_semester(
-        &user.access_token,
-        &current_semester.school_year,
-        &semester_str,
-        &client,
-        &config,
-    ).await {
-        Ok(v) => v,
-        Err(e) => {
-            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(500, serde_json::json!({}), format!("Get semester failed: {}", e));
-            return HttpResponse::InternalServerError().json(resp);
+/// 命中缓存时直接构造响应
+fn cached_schedule_response(ucode: &str) -> Option<ScheduleResponse> {
+    let cached_data = cache::get_cached_schedule(ucode)?;
+    tracing::info!("Cache hit for ucode hash: {}", crate::utils::crypto::hash_ucode(ucode));
+    let semester_weeks = cache::get_cached_semester_weeks(ucode).unwrap_or_default();
+    Some(schedule_response(cached_data, &semester_weeks, None))
+}
+
+/// 获取失败时的统一响应
+pub(crate) fn fetch_error_response(e: &ScheduleFetchError) -> HttpResponse {
+    let status = e.status_code();
+    let resp: ApiResponse<serde_json::Value> = ApiResponse::error(status, serde_json::json!({}), e.to_string());
+    match status {
+        404 => HttpResponse::NotFound().json(resp),
+        _ => HttpResponse::InternalServerError().json(resp),
+    }
+}
+
+/// 读取课表数据和学期周列表：优先使用缓存，未命中时从学校服务器获取（同样会写缓存、记日志）
+pub(crate) async fn load_schedule(
+    config: &AppConfig,
+    db: &DatabaseConnection,
+    ucode: &str,
+    api_key_id: Option<i32>,
+) -> Result<(HashMap<u32, Vec<DayCourse>>, Vec<WeekInfo>), ScheduleFetchError> {
+    let start_time = Instant::now();
+    let cache_enabled = toggles::get_toggles().schedule_cache;
+    if cache_enabled {
+        if let (Some(weeks), Some(semester_weeks)) =
+            (cache::get_cached_schedule(ucode), cache::get_cached_semester_weeks(ucode))
+        {
+            return Ok((weeks, semester_weeks));
         }
-    };
+    }
 
-    // 4) 获取所有周课程（支持并行/顺序）
-    let parallel = payload.parallel.unwrap_or(true);
-    let mut weeks_map: HashMap<u32, Vec<DayCourse>> = match course_service::get_all_courses(
-        &user.access_token,
-        &user.student_id,
-        &semester_weeks,
-        &client,
-        &config,
-        parallel,
-    ).await {
-        Ok(m) => m,
+    let fetched = schedule_service::fetch_schedule(ucode, config, true, |_| {}).await?;
+    let semester_weeks = fetched.semester_weeks.clone();
+    let data = complete_schedule_fetch(config, db, ucode, fetched, api_key_id, start_time, cache_enabled).await;
+    Ok((data.weeks, semester_weeks))
+}
+
+/// 获取成功后的公共处理：变动检测、Webhook 推送、缓存、快照与日志统计
+async fn complete_schedule_fetch(
+    config: &AppConfig,
+    db: &DatabaseConnection,
+    ucode: &str,
+    fetched: FetchedSchedule,
+    api_key_id: Option<i32>,
+    start_time: Instant,
+    cache_enabled: bool,
+) -> ScheduleResponse {
+    let FetchedSchedule { user, semester: current_semester, semester_weeks, weeks: weeks_map, diagnostics } = fetched;
+
+    // 与上一次的课表比较，有变动时异步推送给用户注册的 Webhook
+    let ucode_hash = crate::utils::crypto::hash_ucode(ucode);
+    let school_year = current_semester.school_year.clone();
+    let semester = current_semester.semester;
+    let changes = match diff::record_and_diff(db, &ucode_hash, &school_year, semester, &weeks_map).await {
+        Ok(changes) => changes,
         Err(e) => {
-            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(500, serde_json::json!({}), format!("Get all courses failed: {}", e));
-            return HttpResponse::InternalServerError().json(resp);
+            tracing::error!("Failed to detect schedule changes: {}", e);
+            None
         }
     };
+    if let Some(changes) = changes.as_ref().filter(|c| !c.is_empty()) {
+        tracing::info!("Detected {} schedule changes", changes.changes.len());
+        let db_clone = db.clone();
+        let changes = changes.clone();
+        let server_secret = config.session_secret.clone();
+        let ucode_hash = ucode_hash.clone();
+        tokio::spawn(async move {
+            if let Err(e) = webhook::deliver_schedule_change(&db_clone, &ucode_hash, &changes, &server_secret).await {
+                tracing::error!("Failed to deliver webhooks: {}", e);
+            }
+        });
+    }
 
-    // 5) 对每周的课程按 weekday 排序
-    for day_courses in weeks_map.values_mut() {
-        day_courses.sort_by_key(|dc| dc.weekday);
+    let mut response = schedule_response(weeks_map.clone(), &semester_weeks, changes);
+    response.debug = ScheduleDebug::from_diagnostics(diagnostics);
+    let parse_failures = response.debug.as_ref().map_or(0, ScheduleDebug::failure_count);
+    if parse_failures > 0 {
+        tracing::warn!("{} course records failed to parse", parse_failures);
     }
 
     // 设置缓存
-    cache::set_cached_schedule(&ucode, weeks_map.clone());
+    if cache_enabled {
+        cache::set_cached_schedule(ucode, weeks_map.clone(), semester_weeks);
+    }
 
     // 记录统计和日志
     let duration_ms = start_time.elapsed().as_millis() as i64;
 
     // 异步记录日志和统计（不阻塞响应）
-    let db_clone = db.get_ref().clone();
-    let ucode_clone = ucode.clone();
+    let db_clone = db.clone();
+    let ucode_clone = ucode.to_string();
     let token_clone = user.access_token.clone();
     let student_id_clone = user.student_id.clone();
+    let snapshot_weeks = weeks_map;
 
     tokio::spawn(async move {
-        if let Err(e) = stats::log_request(&db_clone, &token_clone, &student_id_clone, duration_ms).await {
+        if let Err(e) = snapshot::record_snapshot(&db_clone, &ucode_hash, &school_year, semester, &snapshot_weeks).await {
+            tracing::error!("Failed to record schedule snapshot: {}", e);
+        }
+        if let Err(e) = stats::log_request(&db_clone, &token_clone, &student_id_clone, duration_ms, api_key_id).await {
             tracing::error!("Failed to log request: {}", e);
         }
         if let Err(e) = stats::update_stats(&db_clone, &ucode_clone).await {
             tracing::error!("Failed to update stats: {}", e);
         }
+        if let Err(e) = stats::record_parse_failures(&db_clone, parse_failures).await {
+            tracing::error!("Failed to record parse failures: {}", e);
+        }
     });
 
-    // 确定时令与时间表（东八区当前日期）
-    let today = schedule_utils::east8_today_ymd();
-    let season = if schedule_utils::is_summer_schedule(&today) { "summer".to_string() } else { "winter".to_string() };
-    let time_table = if season == "summer" { schedule_utils::get_summer_course_time_table().times } else { schedule_utils::get_winter_course_time_table().times };
+    response
+}
 
-    let data = ScheduleResponse {
-        weeks: weeks_map,
-        time_table,
-        season,
+/// NDJSON 响应的内容类型
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// 流式获取时的选项
+#[derive(Debug, Clone, Copy)]
+struct FetchOptions {
+    use_cache: bool,
+    cache_enabled: bool,
+    parallel: bool,
+    api_key_id: Option<i32>,
+}
+
+/// NDJSON 流中的一行（`Accept: application/x-ndjson` 时 `POST /api/schedule` 的响应）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+// 每个流只构造一次 Done，不值得为此装箱
+#[allow(clippy::large_enum_variant)]
+pub enum ScheduleStreamLine {
+    /// 一周的课程（获取时按完成顺序；命中缓存时当前周在前）
+    Week { week: WeekInfo, days: Vec<DayCourse> },
+    /// 全部完成（不再重复携带课表数据）
+    Done {
+        /// 成功获取的周数
+        weeks: usize,
+        season: String,
+        time_table: Vec<(String, String)>,
+        /// 各时间表（"winter" / "summer" / "version:<id>"）
+        time_tables: HashMap<String, Vec<(String, String)>>,
+        /// 每周每天使用的时间表键（周号 -> 周一到周日）
+        week_seasons: HashMap<u32, Vec<String>>,
+        /// 上课冲突
+        conflicts: Vec<LessonConflict>,
+        /// 解析后的上课地点（原始教室字符串 -> 地点）
+        locations: HashMap<String, Location>,
+        changes: Option<ScheduleDiff>,
+        /// 调试信息（有课程记录解析失败时）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        debug: Option<ScheduleDebug>,
+        /// 是否来自缓存
+        cached: bool,
+    },
+    /// 获取失败，之后连接关闭
+    Error { code: u16, message: String },
+}
+
+/// 构造一行 NDJSON
+fn ndjson_line(line: &ScheduleStreamLine) -> web::Bytes {
+    let mut json = serde_json::to_vec(line).unwrap_or_else(|_| b"{}".to_vec());
+    json.push(b'\n');
+    web::Bytes::from(json)
+}
+
+/// 查找周信息（找不到时只保留周号）
+fn week_info(semester_weeks: &[WeekInfo], week: u32) -> WeekInfo {
+    semester_weeks
+        .iter()
+        .find(|w| w.week == week)
+        .cloned()
+        .unwrap_or(WeekInfo { week, start_time: String::new(), end_time: String::new() })
+}
+
+/// 以 NDJSON 逐周返回课表，每获取完一周立即写出一行
+fn stream_schedule_ndjson(
+    config: AppConfig,
+    db: DatabaseConnection,
+    ucode: String,
+    options: FetchOptions,
+    start_time: Instant,
+) -> HttpResponse {
+    let (tx, rx) = mpsc::unbounded::<web::Bytes>();
+
+    let cached = if options.use_cache { cache::get_cached_schedule(&ucode) } else { None };
+    if let Some(weeks) = cached {
+        let semester_weeks = cache::get_cached_semester_weeks(&ucode).unwrap_or_default();
+        let today = schedule_utils::east8_today_ymd();
+        let current = semester_weeks
+            .iter()
+            .find(|w| w.start_time.as_str() <= today.as_str() && today.as_str() <= w.end_time.as_str())
+            .map(|w| w.week);
+
+        // 当前周排在最前，其余按周号顺序
+        let mut numbers: Vec<u32> = weeks.keys().copied().collect();
+        numbers.sort_by_key(|w| (Some(*w) != current, *w));
+        for number in numbers {
+            let line = ScheduleStreamLine::Week { week: week_info(&semester_weeks, number), days: weeks[&number].clone() };
+            let _ = tx.unbounded_send(ndjson_line(&line));
+        }
+
+        let data = schedule_response(weeks, &semester_weeks, None);
+        let done = ScheduleStreamLine::Done {
+            weeks: data.weeks.len(),
+            season: data.season,
+            time_table: data.time_table,
+            time_tables: data.time_tables,
+            week_seasons: data.week_seasons,
+            conflicts: data.conflicts,
+            locations: data.locations,
+            changes: None,
+            debug: None,
+            cached: true,
+        };
+        let _ = tx.unbounded_send(ndjson_line(&done));
+    } else {
+        // 客户端中途断开时发送会失败，这里忽略错误继续获取，结果仍会写入缓存
+        tokio::spawn(async move {
+            let progress = tx.clone();
+            let mut semester_weeks: Vec<WeekInfo> = Vec::new();
+            let fetched = schedule_service::fetch_schedule(&ucode, &config, options.parallel, move |event| match event {
+                ScheduleEvent::SemesterWeeks { weeks } => semester_weeks = weeks,
+                ScheduleEvent::Week { week, days, .. } => {
+                    let line = ScheduleStreamLine::Week { week: week_info(&semester_weeks, week), days };
+                    let _ = progress.unbounded_send(ndjson_line(&line));
+                }
+                _ => {}
+            })
+            .await;
+
+            let line = match fetched {
+                Ok(fetched) => {
+                    let data = complete_schedule_fetch(
+                        &config,
+                        &db,
+                        &ucode,
+                        fetched,
+                        options.api_key_id,
+                        start_time,
+                        options.cache_enabled,
+                    )
+                    .await;
+                    ScheduleStreamLine::Done {
+                        weeks: data.weeks.len(),
+                        season: data.season,
+                        time_table: data.time_table,
+                        time_tables: data.time_tables,
+                        week_seasons: data.week_seasons,
+                        conflicts: data.conflicts,
+                        locations: data.locations,
+                        changes: data.changes,
+                        debug: data.debug,
+                        cached: false,
+                    }
+                }
+                Err(e) => ScheduleStreamLine::Error { code: e.status_code(), message: e.to_string() },
+            };
+            let _ = tx.unbounded_send(ndjson_line(&line));
+        });
+    }
+
+    HttpResponse::Ok()
+        .content_type(NDJSON_CONTENT_TYPE)
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(rx.map(Ok::<_, actix_web::Error>))
+}
+
+/// 构造一条 SSE 消息
+fn sse_message<T: Serialize>(event: &str, data: &T) -> web::Bytes {
+    let json = serde_json::to_string(data).unwrap_or_else(|_| "{}".to_string());
+    web::Bytes::from(format!("event: {}\ndata: {}\n\n", event, json))
+}
+
+/// 以 SSE 推送进度的方式获取学生课表
+///
+/// 请求参数与 `POST /api/schedule` 相同，响应为 `text/event-stream`，依次推送：
+///
+/// - `authenticated`：已通过学校接口认证
+/// - `school_year_found`：已定位当前学期
+/// - `semester_weeks`：学期周列表
+/// - `week`：每获取完一周推送一次（按完成顺序，附带已完成/总周数）
+/// - `result`：最终结果，内容与 `POST /api/schedule` 的响应相同
+/// - `error`：获取失败，内容为统一错误响应，之后连接关闭
+///
+/// 命中缓存时直接推送 `result`。
+#[utoipa::path(
+    post,
+    path = "/api/schedule/stream",
+    tag = "Schedule",
+    request_body = ScheduleRequest,
+    params(("Authorization" = Option<String>, Header, description = "Bearer 会话令牌（可替代请求体中的 ucode）")),
+    responses(
+        (status = 200, description = "SSE 事件流（text/event-stream），事件数据见 ScheduleEvent 与 ScheduleApiResponse", body = crate::services::schedule::ScheduleEvent),
+        (status = 400, description = "请求参数错误"),
+        (status = 401, description = "会话令牌无效或已过期"),
+        (status = 429, description = "请求过于频繁，见 Retry-After 响应头")
+    )
+)]
+pub async fn post_schedule_stream(
+    config: web::Data<AppConfig>,
+    db: web::Data<DatabaseConnection>,
+    api_key: Option<web::ReqData<ApiKeyIdentity>>,
+    session: AuthSession,
+    payload: web::Json<ScheduleRequest>,
+) -> impl Responder {
+    let start_time = Instant::now();
+    let Some(ucode) = session.ucode_or(payload.ucode.clone()) else {
+        let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), "Missing ucode or session token");
+        return HttpResponse::BadRequest().json(resp);
     };
-    let resp = ApiResponse::success(200, data, "OK");
-    HttpResponse::Ok().json(resp)
+
+    let (tx, rx) = mpsc::unbounded::<web::Bytes>();
+
+    let cache_enabled = toggles::get_toggles().schedule_cache;
+    let use_cache = payload.use_cache.unwrap_or(true) && cache_enabled;
+    let cached = if use_cache { cached_schedule_response(&ucode) } else { None };
+
+    if let Some(data) = cached {
+        let _ = tx.unbounded_send(sse_message("result", &ApiResponse::success(200, data, "OK (from cache)")));
+    } else {
+        let parallel = payload.parallel.unwrap_or(true);
+        let api_key_id = api_key.map(|k| k.id);
+        let config = config.get_ref().clone();
+        let db = db.get_ref().clone();
+
+        // 客户端中途断开时发送会失败，这里忽略错误继续获取，结果仍会写入缓存
+        tokio::spawn(async move {
+            let progress = tx.clone();
+            let fetched = schedule_service::fetch_schedule(&ucode, &config, parallel, move |event| {
+                let _ = progress.unbounded_send(sse_message(event.name(), &event));
+            })
+            .await;
+
+            let message = match fetched {
+                Ok(fetched) => {
+                    let data = complete_schedule_fetch(&config, &db, &ucode, fetched, api_key_id, start_time, cache_enabled).await;
+                    sse_message("result", &ApiResponse::success(200, data, "OK"))
+                }
+                Err(e) => {
+                    let resp: ApiResponse<serde_json::Value> = ApiResponse::error(e.status_code(), serde_json::json!({}), e.to_string());
+                    sse_message("error", &resp)
+                }
+            };
+            let _ = tx.unbounded_send(message);
+        });
+    }
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(rx.map(Ok::<_, actix_web::Error>))
 }
 
 /// 获取用户基本信息
@@ -248,20 +645,24 @@ pub async fn post_schedule(
     path = "/api/auth/userinfo",
     tag = "Auth",
     params(
-        ("ucode" = String, Query, description = "学生 UCode", example = "ABC123DEF456GHI789JKL012MNO345PQR678")
+        ("Authorization" = Option<String>, Header, description = "Bearer 会话令牌（推荐，避免 UCode 出现在访问日志中）"),
+        ("ucode" = Option<String>, Query, description = "学生 UCode（已弃用，未携带会话令牌时使用）", example = "ABC123DEF456GHI789JKL012MNO345PQR678")
     ),
     responses(
         (status = 200, description = "成功获取用户信息", body = UserInfoApiResponse),
-        (status = 400, description = "缺少 ucode 参数"),
+        (status = 400, description = "缺少 ucode 参数或会话令牌"),
+        (status = 401, description = "会话令牌无效或已过期"),
+        (status = 429, description = "请求过于频繁，见 Retry-After 响应头"),
         (status = 500, description = "服务器内部错误")
     )
 )]
 pub async fn get_user_info_endpoint(
     config: web::Data<AppConfig>,
+    session: AuthSession,
     query: web::Query<HashMap<String, String>>,
 ) -> impl Responder {
-    let Some(ucode) = query.get("ucode").cloned() else {
-        let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), "Missing ucode");
+    let Some(ucode) = session.ucode_or(query.get("ucode").cloned()) else {
+        let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), "Missing ucode or session token");
         return HttpResponse::BadRequest().json(resp);
     };
 
@@ -345,20 +746,24 @@ pub struct PingApiResponse {
     path = "/api/schedule/meta",
     tag = "Schedule",
     params(
-        ("ucode" = String, Query, description = "学生 UCode", example = "ABC123DEF456GHI789JKL012MNO345PQR678")
+        ("Authorization" = Option<String>, Header, description = "Bearer 会话令牌（推荐，避免 UCode 出现在访问日志中）"),
+        ("ucode" = Option<String>, Query, description = "学生 UCode（已弃用，未携带会话令牌时使用）", example = "ABC123DEF456GHI789JKL012MNO345PQR678")
     ),
     responses(
         (status = 200, description = "成功获取元数据", body = ScheduleMetaApiResponse),
-        (status = 400, description = "缺少 ucode 参数"),
+        (status = 400, description = "缺少 ucode 参数或会话令牌"),
+        (status = 401, description = "会话令牌无效或已过期"),
+        (status = 429, description = "请求过于频繁，见 Retry-After 响应头"),
         (status = 500, description = "服务器内部错误")
     )
 )]
 pub async fn get_schedule_meta(
     config: web::Data<AppConfig>,
+    session: AuthSession,
     query: web::Query<HashMap<String, String>>,
 ) -> impl Responder {
-    let Some(ucode) = query.get("ucode").cloned() else {
-        let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), "Missing ucode");
+    let Some(ucode) = session.ucode_or(query.get("ucode").cloned()) else {
+        let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), "Missing ucode or session token");
         return HttpResponse::BadRequest().json(resp);
     };
 
@@ -412,6 +817,313 @@ pub async fn get_schedule_meta(
     HttpResponse::Ok().json(ApiResponse::success(200, meta, "OK"))
 }
 
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ScheduleHistory {
+    /// 历史版本列表（新的在前）
+    pub versions: Vec<SnapshotInfo>,
+    /// `as_of` 时刻生效的快照（未传 `as_of` 时为空）
+    pub snapshot: Option<ScheduleSnapshot>,
+}
+
+/// 课表历史 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ScheduleHistoryApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// 历史版本
+    pub data: ScheduleHistory,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 获取课表历史版本
+///
+/// 每次成功获取课表都会按内容哈希保存一个不可修改的快照，内容不变的重复获取只更新获取时间和次数。
+///
+/// **功能说明：**
+/// - 不传 `as_of` 时列出所有历史版本
+/// - 传入 `as_of` 时额外返回该时刻生效的版本（该时刻之前最后获取到的课表）
+/// - 只读取本地保存的快照，不请求学校服务器
+///
+/// **as_of 格式：**
+/// - 13 位毫秒时间戳，或 RFC 3339 时间（如 `2024-10-08T08:00:00+08:00`）
+/// - `YYYY-MM-DDTHH:MM:SS`（按东八区）
+/// - `YYYY-MM-DD`（按东八区当天结束时）
+#[utoipa::path(
+    get,
+    path = "/api/schedule/history",
+    tag = "Schedule",
+    params(
+        ("Authorization" = Option<String>, Header, description = "Bearer 会话令牌（推荐，避免 UCode 出现在访问日志中）"),
+        ("ucode" = Option<String>, Query, description = "学生 UCode（已弃用，未携带会话令牌时使用）"),
+        ("as_of" = Option<String>, Query, description = "查询该时刻生效的版本", example = "2024-10-08"),
+        ("limit" = Option<u64>, Query, description = "最多列出的版本数（默认 50，最大 500）", example = 50)
+    ),
+    responses(
+        (status = 200, description = "成功获取历史版本", body = ScheduleHistoryApiResponse),
+        (status = 400, description = "缺少 ucode 参数或会话令牌，或 as_of 格式错误"),
+        (status = 401, description = "会话令牌无效或已过期"),
+        (status = 404, description = "该时刻之前没有保存的课表"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn get_schedule_history(
+    db: web::Data<DatabaseConnection>,
+    session: AuthSession,
+    query: web::Query<HashMap<String, String>>,
+) -> impl Responder {
+    let ucode_hash = match &session.0 {
+        Some(context) => context.ucode_hash.clone(),
+        None => match session.ucode_or(query.get("ucode").cloned()) {
+            Some(ucode) => crate::utils::crypto::hash_ucode(&ucode),
+            None => {
+                let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), "Missing ucode or session token");
+                return HttpResponse::BadRequest().json(resp);
+            }
+        },
+    };
+
+    let as_of = match query.get("as_of") {
+        Some(raw) => match snapshot::parse_as_of(raw) {
+            Some(ts) => Some(ts),
+            None => {
+                let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), format!("Invalid as_of: {}", raw));
+                return HttpResponse::BadRequest().json(resp);
+            }
+        },
+        None => None,
+    };
+    let limit = query.get("limit").and_then(|v| v.parse::<u64>().ok()).unwrap_or(50).clamp(1, 500);
+
+    let versions = match snapshot::list_snapshots(db.get_ref(), &ucode_hash, limit).await {
+        Ok(v) => v,
+        Err(e) => {
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(500, serde_json::json!({}), format!("Load history failed: {}", e));
+            return HttpResponse::InternalServerError().json(resp);
+        }
+    };
+
+    let snapshot = match as_of {
+        Some(ts) => match snapshot::get_snapshot_as_of(db.get_ref(), &ucode_hash, ts).await {
+            Ok(Some(s)) => Some(s),
+            Ok(None) => {
+                let resp: ApiResponse<serde_json::Value> = ApiResponse::error(404, serde_json::json!({}), "No schedule snapshot before as_of");
+                return HttpResponse::NotFound().json(resp);
+            }
+            Err(e) => {
+                let resp: ApiResponse<serde_json::Value> = ApiResponse::error(500, serde_json::json!({}), format!("Load snapshot failed: {}", e));
+                return HttpResponse::InternalServerError().json(resp);
+            }
+        },
+        None => None,
+    };
+
+    HttpResponse::Ok().json(ApiResponse::success(200, ScheduleHistory { versions, snapshot }, "OK"))
+}
+
+/// 上课冲突 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ConflictsApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// 上课冲突（按周次、星期、节次排序；没有冲突时为空数组）
+    pub data: Vec<LessonConflict>,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 检测上课冲突
+///
+/// 选修、重修的同学可能被排到同一时间的两门课，这里提前把冲突找出来。
+///
+/// **冲突类型：**
+/// - same_slot: 同一周、同一天、同一节次出现了两门不同的课（包括上游把其中一门挪到别的节次、但记录仍声明同一节的情况）
+/// - span_overlap: 某门课按 `continuous_course` 延伸的节次与另一门课重叠
+#[utoipa::path(
+    get,
+    path = "/api/schedule/conflicts",
+    tag = "Schedule",
+    params(
+        ("Authorization" = Option<String>, Header, description = "Bearer 会话令牌"),
+        ("ucode" = Option<String>, Query, description = "学生 UCode（已弃用，未携带会话令牌时使用）")
+    ),
+    responses(
+        (status = 200, description = "成功检测上课冲突", body = ConflictsApiResponse),
+        (status = 400, description = "缺少 ucode 参数或会话令牌"),
+        (status = 401, description = "会话令牌无效或已过期"),
+        (status = 429, description = "请求过于频繁，见 Retry-After 响应头"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn get_conflicts(
+    config: web::Data<AppConfig>,
+    db: web::Data<DatabaseConnection>,
+    api_key: Option<web::ReqData<ApiKeyIdentity>>,
+    session: AuthSession,
+    query: web::Query<HashMap<String, String>>,
+) -> impl Responder {
+    let Some(ucode) = session.ucode_or(query.get("ucode").cloned()) else {
+        let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), "Missing ucode or session token");
+        return HttpResponse::BadRequest().json(resp);
+    };
+
+    match load_schedule(&config, db.get_ref(), &ucode, api_key.map(|k| k.id)).await {
+        Ok((weeks, _)) => {
+            let conflicts = course_service::detect_conflicts(&weeks);
+            HttpResponse::Ok().json(ApiResponse::success(200, conflicts, "OK"))
+        }
+        Err(e) => fetch_error_response(&e),
+    }
+}
+
+/// 课时统计 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ScheduleSummaryApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// 课时统计
+    pub data: ScheduleSummary,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 获取各课程的课时与进度统计
+///
+/// 按展开后的上课列表统计（节假日停上的课不计入，调休日的课计入），课时按每节课时长换算成小时。
+///
+/// **返回数据（每门课）：**
+/// - total_sessions / total_lessons / total_hours: 本学期总次数、总节数、总课时
+/// - completed_sessions: 截至当前已上完的次数
+/// - remaining_sessions / remaining_hours: 之后还要上的次数与课时（传入 `until` 时只统计到该日期，例如考试前）
+/// - first_date / last_date / next_date: 第一次、最后一次、下一次上课日期
+/// - weekly_hours: 每周课时
+#[utoipa::path(
+    get,
+    path = "/api/schedule/summary",
+    tag = "Schedule",
+    params(
+        ("until" = Option<String>, Query, description = "剩余次数统计截止日期（YYYY-MM-DD，含），例如考试日期", example = "2024-12-20"),
+        ("Authorization" = Option<String>, Header, description = "Bearer 会话令牌"),
+        ("ucode" = Option<String>, Query, description = "学生 UCode（已弃用，未携带会话令牌时使用）")
+    ),
+    responses(
+        (status = 200, description = "成功获取课时统计", body = ScheduleSummaryApiResponse),
+        (status = 400, description = "until 格式错误，或缺少 ucode / 会话令牌"),
+        (status = 401, description = "会话令牌无效或已过期"),
+        (status = 429, description = "请求过于频繁，见 Retry-After 响应头"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn get_summary(
+    config: web::Data<AppConfig>,
+    db: web::Data<DatabaseConnection>,
+    api_key: Option<web::ReqData<ApiKeyIdentity>>,
+    session: AuthSession,
+    query: web::Query<HashMap<String, String>>,
+) -> impl Responder {
+    let Some(ucode) = session.ucode_or(query.get("ucode").cloned()) else {
+        let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), "Missing ucode or session token");
+        return HttpResponse::BadRequest().json(resp);
+    };
+    let until = match query.get("until") {
+        Some(raw) => match calendar::parse_date(raw) {
+            Some(date) => Some(date),
+            None => {
+                let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), "Invalid until, expected YYYY-MM-DD");
+                return HttpResponse::BadRequest().json(resp);
+            }
+        },
+        None => None,
+    };
+
+    match load_schedule(&config, db.get_ref(), &ucode, api_key.map(|k| k.id)).await {
+        Ok((weeks, semester_weeks)) => {
+            let now = chrono::Utc::now().with_timezone(&schedule_utils::tz_east8());
+            let summary = summary::summarize(&weeks, &semester_weeks, now, until);
+            HttpResponse::Ok().json(ApiResponse::success(200, summary, "OK"))
+        }
+        Err(e) => fetch_error_response(&e),
+    }
+}
+
+/// 教师列表 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TeachersApiResponse {
+    /// HTTP 状态码
+    #[schema(example = 200)]
+    pub code: u16,
+    /// 响应状态
+    #[schema(example = "success")]
+    pub status: String,
+    /// 教师列表（按教师名排序）
+    pub data: Vec<TeacherSchedule>,
+    /// 响应消息
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// 按教师查看课表
+///
+/// 列出本学期每位任课教师的课程、每周上课时间和教室，方便找教师答疑。
+///
+/// **返回数据（每位教师）：**
+/// - courses: 该教师教的课，以及每门课每周固定的上课时间（星期、节次、教室、上课周次）
+/// - classrooms: 该教师上课的教室
+/// - next: 下一次见到该教师的课（学期内已没有时为空）
+#[utoipa::path(
+    get,
+    path = "/api/schedule/teachers",
+    tag = "Schedule",
+    params(
+        ("name" = Option<String>, Query, description = "只返回名字包含该字符串的教师", example = "张"),
+        ("Authorization" = Option<String>, Header, description = "Bearer 会话令牌"),
+        ("ucode" = Option<String>, Query, description = "学生 UCode（已弃用，未携带会话令牌时使用）")
+    ),
+    responses(
+        (status = 200, description = "成功获取教师列表", body = TeachersApiResponse),
+        (status = 400, description = "缺少 ucode 参数或会话令牌"),
+        (status = 401, description = "会话令牌无效或已过期"),
+        (status = 429, description = "请求过于频繁，见 Retry-After 响应头"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn get_teachers(
+    config: web::Data<AppConfig>,
+    db: web::Data<DatabaseConnection>,
+    api_key: Option<web::ReqData<ApiKeyIdentity>>,
+    session: AuthSession,
+    query: web::Query<HashMap<String, String>>,
+) -> impl Responder {
+    let Some(ucode) = session.ucode_or(query.get("ucode").cloned()) else {
+        let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), "Missing ucode or session token");
+        return HttpResponse::BadRequest().json(resp);
+    };
+
+    match load_schedule(&config, db.get_ref(), &ucode, api_key.map(|k| k.id)).await {
+        Ok((weeks, semester_weeks)) => {
+            let now = chrono::Utc::now().with_timezone(&schedule_utils::tz_east8());
+            let teachers = teacher::teacher_index(&weeks, &semester_weeks, now, query.get("name").map(String::as_str));
+            HttpResponse::Ok().json(ApiResponse::success(200, teachers, "OK"))
+        }
+        Err(e) => fetch_error_response(&e),
+    }
+}
+
 /// 健康检查
 ///
 /// 用于检查 API 服务是否正常运行。
@@ -489,12 +1201,18 @@ pub struct TimeTableResponse {
     pub time_table: Vec<(String, String)>,
     /// 明确的时令字段："winter" | "summer"
     pub season: String,
+    /// 当天生效的时间表版本（有版本时 `time_table` 取自该版本）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<TimeTableVersion>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SeasonResponse {
     /// 当前时令："winter" | "summer"
     pub season: String,
+    /// 当天生效的时间表版本（考试周、节前调整等临时作息）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<TimeTableVersion>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -537,8 +1255,9 @@ pub async fn get_season(query: web::Query<std::collections::HashMap<String, Stri
         return HttpResponse::BadRequest().json(resp);
     }
 
-    let season = if schedule_utils::is_summer_schedule(&date_str) { "summer".to_string() } else { "winter".to_string() };
-    HttpResponse::Ok().json(ApiResponse::success(200, SeasonResponse { season }, "OK"))
+    let season = schedule_utils::season_for_date(&date_str);
+    let version = schedule_utils::time_table_version_for_date(&date_str);
+    HttpResponse::Ok().json(ApiResponse::success(200, SeasonResponse { season, version }, "OK"))
 }
 
 
@@ -557,12 +1276,15 @@ pub struct TimeTableApiResponse {
     pub message: String,
 }
 
-/// 获取课程时间表（支持 auto | winter | summer）
+/// 获取课程时间表（当天有生效的时间表版本时返回该版本，否则按时令）
 #[utoipa::path(
     get,
     path = "/api/time-table",
     tag = "Schedule",
-    params(("date" = String, Query, description = "YYYY-MM-DD；不传则按东八区当天", example = "2025-06-01")),
+    params(
+        ("date" = String, Query, description = "YYYY-MM-DD；不传则按东八区当天", example = "2025-06-01"),
+        ("campus" = Option<String>, Query, description = "校区；该校区没有单独配置时使用默认时间表")
+    ),
     responses(
         (status = 200, description = "成功获取时间表", body = TimeTableApiResponse),
         (status = 400, description = "请求参数错误")
@@ -582,8 +1304,10 @@ pub async fn get_time_table(
         return HttpResponse::BadRequest().json(resp);
     }
 
-    let season = if schedule_utils::is_summer_schedule(&date_str) { "summer".to_string() } else { "winter".to_string() };
-    let time_table = if season == "summer" { schedule_utils::get_summer_course_time_table().times } else { schedule_utils::get_winter_course_time_table().times };
+    let season = schedule_utils::season_for_date(&date_str);
+    let campus = query.get("campus").map(|c| c.trim()).filter(|c| !c.is_empty());
+    let time_table = schedule_utils::get_campus_course_time_table(&date_str, campus).times;
+    let version = schedule_utils::time_table_version_for_date(&date_str);
 
-    HttpResponse::Ok().json(ApiResponse::success(200, TimeTableResponse { time_table, season }, "OK"))
+    HttpResponse::Ok().json(ApiResponse::success(200, TimeTableResponse { time_table, season, version }, "OK"))
 }
@@ -0,0 +1,162 @@
+use actix_web::{web, HttpResponse, Responder};
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::controller::auth::AuthSession;
+use crate::services::webhook::{self, CreatedWebhook, WebhookError, WebhookInfo};
+use crate::utils::{config::AppConfig, response::ApiResponse};
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreateWebhookRequest {
+    /// 推送地址（http/https，不允许内网地址）
+    #[schema(example = "https://example.com/hooks/schedule")]
+    pub url: String,
+}
+
+/// 新建 Webhook API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreatedWebhookApiResponse {
+    #[schema(example = 200)]
+    pub code: u16,
+    #[schema(example = "success")]
+    pub status: String,
+    pub data: CreatedWebhook,
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+/// Webhook 列表 API 响应（具体类型，用于 OpenAPI 文档）
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WebhookListApiResponse {
+    #[schema(example = 200)]
+    pub code: u16,
+    #[schema(example = "success")]
+    pub status: String,
+    pub data: Vec<WebhookInfo>,
+    #[schema(example = "OK")]
+    pub message: String,
+}
+
+fn unauthorized() -> HttpResponse {
+    let resp: ApiResponse<serde_json::Value> = ApiResponse::error(401, serde_json::json!({}), "Missing session token");
+    HttpResponse::Unauthorized().json(resp)
+}
+
+fn webhook_error(e: WebhookError) -> HttpResponse {
+    match e {
+        WebhookError::InvalidUrl(_) | WebhookError::LimitReached => {
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(400, serde_json::json!({}), e.to_string());
+            HttpResponse::BadRequest().json(resp)
+        }
+        WebhookError::NotFound => {
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(404, serde_json::json!({}), e.to_string());
+            HttpResponse::NotFound().json(resp)
+        }
+        WebhookError::SecretNotPersistent => {
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(503, serde_json::json!({}), e.to_string());
+            HttpResponse::ServiceUnavailable().json(resp)
+        }
+        _ => {
+            let resp: ApiResponse<serde_json::Value> = ApiResponse::error(500, serde_json::json!({}), format!("Webhook operation failed: {}", e));
+            HttpResponse::InternalServerError().json(resp)
+        }
+    }
+}
+
+/// 注册课表变动 Webhook
+///
+/// 之后每次获取课表时若检测到课表变动，会向该地址 POST 一份 JSON 格式的变动明细。
+///
+/// **签名校验：**
+/// - 请求头 `X-FJCPC-Timestamp` 为秒级时间戳
+/// - 请求头 `X-FJCPC-Signature` 为 `sha256=<hex>`，
+///   即以创建时返回的 `secret` 为密钥，对 `时间戳 + "." + 请求体` 计算的 HMAC-SHA256
+#[utoipa::path(
+    post,
+    path = "/api/webhooks",
+    tag = "Webhook",
+    request_body = CreateWebhookRequest,
+    params(("Authorization" = String, Header, description = "Bearer 会话令牌")),
+    responses(
+        (status = 200, description = "Webhook 已注册（secret 只返回这一次）", body = CreatedWebhookApiResponse),
+        (status = 400, description = "地址无效或数量已达上限"),
+        (status = 401, description = "缺少或无效的会话令牌"),
+        (status = 500, description = "服务器内部错误"),
+        (status = 503, description = "服务端未配置 SESSION_SECRET，暂不能注册 Webhook")
+    )
+)]
+pub async fn create_webhook(
+    config: web::Data<AppConfig>,
+    db: web::Data<DatabaseConnection>,
+    session: AuthSession,
+    payload: web::Json<CreateWebhookRequest>,
+) -> impl Responder {
+    let Some(context) = session.0 else {
+        return unauthorized();
+    };
+    // 签名密钥用会话密钥加密保存，随机生成的会话密钥重启后就无法解密
+    if !config.session_secret_persistent {
+        return webhook_error(WebhookError::SecretNotPersistent);
+    }
+
+    match webhook::register_webhook(db.get_ref(), &context.ucode_hash, &payload.url, &config.session_secret).await {
+        Ok(created) => HttpResponse::Ok().json(ApiResponse::success(200, created, "OK")),
+        Err(e) => webhook_error(e),
+    }
+}
+
+/// 列出当前用户的 Webhook
+#[utoipa::path(
+    get,
+    path = "/api/webhooks",
+    tag = "Webhook",
+    params(("Authorization" = String, Header, description = "Bearer 会话令牌")),
+    responses(
+        (status = 200, description = "成功获取 Webhook 列表", body = WebhookListApiResponse),
+        (status = 401, description = "缺少或无效的会话令牌"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn list_webhooks(db: web::Data<DatabaseConnection>, session: AuthSession) -> impl Responder {
+    let Some(context) = session.0 else {
+        return unauthorized();
+    };
+
+    match webhook::list_webhooks(db.get_ref(), &context.ucode_hash).await {
+        Ok(hooks) => HttpResponse::Ok().json(ApiResponse::success(200, hooks, "OK")),
+        Err(e) => webhook_error(e.into()),
+    }
+}
+
+/// 删除 Webhook
+#[utoipa::path(
+    delete,
+    path = "/api/webhooks/{id}",
+    tag = "Webhook",
+    params(
+        ("id" = i32, Path, description = "Webhook ID"),
+        ("Authorization" = String, Header, description = "Bearer 会话令牌")
+    ),
+    responses(
+        (status = 200, description = "已删除"),
+        (status = 401, description = "缺少或无效的会话令牌"),
+        (status = 404, description = "Webhook 不存在"),
+        (status = 500, description = "服务器内部错误")
+    )
+)]
+pub async fn delete_webhook(
+    db: web::Data<DatabaseConnection>,
+    session: AuthSession,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let Some(context) = session.0 else {
+        return unauthorized();
+    };
+
+    let id = path.into_inner();
+    match webhook::delete_webhook(db.get_ref(), &context.ucode_hash, id).await {
+        Ok(()) => HttpResponse::Ok().json(ApiResponse::success(200, serde_json::json!({ "deleted": id }), "OK")),
+        Err(e) => webhook_error(e),
+    }
+}
@@ -27,6 +27,7 @@ async fn create_tables(db: &DatabaseConnection) -> Result<(), DbErr> {
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             total_requests INTEGER NOT NULL DEFAULT 0,
             unique_users INTEGER NOT NULL DEFAULT 0,
+            parse_failures INTEGER NOT NULL DEFAULT 0,
             last_updated_at INTEGER NOT NULL,
             created_at INTEGER NOT NULL
         )
@@ -46,7 +47,8 @@ async fn create_tables(db: &DatabaseConnection) -> Result<(), DbErr> {
             duration_ms INTEGER NOT NULL,
             token TEXT NOT NULL,
             encrypted_student_id TEXT NOT NULL,
-            created_at INTEGER NOT NULL
+            created_at INTEGER NOT NULL,
+            api_key_id INTEGER
         )
     "#;
     
@@ -73,6 +75,176 @@ async fn create_tables(db: &DatabaseConnection) -> Result<(), DbErr> {
     ))
     .await?;
     
+    // 创建 API Key 表（第三方接入方使用）
+    let create_api_keys_table = r#"
+        CREATE TABLE IF NOT EXISTS api_keys (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            key_hash TEXT NOT NULL UNIQUE,
+            key_prefix TEXT NOT NULL,
+            scopes TEXT NOT NULL,
+            quota_per_day INTEGER,
+            usage_day TEXT NOT NULL DEFAULT '',
+            usage_count INTEGER NOT NULL DEFAULT 0,
+            expires_at INTEGER,
+            revoked INTEGER NOT NULL DEFAULT 0,
+            last_used_at INTEGER,
+            created_at INTEGER NOT NULL
+        )
+    "#;
+
+    db.execute(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        create_api_keys_table.to_string(),
+    ))
+    .await?;
+
+    // 创建会话表（UCode 加密保存在服务端，客户端只持有会话令牌）
+    let create_sessions_table = r#"
+        CREATE TABLE IF NOT EXISTS sessions (
+            id TEXT PRIMARY KEY,
+            ucode_hash TEXT NOT NULL,
+            encrypted_ucode TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            expires_at INTEGER NOT NULL,
+            revoked INTEGER NOT NULL DEFAULT 0,
+            last_used_at INTEGER NOT NULL
+        )
+    "#;
+
+    db.execute(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        create_sessions_table.to_string(),
+    ))
+    .await?;
+
+    // 创建课表状态表（每个用户最近一次成功获取的课表，用于检测课表变动）
+    let create_schedule_states_table = r#"
+        CREATE TABLE IF NOT EXISTS schedule_states (
+            ucode_hash TEXT PRIMARY KEY,
+            school_year TEXT NOT NULL DEFAULT '',
+            semester INTEGER NOT NULL DEFAULT 0,
+            weeks_json TEXT NOT NULL,
+            updated_at INTEGER NOT NULL
+        )
+    "#;
+
+    db.execute(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        create_schedule_states_table.to_string(),
+    ))
+    .await?;
+
+    // 创建课表快照表（每次获取到的课表按内容哈希保存，内容不可修改）
+    let create_schedule_snapshots_table = r#"
+        CREATE TABLE IF NOT EXISTS schedule_snapshots (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            ucode_hash TEXT NOT NULL,
+            content_hash TEXT NOT NULL,
+            school_year TEXT NOT NULL,
+            semester INTEGER NOT NULL,
+            weeks_json TEXT NOT NULL,
+            first_seen_at INTEGER NOT NULL,
+            last_seen_at INTEGER NOT NULL,
+            fetch_count INTEGER NOT NULL DEFAULT 1
+        )
+    "#;
+
+    db.execute(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        create_schedule_snapshots_table.to_string(),
+    ))
+    .await?;
+
+    db.execute(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        "CREATE INDEX IF NOT EXISTS idx_schedule_snapshots_user ON schedule_snapshots (ucode_hash, first_seen_at)".to_string(),
+    ))
+    .await?;
+
+    // 创建 Webhook 表（课表变动时推送通知）
+    let create_webhooks_table = r#"
+        CREATE TABLE IF NOT EXISTS webhooks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            ucode_hash TEXT NOT NULL,
+            url TEXT NOT NULL,
+            secret TEXT NOT NULL,
+            active INTEGER NOT NULL DEFAULT 1,
+            created_at INTEGER NOT NULL,
+            last_delivery_at INTEGER,
+            last_status INTEGER,
+            failure_count INTEGER NOT NULL DEFAULT 0
+        )
+    "#;
+
+    db.execute(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        create_webhooks_table.to_string(),
+    ))
+    .await?;
+
+    // 创建时间表版本表（带生效日期的临时作息）
+    let create_time_table_versions_table = r#"
+        CREATE TABLE IF NOT EXISTS time_table_versions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            effective_from TEXT NOT NULL,
+            effective_to TEXT NOT NULL,
+            times_json TEXT NOT NULL,
+            created_at INTEGER NOT NULL
+        )
+    "#;
+
+    db.execute(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        create_time_table_versions_table.to_string(),
+    ))
+    .await?;
+
+    // 创建上游结构指纹表
+    let create_schema_fingerprints_table = r#"
+        CREATE TABLE IF NOT EXISTS schema_fingerprints (
+            endpoint TEXT PRIMARY KEY,
+            fingerprint_json TEXT NOT NULL,
+            updated_at INTEGER NOT NULL
+        )
+    "#;
+
+    db.execute(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        create_schema_fingerprints_table.to_string(),
+    ))
+    .await?;
+
+    // 创建上游结构漂移事件表
+    let create_schema_drift_events_table = r#"
+        CREATE TABLE IF NOT EXISTS schema_drift_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            endpoint TEXT NOT NULL,
+            fingerprint TEXT NOT NULL,
+            changes_json TEXT NOT NULL,
+            sample_json TEXT NOT NULL,
+            occurrences INTEGER NOT NULL DEFAULT 1,
+            first_seen_at INTEGER NOT NULL,
+            last_seen_at INTEGER NOT NULL,
+            UNIQUE(endpoint, fingerprint)
+        )
+    "#;
+
+    db.execute(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        create_schema_drift_events_table.to_string(),
+    ))
+    .await?;
+
+    // 旧库的日志表补充 API Key 字段
+    add_column_if_missing(db, "request_logs", "api_key_id", "INTEGER").await?;
+    // 旧库的统计表补充课程解析失败计数
+    add_column_if_missing(db, "access_stats", "parse_failures", "INTEGER NOT NULL DEFAULT 0").await?;
+    // 旧库的课表状态表补充所属学期（学期切换时不与上学期的课表比较）
+    add_column_if_missing(db, "schedule_states", "school_year", "TEXT NOT NULL DEFAULT ''").await?;
+    add_column_if_missing(db, "schedule_states", "semester", "INTEGER NOT NULL DEFAULT 0").await?;
+
     // 初始化统计表（如果为空）
     let init_stats = r#"
         INSERT OR IGNORE INTO access_stats (id, total_requests, unique_users, last_updated_at, created_at)
@@ -88,3 +260,34 @@ async fn create_tables(db: &DatabaseConnection) -> Result<(), DbErr> {
     Ok(())
 }
 
+/// 给已存在的表补充字段（CREATE TABLE IF NOT EXISTS 不会修改旧表结构）
+async fn add_column_if_missing(
+    db: &DatabaseConnection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<(), DbErr> {
+    use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+
+    let rows = db
+        .query_all(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            format!("PRAGMA table_info({})", table),
+        ))
+        .await?;
+
+    let exists = rows
+        .iter()
+        .any(|row| row.try_get::<String>("", "name").map(|name| name == column).unwrap_or(false));
+
+    if !exists {
+        info!("Adding column {}.{}", table, column);
+        db.execute(Statement::from_string(
+            DatabaseBackend::Sqlite,
+            format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+        ))
+        .await?;
+    }
+
+    Ok(())
+}
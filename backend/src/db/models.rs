@@ -9,6 +9,7 @@ pub mod access_stats {
         pub id: i32,
         pub total_requests: i32,
         pub unique_users: i32,
+        pub parse_failures: i64,
         pub last_updated_at: i64,
         pub created_at: i64,
     }
@@ -33,6 +34,8 @@ pub mod request_logs {
         pub token: String,
         pub encrypted_student_id: String,
         pub created_at: i64,
+        /// 发起请求的 API Key（未使用 API Key 时为空）
+        pub api_key_id: Option<i32>,
     }
 
     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -63,3 +66,216 @@ pub mod user_visits {
     impl ActiveModelBehavior for ActiveModel {}
 }
 
+pub mod api_keys {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+    #[sea_orm(table_name = "api_keys")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i32,
+        pub name: String,
+        /// API Key 的 SHA-256 哈希（明文只在创建时返回一次）
+        #[sea_orm(unique)]
+        pub key_hash: String,
+        /// 明文前缀，便于人工辨认
+        pub key_prefix: String,
+        /// 授权范围，逗号分隔（schedule,meta,stats）
+        pub scopes: String,
+        /// 每日配额（为空表示不限）
+        pub quota_per_day: Option<i32>,
+        /// 配额计数对应的日期（东八区 YYYY-MM-DD）
+        pub usage_day: String,
+        /// 当日已用次数
+        pub usage_count: i32,
+        /// 过期时间（毫秒时间戳，为空表示永不过期）
+        pub expires_at: Option<i64>,
+        pub revoked: bool,
+        pub last_used_at: Option<i64>,
+        pub created_at: i64,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod sessions {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+    #[sea_orm(table_name = "sessions")]
+    pub struct Model {
+        /// 会话 ID（随机生成，写入令牌的 sid）
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub id: String,
+        pub ucode_hash: String,
+        /// 使用 SESSION_SECRET 加密的 UCode
+        pub encrypted_ucode: String,
+        pub created_at: i64,
+        pub expires_at: i64,
+        pub revoked: bool,
+        pub last_used_at: i64,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod schedule_states {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+    #[sea_orm(table_name = "schedule_states")]
+    pub struct Model {
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub ucode_hash: String,
+        pub school_year: String,
+        pub semester: i32,
+        /// 最近一次获取到的周课表（JSON，周号 -> 每天课程）
+        pub weeks_json: String,
+        pub updated_at: i64,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod webhooks {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+    #[sea_orm(table_name = "webhooks")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i32,
+        pub ucode_hash: String,
+        pub url: String,
+        /// 签名密钥（只在创建时返回给用户）
+        pub secret: String,
+        pub active: bool,
+        pub created_at: i64,
+        pub last_delivery_at: Option<i64>,
+        /// 最近一次推送的 HTTP 状态码（请求失败时为 0）
+        pub last_status: Option<i32>,
+        /// 连续失败次数
+        pub failure_count: i32,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod schedule_snapshots {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+    #[sea_orm(table_name = "schedule_snapshots")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i32,
+        pub ucode_hash: String,
+        /// 课表内容的 SHA-256（对按周号排序后的 JSON 计算）
+        pub content_hash: String,
+        pub school_year: String,
+        pub semester: i32,
+        /// 课表内容（JSON，周号 -> 每天课程），写入后不再修改
+        pub weeks_json: String,
+        /// 首次获取到该版本的时间
+        pub first_seen_at: i64,
+        /// 最近一次获取到相同内容的时间
+        pub last_seen_at: i64,
+        /// 获取到相同内容的次数
+        pub fetch_count: i32,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod time_table_versions {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+    #[sea_orm(table_name = "time_table_versions")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i32,
+        pub name: String,
+        /// 生效起始日期（YYYY-MM-DD，含）
+        pub effective_from: String,
+        /// 生效结束日期（YYYY-MM-DD，含）
+        pub effective_to: String,
+        /// 每节课的起止时间（JSON 数组）
+        pub times_json: String,
+        pub created_at: i64,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod schema_fingerprints {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+    #[sea_orm(table_name = "schema_fingerprints")]
+    pub struct Model {
+        /// 接口名（如 getXn）
+        #[sea_orm(primary_key, auto_increment = false)]
+        pub endpoint: String,
+        /// 字段路径 -> 类型（JSON 对象）
+        pub fingerprint_json: String,
+        pub updated_at: i64,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod schema_drift_events {
+    use sea_orm::entity::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+    #[sea_orm(table_name = "schema_drift_events")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i32,
+        pub endpoint: String,
+        /// 新结构指纹的哈希
+        pub fingerprint: String,
+        /// 结构变化（JSON 数组）
+        pub changes_json: String,
+        /// 脱敏后的响应样本（JSON）
+        pub sample_json: String,
+        pub occurrences: i64,
+        pub first_seen_at: i64,
+        pub last_seen_at: i64,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
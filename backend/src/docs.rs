@@ -1,33 +1,115 @@
 use utoipa::OpenApi;
 
 use crate::controller;
-use crate::parser::schedule::{CourseInfo, CourseSlot, DayCourse, SchoolYear, WeekInfo};
+use crate::parser::schedule::{CourseInfo, CourseParseDiagnostic, CourseSlot, DayCourse, SchoolYear, WeekInfo};
 use crate::parser::auth::UserInfo;
-use crate::services::stats::StatsResponse;
+use crate::services::session::LoginResponse;
+use crate::services::calendar::{BuildingGroup, CurrentWeek, DaySchedule, NextLesson, TimedLesson, WalkWarning, WeekSchedule, WeekStatus};
+use crate::services::compact::{CompactSchedule, CompactSlot, DayShape, SlotRef, WeekException};
+use crate::services::course::{ConflictKind, LessonConflict};
+use crate::services::occurrence::LessonOccurrence;
+use crate::services::summary::{CourseSummary, ScheduleSummary};
+use crate::services::teacher::{TeacherCourse, TeacherMeeting, TeacherSchedule};
+use crate::services::schedule::ScheduleEvent;
+use crate::services::search::{MatchKind, SearchField, SearchHit, SearchMatch, SearchResults};
+use crate::services::snapshot::{ScheduleSnapshot, SnapshotInfo};
+use crate::services::diff::{LessonChange, LessonSlot, ScheduleDiff};
+use crate::services::webhook::{CreatedWebhook, WebhookInfo, WebhookPayload};
+use crate::services::api_key::{ApiKeyInfo, ApiScope, CreatedApiKey};
+use crate::services::stats::{RequestLogPage, RequestLogSummary, StatsResponse};
+use crate::utils::cache::CacheEntrySummary;
+use crate::utils::http::DnsCacheInfo;
+use crate::services::time_table::NewTimeTableVersion;
+use crate::services::drift::SchemaDriftOverview;
+use crate::utils::drift::{FieldChange, FieldChangeKind, SchemaDriftEvent};
+use crate::utils::location::{Location, LocationConfig, LocationRule, WalkingTime};
+use crate::utils::holiday::{Holiday, HolidayCalendar, MakeupDay, SpecialDay, SpecialDayKind};
+use crate::utils::schedule::{CampusTimeTables, SeasonRange, TimeTableConfig, TimeTableVersion};
+use crate::utils::toggles::{RuntimeToggles, RuntimeTogglesPatch};
 
 #[derive(OpenApi)]
 #[openapi(
     info(
         title = "FJCPC Course Parser Backend",
         version = "1.0.0",
-        description = "福建船政交通职业学院课表解析后端 API\n\n**使用说明：**\n1. 所有接口都需要提供学生的 UCode\n2. UCode 可以从学校移动端应用中获取\n3. 课表数据来源于学校官方 API",
+        description = "福建船政交通职业学院课表解析后端 API\n\n**使用说明：**\n1. 先调用 /api/auth/login 用 UCode 换取会话令牌，之后在 Authorization: Bearer 头中携带令牌（旧的直接传 UCode 方式仍兼容）\n2. UCode 可以从学校移动端应用中获取\n3. 课表数据来源于学校官方 API\n4. 服务端开启 API_KEY_REQUIRED 时，第三方调用需在 X-API-Key 头中携带 API Key",
     ),
     paths(
+        controller::auth::login,
+        controller::auth::logout,
         controller::schedule::post_schedule,
+        controller::schedule::post_schedule_stream,
         controller::schedule::get_user_info_endpoint,
         controller::schedule::get_schedule_meta,
+        controller::schedule::get_schedule_history,
+        controller::calendar::get_day,
+        controller::calendar::get_today,
+        controller::calendar::get_tomorrow,
+        controller::calendar::get_next,
+        controller::calendar::get_current_week,
+        controller::calendar::get_week,
+        controller::calendar::get_occurrences,
+        controller::calendar::search_schedule,
+        controller::schedule::get_conflicts,
+        controller::schedule::get_summary,
+        controller::schedule::get_teachers,
         controller::schedule::get_time_table,
         controller::schedule::get_season,
         controller::schedule::get_stats,
         controller::schedule::ping,
+        controller::webhook::create_webhook,
+        controller::webhook::list_webhooks,
+        controller::webhook::delete_webhook,
+        controller::admin::get_cache,
+        controller::admin::clear_cache,
+        controller::admin::evict_expired_cache,
+        controller::admin::purge_user_cache,
+        controller::admin::get_dns_cache,
+        controller::admin::flush_dns_cache,
+        controller::admin::reset_rate_limits,
+        controller::admin::run_simulator,
+        controller::admin::get_logs,
+        controller::admin::get_admin_stats,
+        controller::admin::get_toggles,
+        controller::admin::update_toggles,
+        controller::admin::get_time_tables,
+        controller::admin::reload_time_tables,
+        controller::admin::list_time_table_versions,
+        controller::admin::create_time_table_version,
+        controller::admin::delete_time_table_version,
+        controller::admin::get_holidays,
+        controller::admin::reload_holidays,
+        controller::admin::get_locations,
+        controller::admin::reload_locations,
+        controller::admin::get_schema_drift,
+        controller::admin::reset_schema_baseline,
+        controller::admin::create_api_key,
+        controller::admin::list_api_keys,
+        controller::admin::revoke_api_key,
     ),
     components(schemas(
+        controller::auth::LoginRequest,
+        controller::auth::LoginApiResponse,
+        LoginResponse,
         controller::schedule::ScheduleRequest,
         controller::schedule::ScheduleResponse,
+        controller::schedule::ScheduleDebug,
         controller::schedule::ScheduleApiResponse,
+        controller::schedule::ScheduleStreamLine,
         controller::schedule::ScheduleMeta,
         controller::schedule::ScheduleMetaApiResponse,
         controller::schedule::UserInfoApiResponse,
+        controller::schedule::ScheduleHistory,
+        controller::calendar::DayScheduleApiResponse,
+        controller::calendar::NextLessonApiResponse,
+        controller::calendar::CurrentWeekApiResponse,
+        controller::calendar::WeekScheduleApiResponse,
+        controller::calendar::OccurrencesApiResponse,
+        controller::calendar::SearchApiResponse,
+        controller::schedule::ConflictsApiResponse,
+        controller::schedule::ScheduleSummaryApiResponse,
+        controller::schedule::TeachersApiResponse,
+        controller::schedule::ScheduleHistoryApiResponse,
         controller::schedule::StatsApiResponse,
         controller::schedule::PingData,
         controller::schedule::PingApiResponse,
@@ -36,20 +118,112 @@ use crate::services::stats::StatsResponse;
         controller::schedule::SeasonApiResponse,
         controller::schedule::TimeTableResponse,
         controller::schedule::TimeTableApiResponse,
+        // Webhook
+        controller::webhook::CreateWebhookRequest,
+        controller::webhook::CreatedWebhookApiResponse,
+        controller::webhook::WebhookListApiResponse,
+        // Admin
+        controller::admin::CacheOverview,
+        controller::admin::CacheOverviewApiResponse,
+        controller::admin::EvictionResult,
+        controller::admin::EvictionApiResponse,
+        controller::admin::PurgeCacheRequest,
+        controller::admin::DnsCacheApiResponse,
+        controller::admin::SimulatorTestRequest,
+        controller::admin::SimulatorTestResult,
+        controller::admin::SimulatorTestApiResponse,
+        controller::admin::RequestLogsApiResponse,
+        controller::admin::AdminStats,
+        controller::admin::AdminStatsApiResponse,
+        controller::admin::TogglesApiResponse,
+        controller::admin::TimeTableConfigApiResponse,
+        controller::admin::TimeTableVersionApiResponse,
+        controller::admin::TimeTableVersionListApiResponse,
+        controller::admin::HolidayCalendarApiResponse,
+        controller::admin::LocationConfigApiResponse,
+        controller::admin::SchemaDriftApiResponse,
+        controller::admin::CreateApiKeyRequest,
+        controller::admin::CreatedApiKeyApiResponse,
+        controller::admin::ApiKeyListApiResponse,
         // Core models
         SchoolYear,
         WeekInfo,
         DayCourse,
         CourseSlot,
         CourseInfo,
+        CourseParseDiagnostic,
+        CompactSchedule,
+        DayShape,
+        CompactSlot,
+        SlotRef,
+        WeekException,
         UserInfo,
         StatsResponse,
+        RequestLogPage,
+        RequestLogSummary,
+        CacheEntrySummary,
+        DnsCacheInfo,
+        RuntimeToggles,
+        RuntimeTogglesPatch,
+        TimeTableConfig,
+        SeasonRange,
+        CampusTimeTables,
+        TimeTableVersion,
+        NewTimeTableVersion,
+        HolidayCalendar,
+        Holiday,
+        MakeupDay,
+        SpecialDay,
+        SpecialDayKind,
+        Location,
+        LocationConfig,
+        LocationRule,
+        WalkingTime,
+        BuildingGroup,
+        WalkWarning,
+        SchemaDriftOverview,
+        SchemaDriftEvent,
+        FieldChange,
+        FieldChangeKind,
+        ApiScope,
+        ApiKeyInfo,
+        CreatedApiKey,
+        ScheduleEvent,
+        TimedLesson,
+        DaySchedule,
+        NextLesson,
+        WeekStatus,
+        LessonOccurrence,
+        SearchResults,
+        SearchHit,
+        SearchMatch,
+        SearchField,
+        MatchKind,
+        LessonConflict,
+        ConflictKind,
+        ScheduleSummary,
+        CourseSummary,
+        TeacherSchedule,
+        TeacherCourse,
+        TeacherMeeting,
+        WeekSchedule,
+        CurrentWeek,
+        SnapshotInfo,
+        ScheduleSnapshot,
+        LessonSlot,
+        LessonChange,
+        ScheduleDiff,
+        WebhookInfo,
+        CreatedWebhook,
+        WebhookPayload,
     )),
     tags(
         (name = "Schedule", description = "课表相关接口 - 提供课表查询、学年学期信息等功能"),
-        (name = "Auth", description = "认证相关接口 - 提供用户信息查询功能"),
+        (name = "Auth", description = "认证相关接口 - 登录换取会话令牌、注销与用户信息查询"),
         (name = "Stats", description = "统计接口 - 提供访问统计信息"),
-        (name = "Test", description = "测试接口 - 用于开发和调试")
+        (name = "Webhook", description = "课表变动通知 - 注册 Webhook，课表变动时推送签名的变动明细"),
+        (name = "Test", description = "测试接口 - 用于开发和调试"),
+        (name = "Admin", description = "管理接口 - 缓存、DNS、日志与运行时开关，需要 X-Admin-Token 请求头")
     )
 )]
 pub struct ApiDoc;
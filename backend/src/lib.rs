@@ -1,6 +1,8 @@
 // src/lib.rs
 pub mod controller;
 pub mod db;
+pub mod docs;
+pub mod middleware;
 pub mod parser;
 pub mod routes;
 pub mod services;
@@ -1,19 +1,14 @@
-mod controller;
-mod db;
-mod parser;
-mod routes;
-mod services;
-mod utils;
-mod docs;
-
 use actix_cors::Cors;
 use actix_web::{middleware, web, App, HttpServer, HttpResponse};
+use actix_web::middleware::from_fn;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 use tracing::info;
 
-use utils::config::AppConfig;
-use utils::log;
+use backend::utils::config::AppConfig;
+use backend::utils::{holiday, location, log, schedule};
+use backend::middleware::{admin::require_admin_token, api_key::require_api_key};
+use backend::{db, docs, routes, services};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -28,12 +23,52 @@ async fn main() -> std::io::Result<()> {
     info!("Loaded config: {:?}", config);
     info!("Running in {:?} mode", config.app_env);
 
+    // 加载作息时间配置（配置不合法时拒绝启动）
+    let time_tables = schedule::reload_time_tables(config.time_table_path.as_deref())
+        .expect("Failed to load time table config");
+    info!(
+        "Time tables loaded from {}: seasons {:?}",
+        config.time_table_path.as_deref().unwrap_or("<builtin>"),
+        time_tables.time_tables.keys().collect::<Vec<_>>()
+    );
+
+    // 加载节假日与调休配置（配置不合法时拒绝启动）
+    let holidays = holiday::reload_holidays(config.holiday_path.as_deref())
+        .expect("Failed to load holiday config");
+    info!(
+        "Holidays loaded from {}: {} holidays, {} makeup days",
+        config.holiday_path.as_deref().unwrap_or("<builtin>"),
+        holidays.holidays.len(),
+        holidays.makeup_days.len()
+    );
+
+    // 加载教室解析规则（配置不合法时拒绝启动）
+    let locations = location::reload_locations(config.location_path.as_deref())
+        .expect("Failed to load location config");
+    info!(
+        "Location rules loaded from {}: {} rules",
+        config.location_path.as_deref().unwrap_or("<builtin>"),
+        locations.config().rules.len()
+    );
+
     // 初始化数据库
     let db = db::connection::init_db()
         .await
         .expect("Failed to initialize database");
     info!("Database initialized");
 
+    // 加载数据库中的时间表版本
+    let versions = services::time_table::refresh_versions(&db)
+        .await
+        .expect("Failed to load time table versions");
+    info!("Loaded {} time table versions", versions);
+
+    // 加载上游结构指纹，启动漂移事件持久化
+    let fingerprints = services::drift::start(&db)
+        .await
+        .expect("Failed to load upstream schema fingerprints");
+    info!("Loaded {} upstream schema fingerprints", fingerprints);
+
     // 启动服务器
     let bind_address = format!("127.0.0.1:{}", config.port);
     info!("Starting server at http://{}", bind_address);
@@ -57,8 +92,15 @@ async fn main() -> std::io::Result<()> {
                     .content_type("application/json")
                     .body(body)
             }))
+            // 管理接口需先于 /api 注册，否则会被 /api 作用域先匹配
+            .service(
+                web::scope("/api/admin")
+                    .wrap(from_fn(require_admin_token))
+                    .configure(routes::admin::configure)
+            )
             .service(
                 web::scope("/api")
+                    .wrap(from_fn(require_api_key))
                     .configure(routes::schedule::configure)
             );
 
@@ -66,7 +108,7 @@ async fn main() -> std::io::Result<()> {
         if is_dev {
             app = app.service(
                 SwaggerUi::new("/docs/{_:.*}")
-                    .url("/api-doc/openapi.json", crate::docs::ApiDoc::openapi())
+                    .url("/api-doc/openapi.json", docs::ApiDoc::openapi())
             );
         }
 
@@ -0,0 +1,48 @@
+use actix_web::{
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    middleware::Next,
+    web, Error, HttpResponse,
+};
+
+use crate::utils::{config::AppConfig, crypto::constant_time_eq, response::ApiResponse};
+
+/// 管理令牌所在的请求头
+pub const ADMIN_TOKEN_HEADER: &str = "X-Admin-Token";
+
+/// 管理接口鉴权中间件
+///
+/// 未配置 `ADMIN_TOKEN` 时整个管理接口禁用（403），
+/// 请求头中的令牌不匹配时返回 401
+pub async fn require_admin_token(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    let expected = req
+        .app_data::<web::Data<AppConfig>>()
+        .and_then(|config| config.admin_token.clone());
+
+    let Some(expected) = expected else {
+        let resp: ApiResponse<serde_json::Value> =
+            ApiResponse::error(403, serde_json::json!({}), "Admin API is disabled");
+        return Ok(req
+            .into_response(HttpResponse::Forbidden().json(resp))
+            .map_into_right_body());
+    };
+
+    let provided = req
+        .headers()
+        .get(ADMIN_TOKEN_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+
+    if !constant_time_eq(provided.as_bytes(), expected.as_bytes()) {
+        let resp: ApiResponse<serde_json::Value> =
+            ApiResponse::error(401, serde_json::json!({}), "Invalid admin token");
+        return Ok(req
+            .into_response(HttpResponse::Unauthorized().json(resp))
+            .map_into_right_body());
+    }
+
+    next.call(req).await.map(ServiceResponse::map_into_left_body)
+}
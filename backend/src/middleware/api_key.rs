@@ -0,0 +1,103 @@
+use actix_web::{
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::{header, StatusCode},
+    middleware::Next,
+    web, Error, HttpMessage, HttpResponse,
+};
+use sea_orm::DatabaseConnection;
+
+use crate::services::api_key::{self, ApiKeyError, ApiScope};
+use crate::utils::{config::AppConfig, response::ApiResponse};
+
+/// API Key 所在的请求头
+pub const API_KEY_HEADER: &str = "X-API-Key";
+
+/// 接口路径对应的授权范围（返回 None 的接口无需 API Key）
+fn required_scope(path: &str) -> Option<ApiScope> {
+    let path = path.strip_prefix("/api").unwrap_or(path);
+    if path.starts_with("/schedule/meta") {
+        Some(ApiScope::Meta)
+    } else if path.starts_with("/schedule") || path.starts_with("/auth") || path.starts_with("/webhooks") {
+        Some(ApiScope::Schedule)
+    } else if path.starts_with("/stats") {
+        Some(ApiScope::Stats)
+    } else {
+        None
+    }
+}
+
+/// API Key 中间件
+///
+/// 配置 `API_KEY_REQUIRED=true` 时，带授权范围的接口必须携带有效的 API Key；
+/// 未强制要求时，携带了 API Key 的请求同样会校验并记入该 Key 的用量。
+/// 校验通过后把 [`api_key::ApiKeyIdentity`] 放进请求扩展，供日志归属使用
+pub async fn require_api_key(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    let Some(scope) = required_scope(req.path()) else {
+        return next.call(req).await.map(ServiceResponse::map_into_left_body);
+    };
+
+    let required = req
+        .app_data::<web::Data<AppConfig>>()
+        .map(|config| config.api_key_required)
+        .unwrap_or(false);
+
+    let provided = req
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+
+    let Some(key) = provided else {
+        if required {
+            return Ok(reject(req, ApiKeyError::Missing));
+        }
+        return next.call(req).await.map(ServiceResponse::map_into_left_body);
+    };
+
+    let Some(db) = req.app_data::<web::Data<DatabaseConnection>>().cloned() else {
+        return next.call(req).await.map(ServiceResponse::map_into_left_body);
+    };
+
+    match api_key::authorize(db.get_ref(), &key, scope).await {
+        Ok(identity) => {
+            req.extensions_mut().insert(identity);
+            next.call(req).await.map(ServiceResponse::map_into_left_body)
+        }
+        Err(e) => Ok(reject(req, e)),
+    }
+}
+
+/// 把校验错误转换为统一响应
+fn reject<B>(req: ServiceRequest, err: ApiKeyError) -> ServiceResponse<EitherBody<B>> {
+    let status = match &err {
+        ApiKeyError::Missing | ApiKeyError::Invalid | ApiKeyError::Revoked | ApiKeyError::Expired => {
+            StatusCode::UNAUTHORIZED
+        }
+        ApiKeyError::ScopeDenied(_) => StatusCode::FORBIDDEN,
+        ApiKeyError::QuotaExceeded { .. } => StatusCode::TOO_MANY_REQUESTS,
+        ApiKeyError::Db(e) => {
+            tracing::error!("Failed to check API key: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    };
+
+    let mut builder = HttpResponse::build(status);
+    let data = if let ApiKeyError::QuotaExceeded { retry_after } = err {
+        builder.insert_header((header::RETRY_AFTER, retry_after.to_string()));
+        serde_json::json!({ "scope": "api_key", "retry_after": retry_after })
+    } else {
+        serde_json::json!({})
+    };
+
+    let message = match &err {
+        ApiKeyError::Db(_) => "Failed to check API key".to_string(),
+        other => other.to_string(),
+    };
+    let resp = ApiResponse::error(status.as_u16(), data, message);
+    req.into_response(builder.json(resp)).map_into_right_body()
+}
@@ -0,0 +1,29 @@
+use actix_web::{
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    middleware::Next,
+    Error, HttpResponse,
+};
+
+use crate::utils::{response::ApiResponse, toggles};
+
+/// 维护模式中间件
+///
+/// 维护模式开启时，被包裹的接口（需要请求学校服务器的那些）直接返回 503
+pub async fn reject_in_maintenance(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    if toggles::get_toggles().maintenance {
+        let resp: ApiResponse<serde_json::Value> = ApiResponse::error(
+            503,
+            serde_json::json!({}),
+            "Service is under maintenance, please try again later",
+        );
+        return Ok(req
+            .into_response(HttpResponse::ServiceUnavailable().json(resp))
+            .map_into_right_body());
+    }
+
+    next.call(req).await.map(ServiceResponse::map_into_left_body)
+}
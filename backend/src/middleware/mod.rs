@@ -0,0 +1,4 @@
+pub mod admin;
+pub mod api_key;
+pub mod maintenance;
+pub mod rate_limit;
@@ -0,0 +1,115 @@
+use actix_web::{
+    body::{EitherBody, MessageBody},
+    dev::{Payload, ServiceRequest, ServiceResponse},
+    http::{header, Method},
+    middleware::Next,
+    web, Error, HttpResponse,
+};
+use std::collections::HashMap;
+
+use crate::utils::{
+    config::AppConfig, crypto::hash_ucode, rate_limit, response::ApiResponse, toggles, token,
+};
+
+/// 限流中间件（按客户端 IP 和 UCode 哈希分别计数）
+///
+/// 只包裹需要请求学校服务器的接口。UCode 哈希优先取自会话令牌，
+/// 否则从查询参数或 JSON 请求体的 `ucode` 字段中读取，读取请求体后会原样放回，不影响后续处理。
+/// 超出配额时返回 429，并带上 `Retry-After` 头
+pub async fn limit_upstream_requests(
+    mut req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    let Some(config) = req.app_data::<web::Data<AppConfig>>().cloned() else {
+        return next.call(req).await.map(ServiceResponse::map_into_left_body);
+    };
+
+    if !toggles::get_toggles().rate_limit {
+        return next.call(req).await.map(ServiceResponse::map_into_left_body);
+    }
+
+    let window = config.rate_limit_window_secs;
+
+    let ip = client_ip(&req, config.trust_proxy_headers);
+    if let Err(retry_after) = rate_limit::check(&format!("ip:{}", ip), config.rate_limit_per_ip, window) {
+        tracing::warn!("Rate limit exceeded for ip {} (retry after {}s)", ip, retry_after);
+        return Ok(too_many_requests(req, "ip", retry_after));
+    }
+
+    if let Some(ucode_hash) = extract_ucode_hash(&mut req, &config.session_secret).await? {
+        let key = format!("ucode:{}", ucode_hash);
+        if let Err(retry_after) = rate_limit::check(&key, config.rate_limit_per_ucode, window) {
+            tracing::warn!("Rate limit exceeded for ucode hash (retry after {}s)", retry_after);
+            return Ok(too_many_requests(req, "ucode", retry_after));
+        }
+    }
+
+    next.call(req).await.map(ServiceResponse::map_into_left_body)
+}
+
+/// 识别客户端 IP（仅在信任代理时读取转发头）
+fn client_ip(req: &ServiceRequest, trust_proxy_headers: bool) -> String {
+    if trust_proxy_headers {
+        if let Some(ip) = req.connection_info().realip_remote_addr() {
+            return ip.to_string();
+        }
+    }
+    req.peer_addr()
+        .map(|addr| addr.ip().to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// 获取请求对应的 UCode 哈希（会话令牌只校验签名，不查库）
+async fn extract_ucode_hash(req: &mut ServiceRequest, session_secret: &str) -> Result<Option<String>, Error> {
+    let from_session = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(token::bearer_token)
+        .and_then(|t| token::verify(t, session_secret).ok())
+        .map(|claims| claims.sub);
+    if from_session.is_some() {
+        return Ok(from_session);
+    }
+
+    Ok(extract_ucode(req).await?.map(|ucode| hash_ucode(&ucode)))
+}
+
+/// 从查询参数或 JSON 请求体读取 UCode
+async fn extract_ucode(req: &mut ServiceRequest) -> Result<Option<String>, Error> {
+    let from_query = web::Query::<HashMap<String, String>>::from_query(req.query_string())
+        .ok()
+        .and_then(|q| q.get("ucode").cloned());
+    if let Some(ucode) = from_query {
+        return Ok(Some(ucode));
+    }
+
+    if req.method() != Method::POST {
+        return Ok(None);
+    }
+
+    // 读出请求体后需要放回去，否则处理函数拿不到 JSON
+    let body = req.extract::<web::Bytes>().await?;
+    let ucode = serde_json::from_slice::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|v| v.get("ucode").and_then(|u| u.as_str()).map(|s| s.trim().to_string()))
+        .filter(|s| !s.is_empty());
+    req.set_payload(Payload::from(body));
+
+    Ok(ucode)
+}
+
+/// 构造 429 响应
+fn too_many_requests<B>(req: ServiceRequest, scope: &str, retry_after: u64) -> ServiceResponse<EitherBody<B>> {
+    let resp = ApiResponse::error(
+        429,
+        serde_json::json!({ "scope": scope, "retry_after": retry_after }),
+        format!("Too many requests, retry after {} seconds", retry_after),
+    );
+    req.into_response(
+        HttpResponse::TooManyRequests()
+            .insert_header((header::RETRY_AFTER, retry_after.to_string()))
+            .json(resp),
+    )
+    .map_into_right_body()
+}
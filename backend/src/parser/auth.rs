@@ -6,7 +6,9 @@ use utoipa::ToSchema;
 use tracing::error;
 
 use crate::utils::config::AppConfig;
+use crate::utils::drift::{self, UpstreamEndpoint};
 use crate::utils::simulator;
+use crate::utils::toggles;
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct UserInfo {
@@ -88,8 +90,8 @@ pub async fn get_user_info(
     match try_get_user_info(&request_url, &ucode, &get_basic_auth(), client).await {
         Ok(user_info) => Ok(user_info),
         Err(e) => {
-            // 如果是 401 错误，尝试使用浏览器模拟获取 Basic Auth
-            if e.to_string().contains("401") {
+            // 如果是 401 错误，尝试使用浏览器模拟获取 Basic Auth（可通过运行时开关关闭）
+            if e.to_string().contains("401") && toggles::get_toggles().simulator_fallback {
                 error!("Error while fetching info: {}", e);
                 error!("Attempting retry with browser simulation...");
 
@@ -130,7 +132,9 @@ async fn try_get_user_info(
         ));
     }
 
-    let token_response: TokenResponse = response.json().await?;
+    let body: serde_json::Value = response.json().await?;
+    drift::observe(UpstreamEndpoint::OauthToken, &body);
+    let token_response: TokenResponse = serde_json::from_value(body)?;
 
     Ok(UserInfo {
         access_token: token_response.access_token,
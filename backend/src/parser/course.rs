@@ -1,12 +1,21 @@
 use anyhow::Result;
 use futures::stream::{FuturesUnordered, StreamExt};
 use reqwest::Client;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use tracing::{error, info};
 
-use super::schedule::{get_week_course, DayCourse, WeekInfo};
+use super::schedule::{get_week_course_checked, CourseParseDiagnostic, DayCourse, WeekInfo};
 use crate::utils::config::AppConfig;
 
+/// 批量获取的结果
+#[derive(Debug, Clone, Default)]
+pub struct FetchedCourses {
+    /// 课程数据，按周号聚合
+    pub weeks: HashMap<u32, Vec<DayCourse>>,
+    /// 解析失败的课程记录，按周号聚合（没有失败的周不出现）
+    pub diagnostics: BTreeMap<u32, Vec<CourseParseDiagnostic>>,
+}
+
 /// 批量获取所有课程
 pub async fn get_all_courses(
     user_token: &str,
@@ -15,6 +24,24 @@ pub async fn get_all_courses(
     client: &Client,
     config: &AppConfig,
 ) -> Result<HashMap<u32, Vec<DayCourse>>> {
+    let fetched = get_all_courses_with_progress(user_token, student_id, semester, client, config, |_, _| {}).await?;
+    Ok(fetched.weeks)
+}
+
+/// 批量获取所有课程，每获取完一周就回调一次 `on_week(周号, 该周课程)`
+///
+/// 回调按完成顺序触发（不保证周号顺序），请求失败的周不会回调
+pub async fn get_all_courses_with_progress<F>(
+    user_token: &str,
+    student_id: &str,
+    semester: &[WeekInfo],
+    client: &Client,
+    config: &AppConfig,
+    mut on_week: F,
+) -> Result<FetchedCourses>
+where
+    F: FnMut(u32, &[DayCourse]),
+{
     if semester.is_empty() {
         return Err(anyhow::anyhow!("Semester info must not be empty"));
     }
@@ -23,7 +50,7 @@ pub async fn get_all_courses(
         return Err(anyhow::anyhow!("Student ID or User token must be provided"));
     }
 
-    let mut courses_map = HashMap::new();
+    let mut fetched = FetchedCourses::default();
     let total_weeks = semester.len();
 
     // 创建并发任务
@@ -42,7 +69,7 @@ pub async fn get_all_courses(
                 student_id, user_token, week_clone.week, total_weeks
             );
 
-            match get_week_course(
+            match get_week_course_checked(
                 &user_token,
                 &student_id,
                 &week_clone.start_time,
@@ -72,10 +99,14 @@ pub async fn get_all_courses(
     // 收集所有结果
     while let Some(result) = futures.next().await {
         if let Some((week, data)) = result {
-            courses_map.insert(week, data);
+            on_week(week, &data.days);
+            if !data.diagnostics.is_empty() {
+                fetched.diagnostics.insert(week, data.diagnostics);
+            }
+            fetched.weeks.insert(week, data.days);
         }
     }
 
-    Ok(courses_map)
+    Ok(fetched)
 }
 
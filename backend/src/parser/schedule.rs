@@ -1,10 +1,12 @@
 use anyhow::Result;
+use chrono::{Duration, NaiveDate};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
-use tracing::error;
+use tracing::{error, warn};
 
 use crate::utils::config::AppConfig;
+use crate::utils::drift::{self, UpstreamEndpoint};
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SchoolYear {
@@ -37,7 +39,7 @@ pub struct WeekInfo {
     pub end_time: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct DayCourse {
     /// 星期几（1=周一，2=周二，...，7=周日）
     #[schema(example = 1)]
@@ -46,7 +48,7 @@ pub struct DayCourse {
     pub course: Vec<CourseSlot>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct CourseSlot {
     /// 课程节次（1-12，通常 1-4 为上午，5-8 为下午，9-12 为晚上）
     #[schema(example = 1)]
@@ -55,7 +57,7 @@ pub struct CourseSlot {
     pub course_info: Option<CourseInfo>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct CourseInfo {
     /// 课程名称
     #[schema(example = "高等数学")]
@@ -86,7 +88,100 @@ pub struct CourseInfo {
     pub code: String,
 }
 
+/// 课程字符串中各字段的名称（按 `|` 分隔的顺序）
+pub const COURSE_FIELDS: [&str; 9] = [
+    "name",
+    "classroom",
+    "class",
+    "teacher",
+    "course_number",
+    "weekday",
+    "color",
+    "continuous_course",
+    "code",
+];
+
+/// 课程字符串解析失败的原因
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum CourseParseError {
+    #[error("expected {expected} fields, found {found}")]
+    MissingFields { expected: usize, found: usize },
+    #[error("field {index} ({field}): invalid number '{value}'")]
+    InvalidNumber {
+        index: usize,
+        field: &'static str,
+        value: String,
+    },
+}
+
+impl CourseParseError {
+    /// 出错字段的下标（从 0 开始；字段数不足时为空）
+    pub fn field_index(&self) -> Option<usize> {
+        match self {
+            CourseParseError::MissingFields { .. } => None,
+            CourseParseError::InvalidNumber { index, .. } => Some(*index),
+        }
+    }
+
+    /// 出错字段的原始值
+    pub fn value(&self) -> Option<&str> {
+        match self {
+            CourseParseError::MissingFields { .. } => None,
+            CourseParseError::InvalidNumber { value, .. } => Some(value),
+        }
+    }
+}
+
+/// 一条解析失败的课程记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CourseParseDiagnostic {
+    /// 星期几（按上游返回的位置）
+    #[schema(example = 1)]
+    pub weekday: u32,
+    /// 节次（按上游返回的位置）
+    #[schema(example = 3)]
+    pub course_number: u32,
+    /// 出错字段的下标（从 0 开始；字段数不足时为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = 4)]
+    pub field_index: Option<usize>,
+    /// 出错字段的原始值
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "三")]
+    pub value: Option<String>,
+    /// 整条课程字符串
+    #[schema(example = "高等数学|A101|计算机2401|张老师|三|1|#FF5733|2|MATH101")]
+    pub raw: String,
+    /// 错误说明
+    #[schema(example = "field 4 (course_number): invalid number '三'")]
+    pub message: String,
+}
+
+/// 学期周列表无法使用的原因
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum SemesterParseError {
+    #[error("semester has no weeks")]
+    Empty,
+    #[error("none of the {rejected} semester week rows is valid")]
+    NoValidRows { rejected: usize },
+    #[error("week {week} appears twice with different dates")]
+    ConflictingDuplicate { week: u32 },
+    #[error("week {week} starts on {start}, not after week {previous}")]
+    NonMonotonic { week: u32, previous: u32, start: String },
+    #[error("weeks {from} and {to} are not {days} days apart")]
+    Discontinuous { from: u32, to: u32, days: i64 },
+}
+
+/// 解析后的一周课程
+#[derive(Debug, Clone, Default)]
+pub struct WeekCourses {
+    pub days: Vec<DayCourse>,
+    /// 解析有问题的课程记录（字段数不足的时段按无课处理，数字字段出错的课程仍保留）
+    pub diagnostics: Vec<CourseParseDiagnostic>,
+}
+
 #[derive(Debug, Deserialize)]
+#[allow(dead_code)]
 struct SchoolYearResponse {
     code: i32,
     msg: Option<String>,
@@ -103,6 +198,7 @@ struct SchoolYearData {
 }
 
 #[derive(Debug, Deserialize)]
+#[allow(dead_code)]
 struct SemesterResponse {
     code: i32,
     msg: Option<String>,
@@ -110,6 +206,7 @@ struct SemesterResponse {
 }
 
 #[derive(Debug, Deserialize)]
+#[allow(dead_code)]
 struct WeekCourseResponse {
     code: i32,
     msg: Option<String>,
@@ -133,7 +230,9 @@ pub async fn get_school_year(user_token: &str, client: &Client, config: &AppConf
         return Err(anyhow::anyhow!("Error while fetching info: {}. Message: {}", status, error_text));
     }
 
-    let school_year_response: SchoolYearResponse = response.json().await?;
+    let body: serde_json::Value = response.json().await?;
+    drift::observe(UpstreamEndpoint::SchoolYear, &body);
+    let school_year_response: SchoolYearResponse = serde_json::from_value(body)?;
 
     let formatted_data = school_year_response
         .data
@@ -174,19 +273,101 @@ pub async fn get_semester(
         return Err(anyhow::anyhow!("Error while fetching info: {}. Message: {}", status, error_text));
     }
 
-    let semester_response: SemesterResponse = response.json().await?;
+    let body: serde_json::Value = response.json().await?;
+    drift::observe(UpstreamEndpoint::Semester, &body);
+    let semester_response: SemesterResponse = serde_json::from_value(body)?;
+    let weeks = parse_semester_weeks(&semester_response.data)?;
 
-    let formatted_data = semester_response
-        .data
+    Ok(weeks)
+}
+
+/// 校验一行周信息：`[周号, 开始日期, 结束日期]`，周号从 1 开始，日期相差 6 天
+fn parse_week_row(row: &[String]) -> Result<(u32, NaiveDate), String> {
+    let field = |index: usize| row.get(index).map(|s| s.trim()).unwrap_or_default();
+    let week: u32 = field(0)
+        .parse()
+        .ok()
+        .filter(|w| *w > 0)
+        .ok_or_else(|| format!("invalid week number '{}'", field(0)))?;
+    let date = |index: usize| {
+        NaiveDate::parse_from_str(field(index), "%Y-%m-%d")
+            .map_err(|_| format!("week {}: invalid date '{}'", week, field(index)))
+    };
+    let (start, end) = (date(1)?, date(2)?);
+    if end < start {
+        return Err(format!("week {}: ends before it starts", week));
+    }
+    if end - start != Duration::days(6) {
+        return Err(format!("week {}: {} to {} is not a 7-day week", week, start, end));
+    }
+    Ok((week, start))
+}
+
+/// 解析学期周列表
+///
+/// 不合法的行（周号、日期无法解析，或不是完整的 7 天）会被丢弃，重复的周只保留一条；
+/// 按周号排序后开始日期必须递增且正好相差 7 天的整数倍，缺失的周按日期补齐。
+/// 没有可用的行、同一周日期不一致或周号与日期对不上时返回错误
+pub fn parse_semester_weeks(rows: &[Vec<String>]) -> Result<Vec<WeekInfo>, SemesterParseError> {
+    if rows.is_empty() {
+        return Err(SemesterParseError::Empty);
+    }
+
+    let mut weeks: Vec<(u32, NaiveDate)> = Vec::new();
+    let mut rejected = 0;
+    for row in rows {
+        match parse_week_row(row) {
+            Ok((week, start)) => match weeks.iter().find(|(w, _)| *w == week) {
+                Some((_, existing)) if *existing != start => {
+                    return Err(SemesterParseError::ConflictingDuplicate { week });
+                }
+                Some(_) => {}
+                None => weeks.push((week, start)),
+            },
+            Err(reason) => {
+                rejected += 1;
+                warn!("Skipping semester week row {:?}: {}", row, reason);
+            }
+        }
+    }
+    if weeks.is_empty() {
+        return Err(SemesterParseError::NoValidRows { rejected });
+    }
+    weeks.sort_by_key(|(week, _)| *week);
+
+    let mut filled = Vec::with_capacity(weeks.len());
+    for pair in weeks.windows(2) {
+        let ((prev_week, prev_start), (week, start)) = (pair[0], pair[1]);
+        if start <= prev_start {
+            return Err(SemesterParseError::NonMonotonic {
+                week,
+                previous: prev_week,
+                start: start.format("%Y-%m-%d").to_string(),
+            });
+        }
+        let days = 7 * i64::from(week - prev_week);
+        if start - prev_start != Duration::days(days) {
+            return Err(SemesterParseError::Discontinuous { from: prev_week, to: week, days });
+        }
+        filled.extend((prev_week..week).map(|w| (w, prev_start + Duration::days(7 * i64::from(w - prev_week)))));
+    }
+    if let Some(last) = weeks.last() {
+        filled.push(*last);
+    }
+    if filled.len() > weeks.len() {
+        let known: Vec<u32> = weeks.iter().map(|(w, _)| *w).collect();
+        let missing: Vec<u32> = filled.iter().map(|(w, _)| *w).filter(|w| !known.contains(w)).collect();
+        warn!("Filled missing semester weeks {:?}", missing);
+    }
+
+    Ok(filled
         .into_iter()
-        .map(|item| WeekInfo {
-            week: item.get(0).and_then(|s| s.parse().ok()).unwrap_or(0),
-            start_time: item.get(1).cloned().unwrap_or_default(),
-            end_time: item.get(2).cloned().unwrap_or_default(),
+        .map(|(week, start)| WeekInfo {
+            week,
+            start_time: start.format("%Y-%m-%d").to_string(),
+            end_time: (start + Duration::days(6)).format("%Y-%m-%d").to_string(),
         })
-        .collect();
-
-    Ok(formatted_data)
+        .collect())
 }
 
 /// 获取周课程
@@ -197,6 +378,22 @@ pub async fn get_week_course(
     client: &Client,
     config: &AppConfig,
 ) -> Result<Vec<DayCourse>> {
+    Ok(get_week_course_checked(user_token, student_id, start_time, client, config).await?.days)
+}
+
+/// 获取周课程，同时返回解析失败的课程记录
+pub async fn get_week_course_checked(
+    user_token: &str,
+    student_id: &str,
+    start_time: &str,
+    client: &Client,
+    config: &AppConfig,
+) -> Result<WeekCourses> {
+    // 开始日期为空或不合法时上游会返回不相关的数据，直接报错
+    if NaiveDate::parse_from_str(start_time.trim(), "%Y-%m-%d").is_err() {
+        return Err(anyhow::anyhow!("Invalid week start date '{}', expected YYYY-MM-DD", start_time));
+    }
+
     let semester_url = format!("{}/gateway/xgwork/appCourseTable/getListByNoWeek2", config.college_app_base_url);
 
     let response = client
@@ -213,40 +410,120 @@ pub async fn get_week_course(
         return Err(anyhow::anyhow!("Error while fetching info: {}. Message: {}", status, error_text));
     }
 
-    let week_course_response: WeekCourseResponse = response.json().await?;
+    let body: serde_json::Value = response.json().await?;
+    drift::observe(UpstreamEndpoint::WeekCourse, &body);
+    let week_course_response: WeekCourseResponse = serde_json::from_value(body)?;
+    let week = parse_week_course(week_course_response.data);
+    for diagnostic in &week.diagnostics {
+        warn!(
+            "Malformed course record on {} (weekday {}, lesson {}): {}",
+            start_time, diagnostic.weekday, diagnostic.course_number, diagnostic.message
+        );
+    }
 
-    let formatted_data = week_course_response
-        .data
+    Ok(week)
+}
+
+/// 解析上游返回的一周课程（外层按星期，内层按节次）
+///
+/// 数字字段无法解析时仍保留课程（使用兜底值），字段数不足的时段按无课处理，都记录到 `diagnostics`
+pub fn parse_week_course(data: Vec<Vec<String>>) -> WeekCourses {
+    let mut diagnostics = Vec::new();
+    let days = data
         .into_iter()
         .enumerate()
-        .map(|(day_index, day_courses)| DayCourse {
-            weekday: (day_index + 1) as u32,
-            course: day_courses
-                .into_iter()
-                .enumerate()
-                .map(|(course_index, course_str)| CourseSlot {
-                    course_number: (course_index + 1) as u32,
-                    course_info: parse_course_string(&course_str),
-                })
-                .collect(),
+        .map(|(day_index, day_courses)| {
+            let weekday = (day_index + 1) as u32;
+            DayCourse {
+                weekday,
+                course: day_courses
+                    .into_iter()
+                    .enumerate()
+                    .map(|(course_index, course_str)| {
+                        let course_number = (course_index + 1) as u32;
+                        let (course_info, errors) = match parse_course_string_lenient(&course_str, weekday, course_number) {
+                            Ok(parsed) => parsed,
+                            Err(e) => (None, vec![e]),
+                        };
+                        diagnostics.extend(errors.into_iter().map(|e| CourseParseDiagnostic {
+                            weekday,
+                            course_number,
+                            field_index: e.field_index(),
+                            value: e.value().map(str::to_string),
+                            raw: course_str.clone(),
+                            message: e.to_string(),
+                        }));
+                        CourseSlot { course_number, course_info }
+                    })
+                    .collect(),
+            }
         })
         .collect();
 
-    Ok(formatted_data)
+    WeekCourses { days, diagnostics }
+}
+
+/// 教师字段可能出现的分隔符（上游混用中英文标点）
+const TEACHER_SEPARATORS: &[char] = &[';', '；', ',', '，', '、', '/', '／'];
+
+/// 规范化教师列表：按混用的分隔符拆分、去掉首尾空白、去掉空项和“无”，按出现顺序去重
+pub fn normalize_teachers(raw: &str) -> Vec<String> {
+    let mut teachers: Vec<String> = Vec::new();
+    for name in raw.split(TEACHER_SEPARATORS).map(str::trim) {
+        if name.is_empty() || name == "无" || teachers.iter().any(|t| t == name) {
+            continue;
+        }
+        teachers.push(name.to_string());
+    }
+    teachers
 }
 
 /// 解析课程信息（把课程信息字符串解析成可读的对象）
-fn parse_course_string(string: &str) -> Option<CourseInfo> {
+///
+/// 空字符串表示无课，返回 `Ok(None)`；字段数不足或数字字段无法解析时返回错误
+pub fn parse_course_string_strict(string: &str) -> Result<Option<CourseInfo>, CourseParseError> {
+    let (info, errors) = parse_course_string_lenient(string, 0, 0)?;
+    match errors.into_iter().next() {
+        Some(e) => Err(e),
+        None => Ok(info),
+    }
+}
+
+/// 宽松解析课程信息：数字字段无法解析时使用兜底值并返回这些字段的错误
+///
+/// 节次、星期取课程所在时段的位置（`course_number`、`weekday`），其余数字字段取 0；
+/// 只有字段数不足时才返回错误、放弃整条记录
+pub fn parse_course_string_lenient(
+    string: &str,
+    weekday: u32,
+    course_number: u32,
+) -> Result<(Option<CourseInfo>, Vec<CourseParseError>), CourseParseError> {
     if string.is_empty() {
-        return None;
+        return Ok((None, Vec::new()));
     }
 
     let split_data: Vec<&str> = string.split('|').collect();
-    if split_data.len() < 9 {
-        return None;
+    if split_data.len() < COURSE_FIELDS.len() {
+        return Err(CourseParseError::MissingFields {
+            expected: COURSE_FIELDS.len(),
+            found: split_data.len(),
+        });
     }
 
-    Some(CourseInfo {
+    let mut errors = Vec::new();
+    let mut number = |index: usize, fallback: u32| -> u32 {
+        let value = split_data[index];
+        value.trim().parse().unwrap_or_else(|_| {
+            errors.push(CourseParseError::InvalidNumber {
+                index,
+                field: COURSE_FIELDS[index],
+                value: value.to_string(),
+            });
+            fallback
+        })
+    };
+
+    let info = CourseInfo {
         name: split_data[0].to_string(),
         classroom: if split_data[1] == "无" {
             None
@@ -254,12 +531,12 @@ fn parse_course_string(string: &str) -> Option<CourseInfo> {
             Some(split_data[1].to_string())
         },
         class: split_data[2].to_string(),
-        teacher: split_data[3].split(';').map(|s| s.to_string()).collect(),
-        course_number: split_data[4].parse().unwrap_or(0),
-        weekday: split_data[5].parse().unwrap_or(0),
+        teacher: normalize_teachers(split_data[3]),
+        course_number: number(4, course_number),
+        weekday: number(5, weekday),
         color: split_data[6].to_string(),
-        continuous_course: split_data[7].parse().unwrap_or(0),
+        continuous_course: number(7, 0),
         code: split_data[8].to_string(),
-    })
+    };
+    Ok((Some(info), errors))
 }
-
@@ -0,0 +1,32 @@
+use actix_web::web;
+
+use crate::controller::admin;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.route("/cache", web::get().to(admin::get_cache))
+        .route("/cache", web::delete().to(admin::clear_cache))
+        .route("/cache/evict-expired", web::post().to(admin::evict_expired_cache))
+        .route("/cache/purge", web::post().to(admin::purge_user_cache))
+        .route("/dns-cache", web::get().to(admin::get_dns_cache))
+        .route("/dns-cache", web::delete().to(admin::flush_dns_cache))
+        .route("/rate-limits", web::delete().to(admin::reset_rate_limits))
+        .route("/simulator", web::post().to(admin::run_simulator))
+        .route("/logs", web::get().to(admin::get_logs))
+        .route("/stats", web::get().to(admin::get_admin_stats))
+        .route("/toggles", web::get().to(admin::get_toggles))
+        .route("/toggles", web::patch().to(admin::update_toggles))
+        .route("/time-tables", web::get().to(admin::get_time_tables))
+        .route("/time-tables/reload", web::post().to(admin::reload_time_tables))
+        .route("/time-table-versions", web::get().to(admin::list_time_table_versions))
+        .route("/time-table-versions", web::post().to(admin::create_time_table_version))
+        .route("/time-table-versions/{id}", web::delete().to(admin::delete_time_table_version))
+        .route("/holidays", web::get().to(admin::get_holidays))
+        .route("/holidays/reload", web::post().to(admin::reload_holidays))
+        .route("/locations", web::get().to(admin::get_locations))
+        .route("/locations/reload", web::post().to(admin::reload_locations))
+        .route("/schema-drift", web::get().to(admin::get_schema_drift))
+        .route("/schema-drift/baselines/{endpoint:.+}", web::delete().to(admin::reset_schema_baseline))
+        .route("/api-keys", web::get().to(admin::list_api_keys))
+        .route("/api-keys", web::post().to(admin::create_api_key))
+        .route("/api-keys/{id}", web::delete().to(admin::revoke_api_key));
+}
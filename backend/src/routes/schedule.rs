@@ -1,14 +1,40 @@
-use actix_web::web;
+use actix_web::{dev::HttpServiceFactory, middleware::from_fn, web, Route};
 
-use crate::controller::schedule;
+use crate::controller::{auth, calendar, schedule, webhook};
+use crate::middleware::{maintenance::reject_in_maintenance, rate_limit::limit_upstream_requests};
 
-pub fn configure(cfg: &mut web::ServiceConfig) {
-    cfg.route("/schedule", web::post().to(schedule::post_schedule))
-        .route("/auth/userinfo", web::get().to(schedule::get_user_info_endpoint))
-        .route("/schedule/meta", web::get().to(schedule::get_schedule_meta))
-        .route("/time-table", web::get().to(schedule::get_time_table))
-        .route("/season", web::get().to(schedule::get_season))
-        .route("/stats", web::get().to(schedule::get_stats))
-        .route("/ping", web::get().to(schedule::ping));
+/// 需要请求学校服务器的接口：限流，维护模式下直接拒绝（后 wrap 的先执行）
+fn upstream_resource(path: &str, route: Route) -> impl HttpServiceFactory {
+    web::resource(path)
+        .wrap(from_fn(limit_upstream_requests))
+        .wrap(from_fn(reject_in_maintenance))
+        .route(route)
 }
 
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(upstream_resource("/schedule", web::post().to(schedule::post_schedule)))
+    .service(upstream_resource("/schedule/stream", web::post().to(schedule::post_schedule_stream)))
+    .service(upstream_resource("/schedule/day", web::get().to(calendar::get_day)))
+    .service(upstream_resource("/schedule/today", web::get().to(calendar::get_today)))
+    .service(upstream_resource("/schedule/tomorrow", web::get().to(calendar::get_tomorrow)))
+    .service(upstream_resource("/schedule/next", web::get().to(calendar::get_next)))
+    .service(upstream_resource("/schedule/week/current", web::get().to(calendar::get_current_week)))
+    .service(upstream_resource("/schedule/week/{n}", web::get().to(calendar::get_week)))
+    .service(upstream_resource("/schedule/occurrences", web::get().to(calendar::get_occurrences)))
+    .service(upstream_resource("/schedule/search", web::get().to(calendar::search_schedule)))
+    .service(upstream_resource("/schedule/conflicts", web::get().to(schedule::get_conflicts)))
+    .service(upstream_resource("/schedule/summary", web::get().to(schedule::get_summary)))
+    .service(upstream_resource("/schedule/teachers", web::get().to(schedule::get_teachers)))
+    .service(upstream_resource("/auth/userinfo", web::get().to(schedule::get_user_info_endpoint)))
+    .service(upstream_resource("/auth/login", web::post().to(auth::login)))
+    .service(upstream_resource("/schedule/meta", web::get().to(schedule::get_schedule_meta)))
+    .route("/schedule/history", web::get().to(schedule::get_schedule_history))
+    .route("/auth/logout", web::post().to(auth::logout))
+    .route("/webhooks", web::post().to(webhook::create_webhook))
+    .route("/webhooks", web::get().to(webhook::list_webhooks))
+    .route("/webhooks/{id}", web::delete().to(webhook::delete_webhook))
+    .route("/time-table", web::get().to(schedule::get_time_table))
+    .route("/season", web::get().to(schedule::get_season))
+    .route("/stats", web::get().to(schedule::get_stats))
+    .route("/ping", web::get().to(schedule::ping));
+}
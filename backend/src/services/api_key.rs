@@ -0,0 +1,274 @@
+use chrono::{Duration, NaiveTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseBackend, DatabaseConnection, DbErr,
+    EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, Set, Statement,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use utoipa::ToSchema;
+
+use crate::db::models::{api_keys, request_logs};
+use crate::utils::crypto::{hash_api_key, random_token};
+use crate::utils::schedule::{east8_today_ymd, tz_east8};
+use crate::utils::time::now_millis;
+
+/// API Key 明文前缀
+const API_KEY_PREFIX: &str = "fjcpc_";
+
+/// API Key 授权范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiScope {
+    /// 课表查询（/schedule、/auth、/webhooks）
+    Schedule,
+    /// 学年学期元数据（/schedule/meta）
+    Meta,
+    /// 访问统计（/stats）
+    Stats,
+}
+
+impl ApiScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiScope::Schedule => "schedule",
+            ApiScope::Meta => "meta",
+            ApiScope::Stats => "stats",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim() {
+            "schedule" => Some(ApiScope::Schedule),
+            "meta" => Some(ApiScope::Meta),
+            "stats" => Some(ApiScope::Stats),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ApiScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 通过校验的 API Key 身份（放在请求扩展里，供处理函数记录日志）
+#[derive(Debug, Clone)]
+pub struct ApiKeyIdentity {
+    pub id: i32,
+    pub name: String,
+}
+
+/// API Key 校验错误
+#[derive(Debug, thiserror::Error)]
+pub enum ApiKeyError {
+    #[error("Missing API key")]
+    Missing,
+    #[error("Invalid API key")]
+    Invalid,
+    #[error("API key has been revoked")]
+    Revoked,
+    #[error("API key has expired")]
+    Expired,
+    #[error("API key does not have the '{0}' scope")]
+    ScopeDenied(ApiScope),
+    #[error("API key daily quota exceeded")]
+    QuotaExceeded { retry_after: u64 },
+    #[error(transparent)]
+    Db(#[from] DbErr),
+}
+
+/// API Key 信息（不含明文和哈希）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyInfo {
+    pub id: i32,
+    /// 接入方名称
+    #[schema(example = "课表小程序")]
+    pub name: String,
+    /// 明文前缀，便于辨认
+    #[schema(example = "fjcpc_1a2b3c4d")]
+    pub key_prefix: String,
+    /// 授权范围
+    pub scopes: Vec<ApiScope>,
+    /// 每日配额（为空表示不限）
+    pub quota_per_day: Option<i32>,
+    /// 今日已用次数
+    pub usage_today: i32,
+    /// 日志中记录的总请求数
+    pub total_requests: u64,
+    /// 过期时间（毫秒时间戳）
+    pub expires_at: Option<i64>,
+    pub revoked: bool,
+    /// 最后使用时间（毫秒时间戳）
+    pub last_used_at: Option<i64>,
+    /// 创建时间（毫秒时间戳）
+    pub created_at: i64,
+}
+
+/// 新建 API Key 的结果（明文只返回这一次）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreatedApiKey {
+    /// API Key 明文，请求时放在 X-API-Key 头里
+    #[schema(example = "fjcpc_1a2b3c4d...")]
+    pub key: String,
+    pub info: ApiKeyInfo,
+}
+
+fn parse_scopes(raw: &str) -> Vec<ApiScope> {
+    raw.split(',').filter_map(ApiScope::parse).collect()
+}
+
+fn join_scopes(scopes: &[ApiScope]) -> String {
+    let mut names: Vec<&str> = scopes.iter().map(ApiScope::as_str).collect();
+    names.sort_unstable();
+    names.dedup();
+    names.join(",")
+}
+
+fn to_info(model: api_keys::Model, total_requests: u64) -> ApiKeyInfo {
+    let usage_today = if model.usage_day == east8_today_ymd() { model.usage_count } else { 0 };
+    ApiKeyInfo {
+        id: model.id,
+        name: model.name,
+        key_prefix: model.key_prefix,
+        scopes: parse_scopes(&model.scopes),
+        quota_per_day: model.quota_per_day,
+        usage_today,
+        total_requests,
+        expires_at: model.expires_at,
+        revoked: model.revoked,
+        last_used_at: model.last_used_at,
+        created_at: model.created_at,
+    }
+}
+
+/// 距离东八区次日零点的秒数（每日配额在零点重置）
+fn seconds_until_east8_midnight() -> u64 {
+    let now = Utc::now().with_timezone(&tz_east8());
+    let tomorrow = (now.date_naive() + Duration::days(1)).and_time(NaiveTime::MIN);
+    (tomorrow - now.naive_local()).num_seconds().max(1) as u64
+}
+
+/// 创建 API Key
+pub async fn create_api_key(
+    db: &DatabaseConnection,
+    name: &str,
+    scopes: &[ApiScope],
+    quota_per_day: Option<i32>,
+    expires_at: Option<i64>,
+) -> Result<CreatedApiKey, DbErr> {
+    let key = format!("{}{}", API_KEY_PREFIX, random_token(24));
+    let now = now_millis();
+
+    let model = api_keys::ActiveModel {
+        name: Set(name.to_string()),
+        key_hash: Set(hash_api_key(&key)),
+        key_prefix: Set(key[..API_KEY_PREFIX.len() + 8].to_string()),
+        scopes: Set(join_scopes(scopes)),
+        quota_per_day: Set(quota_per_day),
+        usage_day: Set(String::new()),
+        usage_count: Set(0),
+        expires_at: Set(expires_at),
+        revoked: Set(false),
+        last_used_at: Set(None),
+        created_at: Set(now),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    Ok(CreatedApiKey {
+        key,
+        info: to_info(model, 0),
+    })
+}
+
+/// 列出所有 API Key（含按日志统计的请求数）
+pub async fn list_api_keys(db: &DatabaseConnection) -> Result<Vec<ApiKeyInfo>, DbErr> {
+    let keys = api_keys::Entity::find()
+        .order_by_asc(api_keys::Column::Id)
+        .all(db)
+        .await?;
+
+    let mut result = Vec::with_capacity(keys.len());
+    for key in keys {
+        let total = request_logs::Entity::find()
+            .filter(request_logs::Column::ApiKeyId.eq(key.id))
+            .count(db)
+            .await?;
+        result.push(to_info(key, total));
+    }
+    Ok(result)
+}
+
+/// 吊销 API Key，返回是否找到该 Key
+pub async fn revoke_api_key(db: &DatabaseConnection, id: i32) -> Result<bool, DbErr> {
+    let Some(key) = api_keys::Entity::find_by_id(id).one(db).await? else {
+        return Ok(false);
+    };
+    let mut active: api_keys::ActiveModel = key.into();
+    active.revoked = Set(true);
+    active.update(db).await?;
+    Ok(true)
+}
+
+/// 校验 API Key 并记一次用量
+pub async fn authorize(
+    db: &DatabaseConnection,
+    key: &str,
+    scope: ApiScope,
+) -> Result<ApiKeyIdentity, ApiKeyError> {
+    let key = api_keys::Entity::find()
+        .filter(api_keys::Column::KeyHash.eq(hash_api_key(key)))
+        .one(db)
+        .await?
+        .ok_or(ApiKeyError::Invalid)?;
+
+    if key.revoked {
+        return Err(ApiKeyError::Revoked);
+    }
+
+    let now = now_millis();
+    if key.expires_at.is_some_and(|expires_at| expires_at <= now) {
+        return Err(ApiKeyError::Expired);
+    }
+
+    if !parse_scopes(&key.scopes).contains(&scope) {
+        return Err(ApiKeyError::ScopeDenied(scope));
+    }
+
+    // 每日配额（东八区零点重置）：检查和计数在同一条 UPDATE 中完成，
+    // 并发请求不会同时读到未超限的旧计数
+    let today = east8_today_ymd();
+    let result = db
+        .execute(Statement::from_sql_and_values(
+            DatabaseBackend::Sqlite,
+            "UPDATE api_keys
+             SET usage_count = CASE WHEN usage_day = ? THEN usage_count + 1 ELSE 1 END,
+                 usage_day = ?,
+                 last_used_at = ?
+             WHERE id = ?
+               AND (quota_per_day IS NULL
+                    OR (CASE WHEN usage_day = ? THEN usage_count ELSE 0 END) < quota_per_day)",
+            [
+                today.clone().into(),
+                today.clone().into(),
+                now.into(),
+                key.id.into(),
+                today.into(),
+            ],
+        ))
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(ApiKeyError::QuotaExceeded {
+            retry_after: seconds_until_east8_midnight(),
+        });
+    }
+
+    let identity = ApiKeyIdentity {
+        id: key.id,
+        name: key.name,
+    };
+
+    Ok(identity)
+}
@@ -0,0 +1,458 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use crate::parser::schedule::{CourseInfo, DayCourse, WeekInfo};
+use crate::services::course::collect_lessons;
+use crate::services::occurrence::{expand_occurrences, OccurrenceRange};
+use crate::utils::holiday::{current_holidays, special_day, SpecialDay, SpecialDayKind};
+use crate::utils::location::{current_locations, parse_location, Location};
+use crate::utils::schedule::{
+    current_time_table_versions, current_time_tables, get_course_time_table, season_for_date,
+    time_table_version_for_date, tz_east8,
+};
+
+/// 带具体时间的一节课
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TimedLesson {
+    /// 起始节次
+    #[schema(example = 1)]
+    pub start: u32,
+    /// 结束节次（含）
+    #[schema(example = 2)]
+    pub end: u32,
+    /// 上课时间（超出作息表节次时为空）
+    #[schema(example = "08:00")]
+    pub start_time: Option<String>,
+    /// 下课时间（超出作息表节次时为空）
+    #[schema(example = "09:40")]
+    pub end_time: Option<String>,
+    pub course: CourseInfo,
+    /// 解析后的上课地点（没有教室时为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<Location>,
+}
+
+/// 同一栋楼里的课
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BuildingGroup {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "南校区")]
+    pub campus: Option<String>,
+    #[schema(example = "教学楼A")]
+    pub building: String,
+    /// 在这栋楼上的课的起始节次
+    #[schema(example = json!([1, 3]))]
+    pub lessons: Vec<u32>,
+}
+
+/// 按楼宇分组（按第一节课的顺序；线上课程和无法识别楼宇的课不分组）
+pub fn group_by_building(lessons: &[TimedLesson]) -> Vec<BuildingGroup> {
+    let mut groups: Vec<BuildingGroup> = Vec::new();
+    for lesson in lessons {
+        let Some(location) = lesson.location.as_ref().filter(|l| !l.online) else {
+            continue;
+        };
+        let Some(building) = location.building.as_ref() else {
+            continue;
+        };
+        match groups.iter_mut().find(|g| &g.building == building && g.campus == location.campus) {
+            Some(group) => group.lessons.push(lesson.start),
+            None => groups.push(BuildingGroup {
+                campus: location.campus.clone(),
+                building: building.clone(),
+                lessons: vec![lesson.start],
+            }),
+        }
+    }
+    groups
+}
+
+/// 课间来不及换楼的提醒
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WalkWarning {
+    /// 日期（YYYY-MM-DD）
+    #[schema(example = "2024-10-08")]
+    pub date: String,
+    /// 星期几（1=周一，...，7=周日）
+    #[schema(example = 2)]
+    pub weekday: u32,
+    /// 前一节课的起始节次
+    #[schema(example = 1)]
+    pub from_lesson: u32,
+    /// 后一节课的起始节次
+    #[schema(example = 3)]
+    pub to_lesson: u32,
+    #[schema(example = "高等数学")]
+    pub from_course: String,
+    #[schema(example = "大学物理")]
+    pub to_course: String,
+    pub from: Location,
+    pub to: Location,
+    /// 两节课之间的休息时间（分钟）
+    #[schema(example = 10)]
+    pub break_minutes: i64,
+    /// 两地之间的步行时间（分钟）
+    #[schema(example = 30)]
+    pub walk_minutes: u32,
+}
+
+/// 检查相邻两节课之间是否来得及换楼
+///
+/// 休息时间按当天作息表中前一节课的下课时间和后一节课的上课时间计算，
+/// 步行时间按教室解析配置中的步行时间；线上课程、无法识别楼宇或没有配置步行时间时不提醒
+pub fn walk_warnings(date: &str, weekday: u32, lessons: &[TimedLesson]) -> Vec<WalkWarning> {
+    let parser = current_locations();
+    let time = |t: Option<&str>| NaiveTime::parse_from_str(t?, "%H:%M").ok();
+
+    lessons
+        .windows(2)
+        .filter_map(|pair| {
+            let (prev, next) = (&pair[0], &pair[1]);
+            let (from, to) = (prev.location.as_ref()?, next.location.as_ref()?);
+            let walk_minutes = parser.walk_minutes(from, to)?;
+            let break_minutes = (time(next.start_time.as_deref())? - time(prev.end_time.as_deref())?).num_minutes();
+            (i64::from(walk_minutes) > break_minutes).then(|| WalkWarning {
+                date: date.to_string(),
+                weekday,
+                from_lesson: prev.start,
+                to_lesson: next.start,
+                from_course: prev.course.name.clone(),
+                to_course: next.course.name.clone(),
+                from: from.clone(),
+                to: to.clone(),
+                break_minutes,
+                walk_minutes,
+            })
+        })
+        .collect()
+}
+
+/// 某一天的课表
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DaySchedule {
+    /// 日期（YYYY-MM-DD）
+    #[schema(example = "2024-10-08")]
+    pub date: String,
+    /// 教学周（不在学期内时为空）
+    #[schema(example = 6)]
+    pub week: Option<u32>,
+    /// 星期几（1=周一，...，7=周日）
+    #[schema(example = 2)]
+    pub weekday: u32,
+    /// 当天使用的时间表："winter" | "summer"，有生效的时间表版本时为 `version:<id>`（同 [`time_table_key`]）
+    #[schema(example = "winter")]
+    pub season: String,
+    /// 当天的课（按节次排序；调休日为所跟随那天的课）
+    pub lessons: Vec<TimedLesson>,
+    /// 节假日或调休说明
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub special: Option<SpecialDay>,
+    /// 因放假停上的课
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cancelled: Vec<TimedLesson>,
+    /// 当天的课按楼宇分组
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub buildings: Vec<BuildingGroup>,
+    /// 课间来不及换楼的提醒
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub walk_warnings: Vec<WalkWarning>,
+}
+
+/// 下一节课
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NextLesson {
+    /// 上课日期（YYYY-MM-DD）
+    #[schema(example = "2024-10-08")]
+    pub date: String,
+    #[schema(example = 6)]
+    pub week: u32,
+    #[schema(example = 2)]
+    pub weekday: u32,
+    /// 上课时刻（ISO 8601，东八区）
+    #[schema(example = "2024-10-08T08:00:00+08:00")]
+    pub starts_at: String,
+    /// 距离上课的分钟数（向上取整）
+    #[schema(example = 25)]
+    pub minutes_until: i64,
+    pub lesson: TimedLesson,
+}
+
+/// 日期使用的作息名（如 "winter" | "summer"）
+pub fn season_of(date: &str) -> String {
+    season_for_date(date)
+}
+
+/// 日期使用的时间表键：当天有生效的时间表版本时为 `version:<id>`，否则为作息名
+pub fn time_table_key(date: &str) -> String {
+    match time_table_version_for_date(date) {
+        Some(version) => version.key(),
+        None => season_of(date),
+    }
+}
+
+/// 所有时间表（作息名或 `version:<id>` -> 每节课的起止时间）
+pub fn season_time_tables() -> HashMap<String, Vec<(String, String)>> {
+    let mut tables = current_time_tables().time_tables.clone();
+    for version in current_time_table_versions().iter() {
+        tables.insert(version.key(), version.times.clone());
+    }
+    tables
+}
+
+/// 每周每天使用的时间表键（周号 -> 周一到周日的键，见 [`time_table_key`]）
+///
+/// 按该周的实际日期判断，跨作息切换日（如 6 月 1 日）或临时作息的周会混用多张时间表
+pub fn week_seasons(semester_weeks: &[WeekInfo]) -> HashMap<u32, Vec<String>> {
+    semester_weeks
+        .iter()
+        .filter_map(|w| {
+            let start = parse_date(&w.start_time)?;
+            let seasons = (0..7)
+                .map(|i| time_table_key(&(start + Duration::days(i)).format("%Y-%m-%d").to_string()))
+                .collect();
+            Some((w.week, seasons))
+        })
+        .collect()
+}
+
+/// 解析 `YYYY-MM-DD`
+pub fn parse_date(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok()
+}
+
+/// 东八区当前时间
+pub fn east8_now() -> NaiveDateTime {
+    chrono::Utc::now().with_timezone(&tz_east8()).naive_local()
+}
+
+/// 查找日期所在的教学周
+pub fn find_week(semester_weeks: &[WeekInfo], date: NaiveDate) -> Option<&WeekInfo> {
+    semester_weeks.iter().find(|w| {
+        match (parse_date(&w.start_time), parse_date(&w.end_time)) {
+            (Some(start), Some(end)) => start <= date && date <= end,
+            _ => false,
+        }
+    })
+}
+
+/// 给一天的课配上具体时间
+fn timed_lessons(days: &[DayCourse], weekday: u32, date: &str) -> Vec<TimedLesson> {
+    let Some(day) = days.iter().find(|d| d.weekday == weekday) else {
+        return Vec::new();
+    };
+    let times = get_course_time_table(date).times;
+    let time_of = |n: u32| n.checked_sub(1).and_then(|i| times.get(i as usize));
+
+    collect_lessons(std::slice::from_ref(day))
+        .into_iter()
+        .map(|l| TimedLesson {
+            start_time: time_of(l.start).map(|t| t.0.clone()),
+            end_time: time_of(l.end).map(|t| t.1.clone()),
+            start: l.start,
+            end: l.end,
+            location: parse_location(l.course.classroom.as_deref()),
+            course: l.course,
+        })
+        .collect()
+}
+
+/// 某一天的课表
+pub fn day_schedule(
+    weeks: &HashMap<u32, Vec<DayCourse>>,
+    semester_weeks: &[WeekInfo],
+    date: NaiveDate,
+) -> DaySchedule {
+    let date_str = date.format("%Y-%m-%d").to_string();
+    let weekday = date.weekday().number_from_monday();
+    let week = find_week(semester_weeks, date).map(|w| w.week);
+    let special = special_day(date);
+
+    // 调休日按所跟随那天的周次和星期取课，上课时间仍按当天的作息
+    let source = match special.as_ref().and_then(|s| s.follows.as_deref()).and_then(parse_date) {
+        Some(follows) => (find_week(semester_weeks, follows).map(|w| w.week), follows.weekday().number_from_monday()),
+        None => (week, weekday),
+    };
+    let mut lessons = source
+        .0
+        .and_then(|w| weeks.get(&w))
+        .map(|days| timed_lessons(days, source.1, &date_str))
+        .unwrap_or_default();
+
+    let cancelled = if special.as_ref().is_some_and(|s| s.kind == SpecialDayKind::Holiday) {
+        std::mem::take(&mut lessons)
+    } else {
+        Vec::new()
+    };
+
+    DaySchedule {
+        buildings: group_by_building(&lessons),
+        walk_warnings: walk_warnings(&date_str, weekday, &lessons),
+        season: time_table_key(&date_str),
+        date: date_str,
+        week,
+        weekday,
+        lessons,
+        special,
+        cancelled,
+    }
+}
+
+/// 从 `now`（东八区）起的下一节课，学期内没有更多课时返回 None
+///
+/// 从当天起每次只展开一周，找到就停，不展开整个学期
+pub fn next_lesson(
+    weeks: &HashMap<u32, Vec<DayCourse>>,
+    semester_weeks: &[WeekInfo],
+    now: NaiveDateTime,
+) -> Option<NextLesson> {
+    let now = tz_east8().from_local_datetime(&now).single()?;
+    let first = semester_weeks.iter().filter_map(|w| parse_date(&w.start_time)).min()?;
+    let last = semester_weeks.iter().filter_map(|w| parse_date(&w.end_time)).max()?;
+
+    let mut from = now.date_naive().max(first);
+    while from <= last {
+        let to = from + Duration::days(6);
+        let range = OccurrenceRange { from: Some(from), to: Some(to), ..Default::default() };
+        let found = expand_occurrences(weeks, semester_weeks, range)
+            .into_iter()
+            .find_map(|occurrence| {
+                let starts_at = occurrence.starts_at().filter(|t| *t > now)?;
+                let seconds = (starts_at - now).num_seconds();
+                Some(NextLesson {
+                    lesson: occurrence.to_timed_lesson(),
+                    date: occurrence.date,
+                    week: occurrence.week,
+                    weekday: occurrence.weekday,
+                    starts_at: occurrence.start,
+                    minutes_until: (seconds + 59) / 60,
+                })
+            });
+        if found.is_some() {
+            return found;
+        }
+        from = to + Duration::days(1);
+    }
+    None
+}
+
+/// 日期相对学期的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WeekStatus {
+    /// 在某个教学周内
+    InSemester,
+    /// 第一周之前（开学前）
+    BeforeSemester,
+    /// 最后一周之后（放假或学期之间）
+    AfterSemester,
+    /// 学期内但不属于任何教学周（周列表中间有缺口，例如整周放假）
+    Gap,
+    /// 没有学期周信息
+    NoSemester,
+}
+
+/// 一周的课表
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WeekSchedule {
+    pub week: WeekInfo,
+    /// 该周的每日课程（周一到周日，不含节假日与调休调整）
+    pub days: Vec<DayCourse>,
+    /// 该周内的节假日与调休日
+    #[serde(default)]
+    pub special_days: Vec<SpecialDay>,
+    /// 该周课间来不及换楼的提醒（已按节假日与调休调整）
+    #[serde(default)]
+    pub walk_warnings: Vec<WalkWarning>,
+}
+
+/// 一周的课表，附带该周内的节假日、调休日和换楼提醒
+pub fn week_schedule(
+    weeks: &HashMap<u32, Vec<DayCourse>>,
+    semester_weeks: &[WeekInfo],
+    info: &WeekInfo,
+) -> WeekSchedule {
+    let (special_days, walk_warnings) = match (parse_date(&info.start_time), parse_date(&info.end_time)) {
+        (Some(start), Some(end)) => (
+            current_holidays().special_days_between(start, end),
+            start
+                .iter_days()
+                .take_while(|d| *d <= end)
+                .flat_map(|d| day_schedule(weeks, semester_weeks, d).walk_warnings)
+                .collect(),
+        ),
+        _ => (Vec::new(), Vec::new()),
+    };
+    WeekSchedule {
+        week: info.clone(),
+        days: weeks.get(&info.week).cloned().unwrap_or_default(),
+        special_days,
+        walk_warnings,
+    }
+}
+
+/// 当前周的解析结果
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CurrentWeek {
+    /// 查询的日期（YYYY-MM-DD，东八区）
+    #[schema(example = "2024-10-08")]
+    pub date: String,
+    pub status: WeekStatus,
+    /// 日期所在的教学周（不在教学周内时为空）
+    #[schema(example = 6)]
+    pub week: Option<u32>,
+    /// 距离第一周开始的天数（仅开学前）
+    pub days_until_start: Option<i64>,
+    /// 距离最后一周结束的天数（仅学期结束后）
+    pub days_since_end: Option<i64>,
+    /// 建议展示的周：所在周；开学前或缺口中为下一个教学周；学期结束后为最后一周
+    pub display: Option<WeekSchedule>,
+}
+
+/// 计算日期对应的教学周
+pub fn resolve_week(
+    weeks: &HashMap<u32, Vec<DayCourse>>,
+    semester_weeks: &[WeekInfo],
+    date: NaiveDate,
+) -> CurrentWeek {
+    let mut dated: Vec<(NaiveDate, NaiveDate, &WeekInfo)> = semester_weeks
+        .iter()
+        .filter_map(|w| Some((parse_date(&w.start_time)?, parse_date(&w.end_time)?, w)))
+        .collect();
+    dated.sort_by_key(|(start, _, _)| *start);
+
+    let schedule_of = |info: &WeekInfo| week_schedule(weeks, semester_weeks, info);
+
+    let mut result = CurrentWeek {
+        date: date.format("%Y-%m-%d").to_string(),
+        status: WeekStatus::NoSemester,
+        week: None,
+        days_until_start: None,
+        days_since_end: None,
+        display: None,
+    };
+
+    let (Some(first), Some(last)) = (dated.first(), dated.last()) else {
+        return result;
+    };
+
+    if date < first.0 {
+        result.status = WeekStatus::BeforeSemester;
+        result.days_until_start = Some((first.0 - date).num_days());
+        result.display = Some(schedule_of(first.2));
+    } else if date > last.1 {
+        result.status = WeekStatus::AfterSemester;
+        result.days_since_end = Some((date - last.1).num_days());
+        result.display = Some(schedule_of(last.2));
+    } else if let Some((_, _, info)) = dated.iter().find(|(start, end, _)| *start <= date && date <= *end) {
+        result.status = WeekStatus::InSemester;
+        result.week = Some(info.week);
+        result.display = Some(schedule_of(info));
+    } else {
+        result.status = WeekStatus::Gap;
+        result.display = dated.iter().find(|(start, _, _)| *start > date).map(|(_, _, info)| schedule_of(info));
+    }
+
+    result
+}
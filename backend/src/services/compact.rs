@@ -0,0 +1,226 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use utoipa::ToSchema;
+
+use crate::parser::schedule::{CourseInfo, CourseSlot, DayCourse};
+
+/// 一天有哪些节次（解码时按这些节次生成空时段）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct DayShape {
+    #[schema(example = 1)]
+    pub weekday: u32,
+    /// 节次列表
+    #[schema(example = json!([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]))]
+    pub slots: Vec<u32>,
+}
+
+/// 有课的一个时段
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct CompactSlot {
+    #[schema(example = 1)]
+    pub weekday: u32,
+    #[schema(example = 1)]
+    pub course_number: u32,
+    /// `courses` 中的下标
+    #[schema(example = 0)]
+    pub course: usize,
+}
+
+/// 一个时段的位置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SlotRef {
+    #[schema(example = 1)]
+    pub weekday: u32,
+    #[schema(example = 1)]
+    pub course_number: u32,
+}
+
+/// 某一周与模板的差异
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct WeekException {
+    /// 模板中没课、这一周有课的时段
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub added: Vec<CompactSlot>,
+    /// 模板中有课、这一周没课的时段
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<SlotRef>,
+    /// 模板和这一周都有课但课程不同的时段
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changed: Vec<CompactSlot>,
+    /// 这一周的节次与模板不同时给出
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shape: Option<Vec<DayShape>>,
+}
+
+impl WeekException {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty() && self.shape.is_none()
+    }
+}
+
+/// 紧凑格式的课表：每周模板 + 各周差异
+///
+/// 同一学期大部分周的课完全相同，按周完整返回时大部分是重复内容；
+/// 这里每个时段取各周最常见的课作为模板，课程详情只出现一次，各周只记录与模板不同的时段
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CompactSchedule {
+    /// 有课表数据的周号（升序）
+    #[schema(example = json!([1, 2, 3]))]
+    pub weeks: Vec<u32>,
+    /// 去重后的课程详情，时段通过下标引用
+    pub courses: Vec<CourseInfo>,
+    /// 模板的节次
+    pub shape: Vec<DayShape>,
+    /// 模板中有课的时段
+    pub template: Vec<CompactSlot>,
+    /// 各周与模板的差异（周号 -> 差异，与模板完全相同的周不出现）
+    #[serde(default)]
+    pub exceptions: BTreeMap<u32, WeekException>,
+}
+
+/// 时段位置（星期, 节次）
+type SlotKey = (u32, u32);
+
+/// 一周的节次与有课的时段
+fn week_slots(days: &[DayCourse]) -> (Vec<DayShape>, BTreeMap<SlotKey, &CourseInfo>) {
+    let shape = days
+        .iter()
+        .map(|d| DayShape {
+            weekday: d.weekday,
+            slots: d.course.iter().map(|s| s.course_number).collect(),
+        })
+        .collect();
+    let filled = days
+        .iter()
+        .flat_map(|d| {
+            d.course
+                .iter()
+                .filter_map(move |s| Some(((d.weekday, s.course_number), s.course_info.as_ref()?)))
+        })
+        .collect();
+    (shape, filled)
+}
+
+/// 出现次数最多的值，次数相同时取先出现的
+fn most_common<T: PartialEq + Clone>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    let mut counts: Vec<(T, usize)> = Vec::new();
+    for value in values {
+        match counts.iter_mut().find(|(v, _)| *v == value) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((value, 1)),
+        }
+    }
+    let max = counts.iter().map(|(_, n)| *n).max()?;
+    counts.into_iter().find(|(_, n)| *n == max).map(|(v, _)| v)
+}
+
+/// 按周聚合的课表编码为紧凑格式
+pub fn encode(weeks: &HashMap<u32, Vec<DayCourse>>) -> CompactSchedule {
+    let mut numbers: Vec<u32> = weeks.keys().copied().collect();
+    numbers.sort_unstable();
+
+    let mut courses: Vec<CourseInfo> = Vec::new();
+    let mut index_of = |info: &CourseInfo| match courses.iter().position(|c| c == info) {
+        Some(i) => i,
+        None => {
+            courses.push(info.clone());
+            courses.len() - 1
+        }
+    };
+
+    // 每周的节次与时段 -> 课程下标
+    let mut per_week: Vec<(u32, Vec<DayShape>, BTreeMap<SlotKey, usize>)> = Vec::new();
+    for week in &numbers {
+        let (shape, filled) = week_slots(&weeks[week]);
+        let filled = filled.into_iter().map(|(key, info)| (key, index_of(info))).collect();
+        per_week.push((*week, shape, filled));
+    }
+
+    let shape = most_common(per_week.iter().map(|(_, shape, _)| shape.clone())).unwrap_or_default();
+
+    // 每个时段取各周最常见的值（包括没课）作为模板
+    let all_keys: BTreeSet<SlotKey> =
+        per_week.iter().flat_map(|(_, _, filled)| filled.keys().copied()).collect();
+    let template: BTreeMap<SlotKey, usize> = all_keys
+        .into_iter()
+        .filter_map(|key| {
+            let course = most_common(per_week.iter().map(|(_, _, filled)| filled.get(&key).copied()))??;
+            Some((key, course))
+        })
+        .collect();
+
+    let slot = |(weekday, course_number): SlotKey, course: usize| CompactSlot { weekday, course_number, course };
+    let mut exceptions = BTreeMap::new();
+    for (week, week_shape, filled) in &per_week {
+        let mut exception = WeekException {
+            shape: (*week_shape != shape).then(|| week_shape.clone()),
+            ..Default::default()
+        };
+        for (key, course) in filled {
+            match template.get(key) {
+                None => exception.added.push(slot(*key, *course)),
+                Some(t) if t != course => exception.changed.push(slot(*key, *course)),
+                Some(_) => {}
+            }
+        }
+        for key in template.keys().filter(|key| !filled.contains_key(key)) {
+            exception.removed.push(SlotRef { weekday: key.0, course_number: key.1 });
+        }
+        if !exception.is_empty() {
+            exceptions.insert(*week, exception);
+        }
+    }
+
+    CompactSchedule {
+        weeks: numbers,
+        courses,
+        shape,
+        template: template.into_iter().map(|(key, course)| slot(key, course)).collect(),
+        exceptions,
+    }
+}
+
+/// 紧凑格式解码为按周聚合的课表（与编码前完全相同）
+///
+/// 引用了不存在的课程下标的时段按没课处理
+pub fn decode(compact: &CompactSchedule) -> HashMap<u32, Vec<DayCourse>> {
+    let template: BTreeMap<SlotKey, usize> = compact
+        .template
+        .iter()
+        .map(|s| ((s.weekday, s.course_number), s.course))
+        .collect();
+
+    compact
+        .weeks
+        .iter()
+        .map(|week| {
+            let exception = compact.exceptions.get(week);
+            let mut filled = template.clone();
+            if let Some(exception) = exception {
+                for s in exception.added.iter().chain(&exception.changed) {
+                    filled.insert((s.weekday, s.course_number), s.course);
+                }
+                for r in &exception.removed {
+                    filled.remove(&(r.weekday, r.course_number));
+                }
+            }
+            let shape = exception.and_then(|e| e.shape.as_ref()).unwrap_or(&compact.shape);
+
+            let days = shape
+                .iter()
+                .map(|day| DayCourse {
+                    weekday: day.weekday,
+                    course: day
+                        .slots
+                        .iter()
+                        .map(|&n| CourseSlot {
+                            course_number: n,
+                            course_info: filled.get(&(day.weekday, n)).and_then(|&i| compact.courses.get(i)).cloned(),
+                        })
+                        .collect(),
+                })
+                .collect();
+            (*week, days)
+        })
+        .collect()
+}
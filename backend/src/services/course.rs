@@ -1,10 +1,13 @@
 use anyhow::Result;
 use reqwest::Client;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use utoipa::ToSchema;
 
-use crate::parser::course::get_all_courses as parser_get_all_courses;
-use crate::parser::schedule::{self, DayCourse, WeekInfo};
+use crate::parser::course::{get_all_courses_with_progress as parser_get_all_courses_with_progress, FetchedCourses};
+use crate::parser::schedule::{self, CourseInfo, DayCourse, WeekInfo};
 use crate::utils::config::AppConfig;
+use crate::utils::location::{current_locations, Location};
 
 /// 批量获取所有课程（业务层封装）
 /// parallel=true 并发；false 顺序
@@ -16,13 +19,30 @@ pub async fn get_all_courses(
     config: &AppConfig,
     parallel: bool,
 ) -> Result<HashMap<u32, Vec<DayCourse>>> {
+    let fetched = get_all_courses_with_progress(user_token, student_id, semester, client, config, parallel, |_, _| {}).await?;
+    Ok(fetched.weeks)
+}
+
+/// 批量获取所有课程，每获取完一周回调一次 `on_week(周号, 该周课程)`
+pub async fn get_all_courses_with_progress<F>(
+    user_token: &str,
+    student_id: &str,
+    semester: &[WeekInfo],
+    client: &Client,
+    config: &AppConfig,
+    parallel: bool,
+    mut on_week: F,
+) -> Result<FetchedCourses>
+where
+    F: FnMut(u32, &[DayCourse]),
+{
     if parallel {
-        return parser_get_all_courses(user_token, student_id, semester, client, config).await;
+        return parser_get_all_courses_with_progress(user_token, student_id, semester, client, config, on_week).await;
     }
     // 顺序请求（按周依次获取）
-    let mut courses_map = HashMap::new();
+    let mut fetched = FetchedCourses::default();
     for week in semester {
-        let data = schedule::get_week_course(
+        let data = schedule::get_week_course_checked(
             user_token,
             student_id,
             &week.start_time,
@@ -30,8 +50,205 @@ pub async fn get_all_courses(
             config,
         )
         .await?;
-        courses_map.insert(week.week, data);
+        on_week(week.week, &data.days);
+        if !data.diagnostics.is_empty() {
+            fetched.diagnostics.insert(week.week, data.diagnostics);
+        }
+        fetched.weeks.insert(week.week, data.days);
+    }
+    Ok(fetched)
+}
+
+/// 一节完整的课（连续节次已合并）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Lesson {
+    /// 星期几（1=周一，...，7=周日）
+    #[schema(example = 1)]
+    pub weekday: u32,
+    /// 起始节次
+    #[schema(example = 1)]
+    pub start: u32,
+    /// 结束节次（含）
+    #[schema(example = 2)]
+    pub end: u32,
+    /// 课程信息（取起始节次的数据）
+    pub course: CourseInfo,
+}
+
+/// 把一周的课程时段合并成完整的课
+///
+/// 上游有时只在起始节次给出课程并用 `continuous_course` 表示跨几节，
+/// 有时每一节都重复给出同一门课，这里两种情况都合并成一节课：
+/// 相邻节次课程代码和名称相同则合并，但不超过起始节次声明的连续节数；
+/// 合并后的结束节次至少覆盖到 `continuous_course` 声明的范围
+pub fn collect_lessons(days: &[DayCourse]) -> Vec<Lesson> {
+    let mut lessons = Vec::new();
+
+    for day in days {
+        let mut current: Option<Lesson> = None;
+
+        for slot in &day.course {
+            let Some(info) = &slot.course_info else {
+                if let Some(lesson) = current.take() {
+                    lessons.push(lesson);
+                }
+                continue;
+            };
+
+            if let Some(lesson) = current.as_mut() {
+                let declared = lesson.course.continuous_course;
+                let same_course = lesson.course.code == info.code && lesson.course.name == info.name;
+                let contiguous = slot.course_number == lesson.end + 1;
+                let within_span = declared == 0 || slot.course_number < lesson.start + declared;
+                if same_course && contiguous && within_span {
+                    lesson.end = slot.course_number;
+                    continue;
+                }
+            }
+
+            if let Some(lesson) = current.take() {
+                lessons.push(lesson);
+            }
+            current = Some(Lesson {
+                weekday: day.weekday,
+                start: slot.course_number,
+                end: slot.course_number,
+                course: info.clone(),
+            });
+        }
+
+        if let Some(lesson) = current.take() {
+            lessons.push(lesson);
+        }
+    }
+
+    for lesson in &mut lessons {
+        let declared_end = lesson.start + lesson.course.continuous_course.max(1) - 1;
+        lesson.end = lesson.end.max(declared_end);
     }
-    Ok(courses_map)
+
+    lessons.sort_by_key(|l| (l.weekday, l.start));
+    lessons
+}
+
+/// 冲突类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictKind {
+    /// 同一节次出现了两门不同的课
+    SameSlot,
+    /// 某门课的连续节次延伸到了另一门课的节次
+    SpanOverlap,
+}
+
+/// 一处上课冲突（两门课重叠的节次范围）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct LessonConflict {
+    #[schema(example = 6)]
+    pub week: u32,
+    /// 星期几（1=周一，...，7=周日）
+    #[schema(example = 2)]
+    pub weekday: u32,
+    /// 冲突的起始节次
+    #[schema(example = 3)]
+    pub start: u32,
+    /// 冲突的结束节次（含）
+    #[schema(example = 4)]
+    pub end: u32,
+    pub kind: ConflictKind,
+    /// 冲突的两门课（按起始节次排序）
+    pub courses: Vec<CourseInfo>,
+}
+
+/// 是否为同一门课（忽略节次、星期、颜色等位置和展示字段）
+fn same_course(a: &CourseInfo, b: &CourseInfo) -> bool {
+    a.code == b.code && a.name == b.name && a.class == b.class && a.classroom == b.classroom && a.teacher == b.teacher
+}
+
+/// 两门课声明的起始时段（记录自带的星期、节次字段）是否相同
+///
+/// 上游每个时段只能放一条记录，两门课排在同一节时其中一门会被挪到别的位置，
+/// 但记录里仍然声明原来的星期和节次，按位置比较发现不了
+fn same_declared_slot(a: &Lesson, b: &Lesson) -> bool {
+    a.course.course_number > 0
+        && a.course.weekday == b.course.weekday
+        && a.course.course_number == b.course.course_number
 }
 
+/// 检测一周内的上课冲突
+///
+/// 先用 [`collect_lessons`] 合并连续节次（按 `continuous_course` 延伸），
+/// 再两两比较不是同一门课的两节课：
+/// - 同一天从同一节开始，或记录声明的起始星期、节次相同，为 [`ConflictKind::SameSlot`]
+/// - 同一天节次范围重叠（前一门课的连续节次延伸到了后一门课），为 [`ConflictKind::SpanOverlap`]
+pub fn detect_week_conflicts(week: u32, days: &[DayCourse]) -> Vec<LessonConflict> {
+    let mut lessons: Vec<Lesson> = Vec::new();
+    for day in days {
+        for lesson in collect_lessons(std::slice::from_ref(day)) {
+            // 同一天重复出现的同一节课只算一次
+            let duplicate = lessons.iter().any(|l| {
+                l.weekday == lesson.weekday && l.start == lesson.start && l.end == lesson.end && same_course(&l.course, &lesson.course)
+            });
+            if !duplicate {
+                lessons.push(lesson);
+            }
+        }
+    }
+    lessons.sort_by_key(|l| (l.weekday, l.start, l.end));
+
+    let mut conflicts = Vec::new();
+    for (i, a) in lessons.iter().enumerate() {
+        for b in &lessons[i + 1..] {
+            if same_course(&a.course, &b.course) {
+                continue;
+            }
+            let (weekday, start, end, kind) = if b.weekday == a.weekday && b.start <= a.end {
+                let kind = if a.start == b.start { ConflictKind::SameSlot } else { ConflictKind::SpanOverlap };
+                (a.weekday, b.start, a.end.min(b.end), kind)
+            } else if same_declared_slot(a, b) {
+                let span = a.course.continuous_course.max(1).min(b.course.continuous_course.max(1));
+                let start = a.course.course_number;
+                (a.course.weekday, start, start + span - 1, ConflictKind::SameSlot)
+            } else {
+                continue;
+            };
+            conflicts.push(LessonConflict {
+                week,
+                weekday,
+                start,
+                end,
+                kind,
+                courses: vec![a.course.clone(), b.course.clone()],
+            });
+        }
+    }
+    conflicts.sort_by_key(|c| (c.weekday, c.start));
+    conflicts
+}
+
+/// 检测整个学期的上课冲突（按周次、星期、节次排序）
+pub fn detect_conflicts(weeks: &HashMap<u32, Vec<DayCourse>>) -> Vec<LessonConflict> {
+    let mut numbers: Vec<u32> = weeks.keys().copied().collect();
+    numbers.sort_unstable();
+    numbers
+        .into_iter()
+        .flat_map(|week| detect_week_conflicts(week, &weeks[&week]))
+        .collect()
+}
+
+/// 解析课表中出现的所有教室（原始教室字符串 -> 地点）
+pub fn collect_locations(weeks: &HashMap<u32, Vec<DayCourse>>) -> HashMap<String, Location> {
+    let parser = current_locations();
+    let mut locations = HashMap::new();
+    let classrooms = weeks
+        .values()
+        .flatten()
+        .flat_map(|day| &day.course)
+        .filter_map(|slot| slot.course_info.as_ref()?.classroom.as_deref());
+    for classroom in classrooms {
+        if !classroom.trim().is_empty() && !locations.contains_key(classroom) {
+            locations.insert(classroom.to_string(), parser.parse(classroom));
+        }
+    }
+    locations
+}
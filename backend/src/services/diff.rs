@@ -0,0 +1,260 @@
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, Set};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use crate::db::models::schedule_states;
+use crate::parser::schedule::{CourseInfo, DayCourse};
+use crate::services::course::{collect_lessons, Lesson};
+use crate::utils::time::now_millis;
+
+/// 课程所在的时段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct LessonSlot {
+    /// 星期几（1=周一，...，7=周日）
+    #[schema(example = 2)]
+    pub weekday: u32,
+    /// 起始节次
+    #[schema(example = 3)]
+    pub start: u32,
+    /// 结束节次（含）
+    #[schema(example = 4)]
+    pub end: u32,
+}
+
+impl From<&Lesson> for LessonSlot {
+    fn from(lesson: &Lesson) -> Self {
+        Self {
+            weekday: lesson.weekday,
+            start: lesson.start,
+            end: lesson.end,
+        }
+    }
+}
+
+/// 单条课表变动
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LessonChange {
+    /// 新增的课
+    Added { week: u32, slot: LessonSlot, course: CourseInfo },
+    /// 取消的课
+    Removed { week: u32, slot: LessonSlot, course: CourseInfo },
+    /// 调课（同一周内换了时间）
+    Moved { week: u32, from: LessonSlot, to: LessonSlot, course: CourseInfo },
+    /// 换教室
+    ClassroomChanged {
+        week: u32,
+        slot: LessonSlot,
+        course: CourseInfo,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    /// 换老师
+    TeacherChanged {
+        week: u32,
+        slot: LessonSlot,
+        course: CourseInfo,
+        old: Vec<String>,
+        new: Vec<String>,
+    },
+}
+
+/// 两个版本课表之间的差异
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ScheduleDiff {
+    /// 变动列表（按周号、星期、节次排序）
+    pub changes: Vec<LessonChange>,
+}
+
+impl ScheduleDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// 比较同一位置的课（未调课时）的教室和老师
+fn compare_details(week: u32, old: &Lesson, new: &Lesson, changes: &mut Vec<LessonChange>) {
+    if old.course.classroom != new.course.classroom {
+        changes.push(LessonChange::ClassroomChanged {
+            week,
+            slot: new.into(),
+            course: new.course.clone(),
+            old: old.course.classroom.clone(),
+            new: new.course.classroom.clone(),
+        });
+    }
+
+    let mut old_teachers = old.course.teacher.clone();
+    let mut new_teachers = new.course.teacher.clone();
+    old_teachers.sort();
+    new_teachers.sort();
+    if old_teachers != new_teachers {
+        changes.push(LessonChange::TeacherChanged {
+            week,
+            slot: new.into(),
+            course: new.course.clone(),
+            old: old.course.teacher.clone(),
+            new: new.course.teacher.clone(),
+        });
+    }
+}
+
+/// 比较一周内的课
+fn diff_week(week: u32, old_days: &[DayCourse], new_days: &[DayCourse], changes: &mut Vec<LessonChange>) {
+    let mut old_lessons = collect_lessons(old_days);
+    let mut new_lessons = collect_lessons(new_days);
+
+    // 1) 同一起始时段、同一门课：只比较教室/老师，节数变化视为调课
+    let mut i = 0;
+    while i < old_lessons.len() {
+        let old = &old_lessons[i];
+        let matched = new_lessons.iter().position(|n| {
+            n.weekday == old.weekday && n.start == old.start && n.course.code == old.course.code
+        });
+        if let Some(j) = matched {
+            let old = old_lessons.remove(i);
+            let new = new_lessons.remove(j);
+            if old.end != new.end {
+                changes.push(LessonChange::Moved {
+                    week,
+                    from: (&old).into(),
+                    to: (&new).into(),
+                    course: new.course.clone(),
+                });
+            }
+            compare_details(week, &old, &new, changes);
+        } else {
+            i += 1;
+        }
+    }
+
+    // 2) 剩下的同一门课按顺序配对，视为调课
+    let mut i = 0;
+    while i < old_lessons.len() {
+        let code = old_lessons[i].course.code.clone();
+        if let Some(j) = new_lessons.iter().position(|n| n.course.code == code) {
+            let old = old_lessons.remove(i);
+            let new = new_lessons.remove(j);
+            changes.push(LessonChange::Moved {
+                week,
+                from: (&old).into(),
+                to: (&new).into(),
+                course: new.course.clone(),
+            });
+            compare_details(week, &old, &new, changes);
+        } else {
+            i += 1;
+        }
+    }
+
+    // 3) 仍未配对的就是取消或新增
+    for old in old_lessons {
+        changes.push(LessonChange::Removed {
+            week,
+            slot: (&old).into(),
+            course: old.course,
+        });
+    }
+    for new in new_lessons {
+        changes.push(LessonChange::Added {
+            week,
+            slot: (&new).into(),
+            course: new.course,
+        });
+    }
+}
+
+/// 变动排序键（周号、星期、节次）
+fn change_order(change: &LessonChange) -> (u32, u32, u32) {
+    match change {
+        LessonChange::Added { week, slot, .. }
+        | LessonChange::Removed { week, slot, .. }
+        | LessonChange::ClassroomChanged { week, slot, .. }
+        | LessonChange::TeacherChanged { week, slot, .. } => (*week, slot.weekday, slot.start),
+        LessonChange::Moved { week, to, .. } => (*week, to.weekday, to.start),
+    }
+}
+
+/// 计算两个版本课表之间的语义差异
+///
+/// 新版本中缺失的周（通常是该周请求失败）不参与比较，避免误报整周停课；
+/// 旧版本中没有而新版本中有的周，其中的课都算新增
+pub fn diff_weeks(
+    old: &HashMap<u32, Vec<DayCourse>>,
+    new: &HashMap<u32, Vec<DayCourse>>,
+) -> ScheduleDiff {
+    let mut changes = Vec::new();
+
+    for (week, new_days) in new {
+        let old_days = old.get(week).map(|d| d.as_slice()).unwrap_or(&[]);
+        diff_week(*week, old_days, new_days, &mut changes);
+    }
+
+    changes.sort_by_key(change_order);
+    ScheduleDiff { changes }
+}
+
+/// 与上一次保存的课表比较，并保存本次课表
+///
+/// 返回 `None` 表示按首次获取处理（不算变动）：该用户此前没有保存过课表、保存的课表无法读取，
+/// 或者保存的课表属于另一个学期（学期切换后同一周号不是同一周，直接用本次课表替换）。
+/// 同一学期内本次缺失的周保留上一次的数据，避免某周请求失败后下次又被当成新增
+pub async fn record_and_diff(
+    db: &DatabaseConnection,
+    ucode_hash: &str,
+    school_year: &str,
+    semester: u32,
+    weeks: &HashMap<u32, Vec<DayCourse>>,
+) -> anyhow::Result<Option<ScheduleDiff>> {
+    let now = now_millis();
+    let previous = schedule_states::Entity::find_by_id(ucode_hash.to_string())
+        .one(db)
+        .await?;
+
+    let Some(previous) = previous else {
+        schedule_states::ActiveModel {
+            ucode_hash: Set(ucode_hash.to_string()),
+            school_year: Set(school_year.to_string()),
+            semester: Set(semester as i32),
+            weeks_json: Set(serde_json::to_string(weeks)?),
+            updated_at: Set(now),
+        }
+        .insert(db)
+        .await?;
+        return Ok(None);
+    };
+
+    let same_semester = previous.school_year == school_year && previous.semester == semester as i32;
+    let old_weeks = match serde_json::from_str::<HashMap<u32, Vec<DayCourse>>>(&previous.weeks_json) {
+        Ok(w) if same_semester => Some(w),
+        Ok(_) => {
+            tracing::info!(
+                "Schedule state moved from {} {} to {} {}, resetting",
+                previous.school_year,
+                previous.semester,
+                school_year,
+                semester
+            );
+            None
+        }
+        Err(e) => {
+            // 按首次获取处理，否则整张课表都会被当成新增推送出去
+            tracing::warn!("Stored schedule state is unreadable, resetting: {}", e);
+            None
+        }
+    };
+
+    let diff = old_weeks.as_ref().map(|old| diff_weeks(old, weeks));
+    let mut merged = old_weeks.unwrap_or_default();
+    merged.extend(weeks.iter().map(|(w, d)| (*w, d.clone())));
+
+    let mut active: schedule_states::ActiveModel = previous.into();
+    active.school_year = Set(school_year.to_string());
+    active.semester = Set(semester as i32);
+    active.weeks_json = Set(serde_json::to_string(&merged)?);
+    active.updated_at = Set(now);
+    active.update(db).await?;
+
+    Ok(diff)
+}
@@ -0,0 +1,141 @@
+use futures::{channel::mpsc, StreamExt};
+use sea_orm::{
+    sea_query::OnConflict, ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect, Set,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use utoipa::ToSchema;
+
+use crate::db::models::{schema_drift_events, schema_fingerprints};
+use crate::utils::drift::{self, DriftRecord, Fingerprint, SchemaDriftEvent, UpstreamEndpoint};
+use crate::utils::time::now_millis;
+
+/// 上游结构监测概览
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SchemaDriftOverview {
+    /// 各接口的结构指纹（接口名 -> 字段路径 -> 类型）
+    #[schema(value_type = Object)]
+    pub baselines: BTreeMap<String, Fingerprint>,
+    /// 已记录的漂移事件（最近出现的在前）
+    pub events: Vec<SchemaDriftEvent>,
+}
+
+fn to_event(model: schema_drift_events::Model) -> SchemaDriftEvent {
+    SchemaDriftEvent {
+        endpoint: model.endpoint,
+        fingerprint: model.fingerprint,
+        changes: serde_json::from_str(&model.changes_json).unwrap_or_default(),
+        sample: serde_json::from_str(&model.sample_json).unwrap_or_default(),
+        occurrences: model.occurrences,
+        first_seen_at: model.first_seen_at,
+        last_seen_at: model.last_seen_at,
+    }
+}
+
+/// 加载数据库中的指纹，并启动后台任务持久化之后的指纹与漂移事件
+pub async fn start(db: &DatabaseConnection) -> Result<usize, DbErr> {
+    let baselines: HashMap<String, Fingerprint> = schema_fingerprints::Entity::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .filter_map(|m| Some((m.endpoint, serde_json::from_str(&m.fingerprint_json).ok()?)))
+        .collect();
+    let count = baselines.len();
+    drift::set_baselines(baselines);
+
+    let (tx, mut rx) = mpsc::unbounded();
+    if drift::set_sink(tx) {
+        let db = db.clone();
+        tokio::spawn(async move {
+            while let Some(record) = rx.next().await {
+                if let Err(e) = persist(&db, record).await {
+                    tracing::error!("Failed to persist schema drift record: {}", e);
+                }
+            }
+        });
+    }
+    Ok(count)
+}
+
+/// 写入一条记录：指纹整体覆盖；漂移事件按（接口, 指纹哈希）合并，次数加一
+pub async fn persist(db: &DatabaseConnection, record: DriftRecord) -> Result<(), DbErr> {
+    let now = now_millis();
+    match record {
+        DriftRecord::Baseline { endpoint, fingerprint } => {
+            let model = schema_fingerprints::ActiveModel {
+                endpoint: Set(endpoint),
+                fingerprint_json: Set(serde_json::to_string(&fingerprint).unwrap_or_default()),
+                updated_at: Set(now),
+            };
+            schema_fingerprints::Entity::insert(model)
+                .on_conflict(
+                    OnConflict::column(schema_fingerprints::Column::Endpoint)
+                        .update_columns([
+                            schema_fingerprints::Column::FingerprintJson,
+                            schema_fingerprints::Column::UpdatedAt,
+                        ])
+                        .to_owned(),
+                )
+                .exec(db)
+                .await?;
+        }
+        DriftRecord::Drift(event) => {
+            let existing = schema_drift_events::Entity::find()
+                .filter(schema_drift_events::Column::Endpoint.eq(&event.endpoint))
+                .filter(schema_drift_events::Column::Fingerprint.eq(&event.fingerprint))
+                .one(db)
+                .await?;
+            match existing {
+                Some(model) => {
+                    let occurrences = model.occurrences + 1;
+                    let mut active: schema_drift_events::ActiveModel = model.into();
+                    active.occurrences = Set(occurrences);
+                    active.last_seen_at = Set(event.last_seen_at);
+                    active.update(db).await?;
+                }
+                None => {
+                    let active = schema_drift_events::ActiveModel {
+                        endpoint: Set(event.endpoint),
+                        fingerprint: Set(event.fingerprint),
+                        changes_json: Set(serde_json::to_string(&event.changes).unwrap_or_default()),
+                        sample_json: Set(event.sample.to_string()),
+                        occurrences: Set(1),
+                        first_seen_at: Set(event.first_seen_at),
+                        last_seen_at: Set(event.last_seen_at),
+                        ..Default::default()
+                    };
+                    active.insert(db).await?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 最近出现的漂移事件
+pub async fn list_events(db: &DatabaseConnection, limit: u64) -> Result<Vec<SchemaDriftEvent>, DbErr> {
+    let events = schema_drift_events::Entity::find()
+        .order_by_desc(schema_drift_events::Column::LastSeenAt)
+        .limit(limit)
+        .all(db)
+        .await?;
+    Ok(events.into_iter().map(to_event).collect())
+}
+
+/// 指纹与最近的漂移事件
+pub async fn overview(db: &DatabaseConnection, limit: u64) -> Result<SchemaDriftOverview, DbErr> {
+    Ok(SchemaDriftOverview {
+        baselines: drift::current_baselines(),
+        events: list_events(db, limit).await?,
+    })
+}
+
+/// 确认上游变化：删除接口的指纹，下一次响应重新学习（已记录的事件保留）
+pub async fn reset_baseline(db: &DatabaseConnection, endpoint: UpstreamEndpoint) -> Result<bool, DbErr> {
+    let in_memory = drift::clear_baseline(endpoint);
+    let deleted = schema_fingerprints::Entity::delete_by_id(endpoint.name().to_string())
+        .exec(db)
+        .await?;
+    Ok(in_memory || deleted.rows_affected > 0)
+}
@@ -1,3 +1,16 @@
+pub mod api_key;
+pub mod calendar;
+pub mod compact;
 pub mod course;
+pub mod diff;
+pub mod drift;
+pub mod occurrence;
+pub mod schedule;
+pub mod search;
+pub mod session;
+pub mod snapshot;
 pub mod stats;
-
+pub mod summary;
+pub mod teacher;
+pub mod time_table;
+pub mod webhook;
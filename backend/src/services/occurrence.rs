@@ -0,0 +1,153 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use crate::parser::schedule::{CourseInfo, DayCourse, WeekInfo};
+use crate::services::calendar::{day_schedule, parse_date, TimedLesson};
+use crate::utils::holiday::SpecialDay;
+use crate::utils::location::Location;
+use crate::utils::schedule::tz_east8;
+
+/// 一次具体的上课（连续节次已合并）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LessonOccurrence {
+    /// 稳定 ID：`课程代码-周次-星期-起始节次`，同一课表重复展开时不变
+    #[schema(example = "MATH101-6-2-1")]
+    pub id: String,
+    /// 上课日期（YYYY-MM-DD）
+    #[schema(example = "2024-10-08")]
+    pub date: String,
+    #[schema(example = 6)]
+    pub week: u32,
+    /// 星期几（1=周一，...，7=周日）
+    #[schema(example = 2)]
+    pub weekday: u32,
+    /// 起始节次
+    #[schema(example = 1)]
+    pub start_slot: u32,
+    /// 结束节次（含）
+    #[schema(example = 2)]
+    pub end_slot: u32,
+    /// 上课时刻（ISO 8601，东八区）
+    #[schema(example = "2024-10-08T08:00:00+08:00")]
+    pub start: String,
+    /// 下课时刻（ISO 8601，东八区）
+    #[schema(example = "2024-10-08T09:40:00+08:00")]
+    pub end: String,
+    /// 是否因放假停上
+    pub cancelled: bool,
+    /// 节假日或调休说明
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub special: Option<SpecialDay>,
+    pub course: CourseInfo,
+    /// 解析后的上课地点（没有教室时为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<Location>,
+}
+
+impl LessonOccurrence {
+    /// 上课时刻
+    pub fn starts_at(&self) -> Option<DateTime<FixedOffset>> {
+        DateTime::parse_from_rfc3339(&self.start).ok()
+    }
+
+    /// 转回带时间的一节课
+    pub fn to_timed_lesson(&self) -> TimedLesson {
+        let time_of = |s: &str| DateTime::parse_from_rfc3339(s).ok().map(|t| t.format("%H:%M").to_string());
+        TimedLesson {
+            start: self.start_slot,
+            end: self.end_slot,
+            start_time: time_of(&self.start),
+            end_time: time_of(&self.end),
+            course: self.course.clone(),
+            location: self.location.clone(),
+        }
+    }
+}
+
+/// 展开选项
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OccurrenceRange {
+    /// 起始日期（含），不传则从学期第一天开始
+    pub from: Option<NaiveDate>,
+    /// 结束日期（含），不传则到学期最后一天
+    pub to: Option<NaiveDate>,
+    /// 是否包含因放假停上的课
+    pub include_cancelled: bool,
+}
+
+/// 同一门课的归并键（课程代码为空时使用课程名）
+pub fn course_key<'a>(code: &'a str, name: &'a str) -> &'a str {
+    let code = code.trim();
+    if code.is_empty() { name.trim() } else { code }
+}
+
+/// 稳定 ID（课程代码为空时使用课程名）
+pub fn occurrence_id(course: &CourseInfo, week: u32, weekday: u32, slot: u32) -> String {
+    format!("{}-{}-{}-{}", course_key(&course.code, &course.name), week, weekday, slot)
+}
+
+/// 日期 + HH:MM 转为东八区 ISO 8601
+fn east8_rfc3339(date: NaiveDate, time: Option<&str>) -> Option<String> {
+    let time = NaiveTime::parse_from_str(time?, "%H:%M").ok()?;
+    tz_east8()
+        .from_local_datetime(&date.and_time(time))
+        .single()
+        .map(|dt| dt.to_rfc3339())
+}
+
+/// 将按周聚合的课表展开为具体的上课列表（按上课时刻排序）
+///
+/// 逐日使用 [`day_schedule`]，因此节假日、调休和时间表版本都已生效；
+/// 超出作息表节次、无法确定时间的课会被跳过
+pub fn expand_occurrences(
+    weeks: &HashMap<u32, Vec<DayCourse>>,
+    semester_weeks: &[WeekInfo],
+    range: OccurrenceRange,
+) -> Vec<LessonOccurrence> {
+    let first = semester_weeks.iter().filter_map(|w| parse_date(&w.start_time)).min();
+    let last = semester_weeks.iter().filter_map(|w| parse_date(&w.end_time)).max();
+    let (Some(first), Some(last)) = (first, last) else {
+        return Vec::new();
+    };
+    let from = range.from.map_or(first, |d| d.max(first));
+    let to = range.to.map_or(last, |d| d.min(last));
+
+    let mut occurrences = Vec::new();
+    for date in from.iter_days().take_while(|d| *d <= to) {
+        let day = day_schedule(weeks, semester_weeks, date);
+        let Some(week) = day.week else {
+            continue;
+        };
+
+        let cancelled = if range.include_cancelled { day.cancelled.as_slice() } else { &[] };
+        let lessons = day.lessons.iter().map(|l| (l, false)).chain(cancelled.iter().map(|l| (l, true)));
+        for (lesson, cancelled) in lessons {
+            let (Some(start), Some(end)) = (
+                east8_rfc3339(date, lesson.start_time.as_deref()),
+                east8_rfc3339(date, lesson.end_time.as_deref()),
+            ) else {
+                continue;
+            };
+            occurrences.push(LessonOccurrence {
+                id: occurrence_id(&lesson.course, week, day.weekday, lesson.start),
+                date: day.date.clone(),
+                week,
+                weekday: day.weekday,
+                start_slot: lesson.start,
+                end_slot: lesson.end,
+                start,
+                end,
+                cancelled,
+                special: day.special.clone(),
+                course: lesson.course.clone(),
+                location: lesson.location.clone(),
+            });
+        }
+    }
+
+    // 时刻都是东八区的 RFC 3339，可以直接按字符串排序
+    occurrences.sort_by(|a, b| a.start.cmp(&b.start));
+    occurrences
+}
@@ -0,0 +1,175 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use utoipa::ToSchema;
+
+use crate::parser::auth::{self, UserInfo};
+use crate::parser::schedule::{self, CourseParseDiagnostic, DayCourse, SchoolYear, WeekInfo};
+use crate::services::course as course_service;
+use crate::utils::{config::AppConfig, http::create_http_client};
+
+/// 获取课表过程中的进度事件
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ScheduleEvent {
+    /// 已通过学校接口认证
+    Authenticated {
+        #[schema(example = "245800001")]
+        student_id: String,
+    },
+    /// 已定位当前学期
+    SchoolYearFound {
+        #[schema(example = "2024-2025")]
+        school_year: String,
+        #[schema(example = 1)]
+        semester: u32,
+    },
+    /// 已获取学期周列表
+    SemesterWeeks { weeks: Vec<WeekInfo> },
+    /// 某一周的课程已获取（按完成顺序，不保证周号顺序）
+    Week {
+        week: u32,
+        days: Vec<DayCourse>,
+        /// 已完成周数
+        completed: usize,
+        /// 总周数
+        total: usize,
+    },
+}
+
+impl ScheduleEvent {
+    /// 事件名（用作 SSE 的 `event:` 字段）
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScheduleEvent::Authenticated { .. } => "authenticated",
+            ScheduleEvent::SchoolYearFound { .. } => "school_year_found",
+            ScheduleEvent::SemesterWeeks { .. } => "semester_weeks",
+            ScheduleEvent::Week { .. } => "week",
+        }
+    }
+}
+
+/// 获取课表失败的原因
+#[derive(Debug, thiserror::Error)]
+pub enum ScheduleFetchError {
+    #[error("Create client failed: {0}")]
+    Client(anyhow::Error),
+    #[error("Get user info failed: {0}")]
+    UserInfo(anyhow::Error),
+    #[error("Get school year failed: {0}")]
+    SchoolYear(anyhow::Error),
+    #[error("No current semester found")]
+    NoCurrentSemester,
+    #[error("Get semester failed: {0}")]
+    Semester(anyhow::Error),
+    #[error("Get all courses failed: {0}")]
+    Courses(anyhow::Error),
+}
+
+impl ScheduleFetchError {
+    /// 对应的 HTTP 状态码
+    pub fn status_code(&self) -> u16 {
+        match self {
+            ScheduleFetchError::NoCurrentSemester => 404,
+            _ => 500,
+        }
+    }
+}
+
+/// 一次完整获取的结果
+#[derive(Debug, Clone)]
+pub struct FetchedSchedule {
+    pub user: UserInfo,
+    /// 当前学期
+    pub semester: SchoolYear,
+    /// 学期周列表
+    pub semester_weeks: Vec<WeekInfo>,
+    /// 课表数据（每周按 weekday 排好序）
+    pub weeks: HashMap<u32, Vec<DayCourse>>,
+    /// 解析失败的课程记录，按周号聚合
+    pub diagnostics: BTreeMap<u32, Vec<CourseParseDiagnostic>>,
+}
+
+/// 从学校服务器获取当前学期课表
+///
+/// 流程：获取用户信息 → 获取学年并定位当前学期 → 获取学期周列表 → 获取每周课程。
+/// 每完成一步调用一次 `on_event`，供流式接口推送进度
+pub async fn fetch_schedule<F>(
+    ucode: &str,
+    config: &AppConfig,
+    parallel: bool,
+    mut on_event: F,
+) -> Result<FetchedSchedule, ScheduleFetchError>
+where
+    F: FnMut(ScheduleEvent),
+{
+    let client = create_http_client().await.map_err(ScheduleFetchError::Client)?;
+
+    // 1) 获取用户信息
+    let user = auth::get_user_info(ucode, &client, config)
+        .await
+        .map_err(ScheduleFetchError::UserInfo)?;
+    on_event(ScheduleEvent::Authenticated {
+        student_id: user.student_id.clone(),
+    });
+
+    // 2) 获取学年，定位当前学期
+    let years = schedule::get_school_year(&user.access_token, &client, config)
+        .await
+        .map_err(ScheduleFetchError::SchoolYear)?;
+    let semester = years
+        .into_iter()
+        .rfind(|y| y.is_current_semester)
+        .ok_or(ScheduleFetchError::NoCurrentSemester)?;
+    on_event(ScheduleEvent::SchoolYearFound {
+        school_year: semester.school_year.clone(),
+        semester: semester.semester,
+    });
+
+    // 3) 获取学期周信息
+    let semester_weeks = schedule::get_semester(
+        &user.access_token,
+        &semester.school_year,
+        &semester.semester.to_string(),
+        &client,
+        config,
+    )
+    .await
+    .map_err(ScheduleFetchError::Semester)?;
+    on_event(ScheduleEvent::SemesterWeeks {
+        weeks: semester_weeks.clone(),
+    });
+
+    // 4) 获取所有周课程（支持并行/顺序）
+    let total = semester_weeks.len();
+    let mut completed = 0;
+    let courses = course_service::get_all_courses_with_progress(
+        &user.access_token,
+        &user.student_id,
+        &semester_weeks,
+        &client,
+        config,
+        parallel,
+        |week, days| {
+            completed += 1;
+            let mut days = days.to_vec();
+            days.sort_by_key(|dc| dc.weekday);
+            on_event(ScheduleEvent::Week { week, days, completed, total });
+        },
+    )
+    .await
+    .map_err(ScheduleFetchError::Courses)?;
+    let mut weeks = courses.weeks;
+
+    // 5) 对每周的课程按 weekday 排序
+    for day_courses in weeks.values_mut() {
+        day_courses.sort_by_key(|dc| dc.weekday);
+    }
+
+    Ok(FetchedSchedule {
+        user,
+        semester,
+        semester_weeks,
+        weeks,
+        diagnostics: courses.diagnostics,
+    })
+}
@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use crate::parser::schedule::{normalize_teachers, CourseInfo, DayCourse, WeekInfo};
+use crate::services::occurrence::{expand_occurrences, LessonOccurrence, OccurrenceRange};
+use crate::utils::pinyin::{self, PinyinMatch};
+
+/// 搜索关键字的最大长度（字符数）
+pub const MAX_QUERY_CHARS: usize = 32;
+
+/// 命中的字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchField {
+    Name,
+    Code,
+    Teacher,
+    Classroom,
+    Class,
+}
+
+/// 匹配方式（按相关度从高到低）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchKind {
+    /// 完全相同（忽略大小写和空白）
+    Exact,
+    /// 包含关键字
+    Substring,
+    /// 拼音全拼
+    Pinyin,
+    /// 拼音首字母或简拼（如 gdsx）
+    PinyinInitials,
+    /// 模糊匹配：按顺序包含关键字的每个字（如 “高数”）、拼音简称（如 gaoshu），或有一两个字输错
+    Fuzzy,
+}
+
+/// 一门课最相关的命中
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SearchMatch {
+    pub field: SearchField,
+    pub kind: MatchKind,
+    /// 命中的字段值
+    #[schema(example = "高等数学")]
+    pub value: String,
+}
+
+/// 一次命中的上课
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SearchHit {
+    pub occurrence: LessonOccurrence,
+    pub matched: SearchMatch,
+}
+
+/// 搜索结果
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SearchResults {
+    #[schema(example = "gdsx")]
+    pub query: String,
+    /// 命中的上课总数（截断前）
+    #[schema(example = 32)]
+    pub total: usize,
+    /// 按相关度、上课时刻排序
+    pub hits: Vec<SearchHit>,
+}
+
+/// 忽略大小写和空白
+fn normalize(text: &str) -> String {
+    text.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase).collect()
+}
+
+/// 关键字的每个字是否按顺序出现在文本中
+fn is_subsequence(query: &[char], text: &[char]) -> bool {
+    let mut rest = text.iter();
+    query.iter().all(|q| rest.any(|c| c == q))
+}
+
+/// 允许的输错字数：3 个字以内不允许，6 个字以内 1 个，更长 2 个
+fn typo_budget(len: usize) -> usize {
+    match len {
+        0..=3 => 0,
+        4..=6 => 1,
+        _ => 2,
+    }
+}
+
+/// 文本中是否有一段与关键字只差几个字
+fn within_typos(query: &[char], text: &[char]) -> bool {
+    let budget = typo_budget(query.len());
+    if budget == 0 || text.is_empty() {
+        return false;
+    }
+    let query: String = query.iter().collect();
+    let width = query.chars().count().min(text.len());
+    text.windows(width)
+        .any(|window| strsim::levenshtein(&query, &window.iter().collect::<String>()) <= budget)
+}
+
+/// 关键字与一个字段值的匹配方式
+pub fn match_text(query: &str, text: &str) -> Option<MatchKind> {
+    let (q, t) = (normalize(query), normalize(text));
+    if q.is_empty() || t.is_empty() {
+        return None;
+    }
+    if q == t {
+        return Some(MatchKind::Exact);
+    }
+    if t.contains(&q) {
+        return Some(MatchKind::Substring);
+    }
+    match pinyin::match_pinyin(&q, text) {
+        Some(PinyinMatch::Full) => return Some(MatchKind::Pinyin),
+        Some(PinyinMatch::Initials) => return Some(MatchKind::PinyinInitials),
+        None => {}
+    }
+
+    let q_chars: Vec<char> = q.chars().collect();
+    let t_chars: Vec<char> = t.chars().collect();
+    // 字母数字的关键字太短时按顺序包含几乎总能成立
+    let min_len = if q.is_ascii() { 3 } else { 2 };
+    if q_chars.len() >= min_len && is_subsequence(&q_chars, &t_chars) {
+        return Some(MatchKind::Fuzzy);
+    }
+    // 拼音简称（如 gaoshu）
+    if q_chars.len() >= 3 && pinyin::match_pinyin_skipping(&q, text) {
+        return Some(MatchKind::Fuzzy);
+    }
+    // 拼音输错几个字母（如 gaodengshuxeu）
+    let spelled: Vec<char> = if q.is_ascii() {
+        pinyin::syllables(text).concat().chars().collect()
+    } else {
+        t_chars
+    };
+    within_typos(&q_chars, &spelled).then_some(MatchKind::Fuzzy)
+}
+
+/// 一门课各字段中最相关的命中（同等相关度时按课程名、代码、教师、教室、班级的顺序）
+pub fn match_course(query: &str, course: &CourseInfo) -> Option<SearchMatch> {
+    let mut fields: Vec<(SearchField, String)> = vec![
+        (SearchField::Name, course.name.clone()),
+        (SearchField::Code, course.code.clone()),
+    ];
+    fields.extend(
+        normalize_teachers(&course.teacher.join(";"))
+            .into_iter()
+            .map(|t| (SearchField::Teacher, t)),
+    );
+    fields.extend(course.classroom.clone().map(|c| (SearchField::Classroom, c)));
+    fields.push((SearchField::Class, course.class.clone()));
+
+    fields
+        .into_iter()
+        .filter_map(|(field, value)| Some(SearchMatch { field, kind: match_text(query, &value)?, value }))
+        .min_by_key(|m| m.kind)
+}
+
+/// 在展开后的上课列表中搜索
+///
+/// 同一门课只匹配一次，命中的课的每次上课都会返回；结果按相关度、上课时刻排序，最多 `limit` 条
+pub fn search(
+    weeks: &HashMap<u32, Vec<DayCourse>>,
+    semester_weeks: &[WeekInfo],
+    query: &str,
+    range: OccurrenceRange,
+    limit: usize,
+) -> SearchResults {
+    let mut matched: HashMap<String, Option<SearchMatch>> = HashMap::new();
+    let mut hits: Vec<SearchHit> = expand_occurrences(weeks, semester_weeks, range)
+        .into_iter()
+        .filter_map(|occurrence| {
+            let c = &occurrence.course;
+            let key = format!("{}|{}|{}|{}|{:?}", c.code, c.name, c.class, c.teacher.join(";"), c.classroom);
+            let found = matched.entry(key).or_insert_with(|| match_course(query, c)).clone()?;
+            Some(SearchHit { occurrence, matched: found })
+        })
+        .collect();
+
+    // 上课列表已按时刻排序，稳定排序保持同一相关度内的时间顺序
+    hits.sort_by_key(|hit| hit.matched.kind);
+    let total = hits.len();
+    hits.truncate(limit);
+    SearchResults { query: query.to_string(), total, hits }
+}
@@ -0,0 +1,141 @@
+use reqwest::Client;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, DbErr, EntityTrait, Set};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::db::models::sessions;
+use crate::parser::auth;
+use crate::utils::config::AppConfig;
+use crate::utils::crypto::{decrypt_with_secret, encrypt_with_secret, hash_ucode, random_token};
+use crate::utils::time::now_millis;
+use crate::utils::token::{self, SessionClaims, TokenError};
+
+/// 已解析的会话（服务端解密出的 UCode 只在处理请求期间存在于内存）
+#[derive(Debug, Clone)]
+pub struct SessionContext {
+    pub session_id: String,
+    pub ucode: String,
+    pub ucode_hash: String,
+}
+
+/// 登录结果
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LoginResponse {
+    /// 会话令牌，之后放在 `Authorization: Bearer <token>` 头中
+    pub token: String,
+    /// 令牌类型
+    #[schema(example = "Bearer")]
+    pub token_type: String,
+    /// 过期时间（毫秒时间戳）
+    #[schema(example = "1704672000000")]
+    pub expires_at: i64,
+    /// 学号
+    #[schema(example = "245800001")]
+    pub student_id: String,
+    /// 真实姓名
+    #[schema(example = "张三")]
+    pub student_realname: String,
+}
+
+/// 会话错误
+#[derive(Debug, thiserror::Error)]
+pub enum SessionError {
+    #[error("Invalid session token: {0}")]
+    Token(#[from] TokenError),
+    #[error("Session not found or revoked")]
+    Revoked,
+    #[error("Session has expired")]
+    Expired,
+    #[error("Failed to restore session")]
+    Corrupted,
+    #[error(transparent)]
+    Db(#[from] DbErr),
+}
+
+/// 登录：通过学校接口校验 UCode，保存加密后的 UCode 并签发会话令牌
+pub async fn login(
+    db: &DatabaseConnection,
+    ucode: &str,
+    client: &Client,
+    config: &AppConfig,
+) -> anyhow::Result<LoginResponse> {
+    let user = auth::get_user_info(ucode, client, config).await?;
+
+    let now = now_millis();
+    let expires_at = now + config.session_ttl_hours * 60 * 60 * 1000;
+    let session_id = random_token(16);
+    let ucode_hash = hash_ucode(ucode);
+
+    sessions::ActiveModel {
+        id: Set(session_id.clone()),
+        ucode_hash: Set(ucode_hash.clone()),
+        encrypted_ucode: Set(encrypt_with_secret(ucode, &config.session_secret)?),
+        created_at: Set(now),
+        expires_at: Set(expires_at),
+        revoked: Set(false),
+        last_used_at: Set(now),
+    }
+    .insert(db)
+    .await?;
+
+    let claims = SessionClaims {
+        sub: ucode_hash,
+        sid: session_id,
+        iat: now / 1000,
+        exp: expires_at / 1000,
+    };
+
+    Ok(LoginResponse {
+        token: token::sign(&claims, &config.session_secret),
+        token_type: "Bearer".to_string(),
+        expires_at,
+        student_id: user.student_id,
+        student_realname: user.student_realname,
+    })
+}
+
+/// 校验会话令牌并取回服务端保存的 UCode
+pub async fn resolve(
+    db: &DatabaseConnection,
+    token_str: &str,
+    config: &AppConfig,
+) -> Result<SessionContext, SessionError> {
+    let claims = token::verify(token_str, &config.session_secret)?;
+
+    let session = sessions::Entity::find_by_id(claims.sid.clone())
+        .one(db)
+        .await?
+        .filter(|s| !s.revoked && s.ucode_hash == claims.sub)
+        .ok_or(SessionError::Revoked)?;
+
+    let now = now_millis();
+    if session.expires_at <= now {
+        return Err(SessionError::Expired);
+    }
+
+    let ucode = decrypt_with_secret(&session.encrypted_ucode, &config.session_secret)
+        .map_err(|_| SessionError::Corrupted)?;
+
+    let context = SessionContext {
+        session_id: session.id.clone(),
+        ucode,
+        ucode_hash: session.ucode_hash.clone(),
+    };
+
+    let mut active: sessions::ActiveModel = session.into();
+    active.last_used_at = Set(now);
+    active.update(db).await?;
+
+    Ok(context)
+}
+
+/// 注销会话，返回是否找到该会话
+pub async fn logout(db: &DatabaseConnection, session_id: &str) -> Result<bool, DbErr> {
+    let Some(session) = sessions::Entity::find_by_id(session_id.to_string()).one(db).await? else {
+        return Ok(false);
+    };
+    let mut active: sessions::ActiveModel = session.into();
+    active.revoked = Set(true);
+    active.update(db).await?;
+    Ok(true)
+}
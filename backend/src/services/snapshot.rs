@@ -0,0 +1,185 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use utoipa::ToSchema;
+
+use crate::db::models::schedule_snapshots;
+use crate::parser::schedule::DayCourse;
+use crate::utils::crypto::sha256_hex;
+use crate::utils::schedule::tz_east8;
+use crate::utils::time::now_millis;
+
+/// 快照元数据（不含课表内容）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SnapshotInfo {
+    pub id: i32,
+    /// 课表内容哈希（相同内容的快照哈希相同）
+    #[schema(example = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08")]
+    pub content_hash: String,
+    /// 学年
+    #[schema(example = "2024-2025")]
+    pub school_year: String,
+    /// 学期
+    #[schema(example = 1)]
+    pub semester: u32,
+    /// 包含的周数
+    #[schema(example = 20)]
+    pub weeks: usize,
+    /// 首次获取到该版本的时间（毫秒时间戳）
+    pub first_seen_at: i64,
+    /// 最近一次获取到相同内容的时间（毫秒时间戳）
+    pub last_seen_at: i64,
+    /// 获取到相同内容的次数
+    pub fetch_count: i32,
+}
+
+/// 完整快照
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ScheduleSnapshot {
+    pub info: SnapshotInfo,
+    /// 当时获取到的课表（周号 -> 每天课程）
+    pub weeks: HashMap<u32, Vec<DayCourse>>,
+}
+
+/// 按周号排序后序列化，保证相同课表得到相同的 JSON 和哈希
+fn canonical_json(weeks: &HashMap<u32, Vec<DayCourse>>) -> serde_json::Result<String> {
+    let ordered: BTreeMap<&u32, &Vec<DayCourse>> = weeks.iter().collect();
+    serde_json::to_string(&ordered)
+}
+
+/// 计算课表内容哈希
+pub fn content_hash(weeks: &HashMap<u32, Vec<DayCourse>>) -> serde_json::Result<String> {
+    let json = canonical_json(weeks)?;
+    Ok(sha256_hex(&json))
+}
+
+fn to_info(model: &schedule_snapshots::Model) -> SnapshotInfo {
+    let weeks = serde_json::from_str::<HashMap<u32, serde_json::Value>>(&model.weeks_json)
+        .map(|w| w.len())
+        .unwrap_or(0);
+    SnapshotInfo {
+        id: model.id,
+        content_hash: model.content_hash.clone(),
+        school_year: model.school_year.clone(),
+        semester: model.semester as u32,
+        weeks,
+        first_seen_at: model.first_seen_at,
+        last_seen_at: model.last_seen_at,
+        fetch_count: model.fetch_count,
+    }
+}
+
+/// 保存一次获取结果
+///
+/// 与该用户最新的快照内容相同时只更新最近获取时间和次数，否则新增一个版本。
+/// 只和最新版本比较：课表改回旧内容时也会记为新版本，这样按时间查询才能得到当时的课表
+pub async fn record_snapshot(
+    db: &DatabaseConnection,
+    ucode_hash: &str,
+    school_year: &str,
+    semester: u32,
+    weeks: &HashMap<u32, Vec<DayCourse>>,
+) -> anyhow::Result<SnapshotInfo> {
+    let now = now_millis();
+    let weeks_json = canonical_json(weeks)?;
+    let hash = content_hash(weeks)?;
+
+    let latest = schedule_snapshots::Entity::find()
+        .filter(schedule_snapshots::Column::UcodeHash.eq(ucode_hash))
+        .order_by_desc(schedule_snapshots::Column::FirstSeenAt)
+        .order_by_desc(schedule_snapshots::Column::Id)
+        .one(db)
+        .await?;
+
+    if let Some(latest) = latest.filter(|s| {
+        s.content_hash == hash && s.school_year == school_year && s.semester == semester as i32
+    }) {
+        let fetch_count = latest.fetch_count + 1;
+        let mut active: schedule_snapshots::ActiveModel = latest.into();
+        active.last_seen_at = Set(now);
+        active.fetch_count = Set(fetch_count);
+        let updated = active.update(db).await?;
+        return Ok(to_info(&updated));
+    }
+
+    let model = schedule_snapshots::ActiveModel {
+        ucode_hash: Set(ucode_hash.to_string()),
+        content_hash: Set(hash),
+        school_year: Set(school_year.to_string()),
+        semester: Set(semester as i32),
+        weeks_json: Set(weeks_json),
+        first_seen_at: Set(now),
+        last_seen_at: Set(now),
+        fetch_count: Set(1),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    Ok(to_info(&model))
+}
+
+/// 列出用户的所有快照版本（新的在前）
+pub async fn list_snapshots(
+    db: &DatabaseConnection,
+    ucode_hash: &str,
+    limit: u64,
+) -> Result<Vec<SnapshotInfo>, DbErr> {
+    let snapshots = schedule_snapshots::Entity::find()
+        .filter(schedule_snapshots::Column::UcodeHash.eq(ucode_hash))
+        .order_by_desc(schedule_snapshots::Column::FirstSeenAt)
+        .order_by_desc(schedule_snapshots::Column::Id)
+        .limit(limit)
+        .all(db)
+        .await?;
+    Ok(snapshots.iter().map(to_info).collect())
+}
+
+/// 查询某一时刻生效的快照（该时刻之前最后获取到的版本）
+pub async fn get_snapshot_as_of(
+    db: &DatabaseConnection,
+    ucode_hash: &str,
+    as_of: i64,
+) -> anyhow::Result<Option<ScheduleSnapshot>> {
+    let snapshot = schedule_snapshots::Entity::find()
+        .filter(schedule_snapshots::Column::UcodeHash.eq(ucode_hash))
+        .filter(schedule_snapshots::Column::FirstSeenAt.lte(as_of))
+        .order_by_desc(schedule_snapshots::Column::FirstSeenAt)
+        .order_by_desc(schedule_snapshots::Column::Id)
+        .one(db)
+        .await?;
+
+    let Some(snapshot) = snapshot else {
+        return Ok(None);
+    };
+    Ok(Some(ScheduleSnapshot {
+        info: to_info(&snapshot),
+        weeks: serde_json::from_str(&snapshot.weeks_json)?,
+    }))
+}
+
+/// 解析 `as_of` 参数，返回毫秒时间戳
+///
+/// 支持 13 位毫秒时间戳、RFC 3339 时间、`YYYY-MM-DDTHH:MM:SS`（东八区）
+/// 以及 `YYYY-MM-DD`（东八区当天结束时）。其他位数的纯数字（如 `20241008`）不当作时间戳，返回 `None`
+pub fn parse_as_of(raw: &str) -> Option<i64> {
+    let raw = raw.trim();
+    if raw.len() == 13 && raw.bytes().all(|b| b.is_ascii_digit()) {
+        return raw.parse().ok();
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(raw) {
+        return Some(dt.timestamp_millis());
+    }
+    for fmt in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(raw, fmt) {
+            return tz_east8().from_local_datetime(&naive).single().map(|dt| dt.timestamp_millis());
+        }
+    }
+    let date = NaiveDate::parse_from_str(raw, "%Y-%m-%d").ok()?;
+    let end_of_day = date.and_time(NaiveTime::from_hms_milli_opt(23, 59, 59, 999)?);
+    tz_east8().from_local_datetime(&end_of_day).single().map(|dt| dt.timestamp_millis())
+}
@@ -1,19 +1,12 @@
 use anyhow::Result;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set,
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseBackend, DatabaseConnection, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set, Statement,
 };
-use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::db::models::{access_stats, request_logs, user_visits};
 use crate::utils::crypto::{encrypt_student_id, hash_ucode};
-
-/// 获取当前时间戳（毫秒）
-fn current_timestamp_millis() -> i64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_millis() as i64
-}
+use crate::utils::time::now_millis;
 
 /// 记录请求日志
 pub async fn log_request(
@@ -21,8 +14,9 @@ pub async fn log_request(
     token: &str,
     student_id: &str,
     duration_ms: i64,
+    api_key_id: Option<i32>,
 ) -> Result<()> {
-    let timestamp = current_timestamp_millis();
+    let timestamp = now_millis();
     
     // 使用 timestamp 加密学号
     let encrypted_student_id = encrypt_student_id(student_id, timestamp)?;
@@ -33,6 +27,7 @@ pub async fn log_request(
         token: Set(token.to_string()),
         encrypted_student_id: Set(encrypted_student_id),
         created_at: Set(timestamp),
+        api_key_id: Set(api_key_id),
         ..Default::default()
     };
     
@@ -42,7 +37,7 @@ pub async fn log_request(
 
 /// 更新访问统计
 pub async fn update_stats(db: &DatabaseConnection, ucode: &str) -> Result<()> {
-    let timestamp = current_timestamp_millis();
+    let timestamp = now_millis();
     let ucode_hash = hash_ucode(ucode);
     
     // 检查用户是否首次访问
@@ -90,6 +85,23 @@ pub async fn update_stats(db: &DatabaseConnection, ucode: &str) -> Result<()> {
     Ok(())
 }
 
+/// 累加课程解析失败次数（上游格式变动时会明显上升）
+pub async fn record_parse_failures(db: &DatabaseConnection, count: usize) -> Result<()> {
+    if count == 0 {
+        return Ok(());
+    }
+
+    // 在数据库里直接累加，并发请求不会互相覆盖
+    db.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Sqlite,
+        "UPDATE access_stats SET parse_failures = parse_failures + ?, last_updated_at = ? WHERE id = 1",
+        [(count as i64).into(), now_millis().into()],
+    ))
+    .await?;
+
+    Ok(())
+}
+
 /// 获取统计信息
 pub async fn get_stats(db: &DatabaseConnection) -> Result<StatsResponse> {
     let stats = access_stats::Entity::find()
@@ -101,12 +113,14 @@ pub async fn get_stats(db: &DatabaseConnection) -> Result<StatsResponse> {
         Ok(StatsResponse {
             total_requests: stats.total_requests,
             unique_users: stats.unique_users,
+            parse_failures: stats.parse_failures,
             last_updated_at: stats.last_updated_at,
         })
     } else {
         Ok(StatsResponse {
             total_requests: 0,
             unique_users: 0,
+            parse_failures: 0,
             last_updated_at: 0,
         })
     }
@@ -121,8 +135,62 @@ pub struct StatsResponse {
     /// 唯一用户数
     #[schema(example = 567)]
     pub unique_users: i32,
+    /// 课程记录解析失败次数（累计）
+    #[schema(example = 0)]
+    pub parse_failures: i64,
     /// 最后更新时间（毫秒时间戳）
     #[schema(example = "1704067200000")]
     pub last_updated_at: i64,
 }
 
+/// 获取最近的请求日志（按时间倒序，不返回令牌和加密学号）
+pub async fn get_recent_logs(
+    db: &DatabaseConnection,
+    limit: u64,
+    offset: u64,
+) -> Result<RequestLogPage> {
+    let total = request_logs::Entity::find().count(db).await?;
+    let logs = request_logs::Entity::find()
+        .order_by_desc(request_logs::Column::Id)
+        .limit(limit)
+        .offset(offset)
+        .all(db)
+        .await?;
+
+    Ok(RequestLogPage {
+        total,
+        items: logs
+            .into_iter()
+            .map(|log| RequestLogSummary {
+                id: log.id,
+                timestamp: log.timestamp,
+                duration_ms: log.duration_ms,
+                api_key_id: log.api_key_id,
+            })
+            .collect(),
+    })
+}
+
+/// 请求日志摘要
+#[derive(Debug, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct RequestLogSummary {
+    /// 日志 ID
+    pub id: i32,
+    /// 请求时间（毫秒时间戳）
+    #[schema(example = "1704067200000")]
+    pub timestamp: i64,
+    /// 处理耗时（毫秒）
+    #[schema(example = 1520)]
+    pub duration_ms: i64,
+    /// 发起请求的 API Key ID（未使用 API Key 时为空）
+    pub api_key_id: Option<i32>,
+}
+
+/// 请求日志分页结果
+#[derive(Debug, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct RequestLogPage {
+    /// 日志总数
+    pub total: u64,
+    /// 当前页日志
+    pub items: Vec<RequestLogSummary>,
+}
@@ -0,0 +1,166 @@
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use utoipa::ToSchema;
+
+use crate::parser::schedule::{DayCourse, WeekInfo};
+use crate::services::occurrence::{course_key, expand_occurrences, LessonOccurrence, OccurrenceRange};
+use crate::utils::schedule::get_school_schedule;
+
+/// 一门课的课时与进度
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CourseSummary {
+    /// 课程代码
+    #[schema(example = "MATH101")]
+    pub code: String,
+    /// 课程名称
+    #[schema(example = "高等数学")]
+    pub name: String,
+    /// 授课教师（去重）
+    pub teachers: Vec<String>,
+    /// 总上课次数（连续节次算一次）
+    #[schema(example = 32)]
+    pub total_sessions: u32,
+    /// 总节数
+    #[schema(example = 64)]
+    pub total_lessons: u32,
+    /// 总课时（小时，按每节课时长计算）
+    #[schema(example = 48.0)]
+    pub total_hours: f64,
+    /// 已上次数（截至当前时刻已下课）
+    #[schema(example = 12)]
+    pub completed_sessions: u32,
+    /// 剩余次数（含正在上的一次，截至 `until`，未传时截至学期结束）
+    #[schema(example = 20)]
+    pub remaining_sessions: u32,
+    /// 剩余课时（小时）
+    #[schema(example = 30.0)]
+    pub remaining_hours: f64,
+    /// 第一次上课日期（YYYY-MM-DD）
+    #[schema(example = "2024-09-02")]
+    pub first_date: String,
+    /// 最后一次上课日期（YYYY-MM-DD）
+    #[schema(example = "2024-12-27")]
+    pub last_date: String,
+    /// 下一次上课日期（已全部上完时为空）
+    #[schema(example = "2024-10-08")]
+    pub next_date: Option<String>,
+    /// 每周课时（周号 -> 小时）
+    pub weekly_hours: BTreeMap<u32, f64>,
+}
+
+/// 整个学期的课时统计
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ScheduleSummary {
+    /// 统计时刻（ISO 8601，东八区）
+    #[schema(example = "2024-10-08T12:00:00+08:00")]
+    pub as_of: String,
+    /// 剩余次数统计截止日期（YYYY-MM-DD，含；未传时为空，表示学期结束）
+    #[schema(example = "2024-12-20")]
+    pub until: Option<String>,
+    /// 每节课时长（分钟）
+    #[schema(example = 45)]
+    pub lesson_duration: u32,
+    /// 全部课程的总课时（小时）
+    #[schema(example = 420.0)]
+    pub total_hours: f64,
+    /// 每周总课时（周号 -> 小时）
+    pub weekly_hours: BTreeMap<u32, f64>,
+    /// 各课程统计（按第一次上课日期排序）
+    pub courses: Vec<CourseSummary>,
+}
+
+/// 保留两位小数
+fn round_hours(hours: f64) -> f64 {
+    (hours * 100.0).round() / 100.0
+}
+
+/// 统计每门课的课时与进度
+///
+/// 基于展开后的上课列表，节假日停上的课不计入；
+/// `now` 之前已下课的为已上，其余在 `until`（含）之前的为剩余（正在上的也算剩余），
+/// 两者之和等于总次数（`until` 未传时）
+pub fn summarize(
+    weeks: &HashMap<u32, Vec<DayCourse>>,
+    semester_weeks: &[WeekInfo],
+    now: DateTime<FixedOffset>,
+    until: Option<NaiveDate>,
+) -> ScheduleSummary {
+    let lesson_duration = get_school_schedule().basic_info.lesson_duration;
+    let hours_of = |o: &LessonOccurrence| (o.end_slot - o.start_slot + 1) as f64 * lesson_duration as f64 / 60.0;
+
+    let mut courses: Vec<CourseSummary> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut weekly_hours: BTreeMap<u32, f64> = BTreeMap::new();
+
+    // 上课列表已按时刻排序，第一次出现即第一次上课
+    for occurrence in expand_occurrences(weeks, semester_weeks, OccurrenceRange::default()) {
+        let hours = hours_of(&occurrence);
+        let key = course_key(&occurrence.course.code, &occurrence.course.name).to_string();
+        let i = *index.entry(key).or_insert_with(|| {
+            courses.push(CourseSummary {
+                code: occurrence.course.code.clone(),
+                name: occurrence.course.name.clone(),
+                teachers: Vec::new(),
+                total_sessions: 0,
+                total_lessons: 0,
+                total_hours: 0.0,
+                completed_sessions: 0,
+                remaining_sessions: 0,
+                remaining_hours: 0.0,
+                first_date: occurrence.date.clone(),
+                last_date: occurrence.date.clone(),
+                next_date: None,
+                weekly_hours: BTreeMap::new(),
+            });
+            courses.len() - 1
+        });
+        let course = &mut courses[i];
+
+        for teacher in &occurrence.course.teacher {
+            if !teacher.trim().is_empty() && !course.teachers.contains(teacher) {
+                course.teachers.push(teacher.clone());
+            }
+        }
+        course.total_sessions += 1;
+        course.total_lessons += occurrence.end_slot - occurrence.start_slot + 1;
+        course.total_hours += hours;
+        course.last_date = occurrence.date.clone();
+        *course.weekly_hours.entry(occurrence.week).or_default() += hours;
+        *weekly_hours.entry(occurrence.week).or_default() += hours;
+
+        let ended = DateTime::parse_from_rfc3339(&occurrence.end).is_ok_and(|end| end <= now);
+        let started = occurrence.starts_at().is_some_and(|start| start <= now);
+        if ended {
+            course.completed_sessions += 1;
+        }
+        if !started && course.next_date.is_none() {
+            course.next_date = Some(occurrence.date.clone());
+        }
+        if !ended {
+            let date = NaiveDate::parse_from_str(&occurrence.date, "%Y-%m-%d").ok();
+            let within = until.is_none_or(|u| date.is_some_and(|d| d <= u));
+            if within {
+                course.remaining_sessions += 1;
+                course.remaining_hours += hours;
+            }
+        }
+    }
+
+    for course in &mut courses {
+        course.total_hours = round_hours(course.total_hours);
+        course.remaining_hours = round_hours(course.remaining_hours);
+        course.weekly_hours.values_mut().for_each(|h| *h = round_hours(*h));
+    }
+    weekly_hours.values_mut().for_each(|h| *h = round_hours(*h));
+    courses.sort_by(|a, b| (&a.first_date, &a.name).cmp(&(&b.first_date, &b.name)));
+
+    ScheduleSummary {
+        as_of: now.to_rfc3339(),
+        until: until.map(|u| u.format("%Y-%m-%d").to_string()),
+        lesson_duration,
+        total_hours: round_hours(courses.iter().map(|c| c.total_hours).sum()),
+        weekly_hours,
+        courses,
+    }
+}
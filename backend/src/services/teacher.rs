@@ -0,0 +1,172 @@
+use chrono::{DateTime, Datelike, FixedOffset};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use utoipa::ToSchema;
+
+use crate::parser::schedule::{normalize_teachers, DayCourse, WeekInfo};
+use crate::services::calendar::{find_week, parse_date};
+use crate::services::occurrence::{course_key, expand_occurrences, LessonOccurrence, OccurrenceRange};
+use crate::utils::location::Location;
+
+/// 某位教师的一门课在每周固定的上课时间
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TeacherMeeting {
+    /// 星期几（1=周一，...，7=周日）
+    #[schema(example = 2)]
+    pub weekday: u32,
+    /// 起始节次
+    #[schema(example = 1)]
+    pub start_slot: u32,
+    /// 结束节次（含）
+    #[schema(example = 2)]
+    pub end_slot: u32,
+    /// 上课时间（取第一次上课的时间，作息季节不同时可能变化）
+    #[schema(example = "08:00")]
+    pub start_time: Option<String>,
+    #[schema(example = "09:40")]
+    pub end_time: Option<String>,
+    /// 教室
+    #[schema(example = "教学楼A101")]
+    pub classroom: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<Location>,
+    /// 上课的周次（升序）
+    #[schema(example = json!([1, 2, 3, 4]))]
+    pub weeks: Vec<u32>,
+    /// 因调休挪到其他日期上的课的实际日期（周次和星期仍按原课表计入上面的字段）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schema(example = json!(["2024-09-29"]))]
+    pub makeup_dates: Vec<String>,
+}
+
+/// 某位教师教的一门课
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TeacherCourse {
+    #[schema(example = "MATH101")]
+    pub code: String,
+    #[schema(example = "高等数学")]
+    pub name: String,
+    /// 每周上课时间（按星期、节次排序）
+    pub meetings: Vec<TeacherMeeting>,
+}
+
+/// 一位教师及其课程
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TeacherSchedule {
+    #[schema(example = "张老师")]
+    pub name: String,
+    pub courses: Vec<TeacherCourse>,
+    /// 在这些教室上过课（去重）
+    pub classrooms: Vec<String>,
+    /// 下一次见到这位教师的课（学期内已没有时为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<LessonOccurrence>,
+}
+
+/// 按教师整理课表
+///
+/// 基于展开后的上课列表（节假日停上的课不计入，调休日的课按所跟随那天的周次和星期计入）；教师名会再规范化一次，
+/// 兼容规范化之前缓存的课表。`name` 不为空时只返回名字包含该字符串的教师。
+/// 结果按教师名排序
+pub fn teacher_index(
+    weeks: &HashMap<u32, Vec<DayCourse>>,
+    semester_weeks: &[WeekInfo],
+    now: DateTime<FixedOffset>,
+    name: Option<&str>,
+) -> Vec<TeacherSchedule> {
+    let filter = name.map(str::trim).filter(|n| !n.is_empty());
+    let mut teachers: BTreeMap<String, TeacherSchedule> = BTreeMap::new();
+
+    // 上课列表已按时刻排序，第一个晚于 now 的即下一次
+    for occurrence in expand_occurrences(weeks, semester_weeks, OccurrenceRange::default()) {
+        let names = normalize_teachers(&occurrence.course.teacher.join(";"));
+        let upcoming = occurrence.starts_at().is_some_and(|start| start > now);
+        let lesson = occurrence.to_timed_lesson();
+        // 调休日上的是另一天的课，按那一天归入每周固定的上课时间
+        let follows = occurrence.special.as_ref().and_then(|s| s.follows.as_deref()).and_then(parse_date);
+        let (week, weekday) = match follows {
+            Some(date) => (
+                find_week(semester_weeks, date).map_or(occurrence.week, |w| w.week),
+                date.weekday().number_from_monday(),
+            ),
+            None => (occurrence.week, occurrence.weekday),
+        };
+        let makeup_date = follows.map(|_| occurrence.date.clone());
+
+        for teacher_name in names {
+            if filter.is_some_and(|f| !teacher_name.contains(f)) {
+                continue;
+            }
+            let teacher = teachers.entry(teacher_name.clone()).or_insert_with(|| TeacherSchedule {
+                name: teacher_name,
+                courses: Vec::new(),
+                classrooms: Vec::new(),
+                next: None,
+            });
+
+            let key = course_key(&occurrence.course.code, &occurrence.course.name);
+            let course = match teacher.courses.iter().position(|c| course_key(&c.code, &c.name) == key) {
+                Some(i) => &mut teacher.courses[i],
+                None => {
+                    teacher.courses.push(TeacherCourse {
+                        code: occurrence.course.code.clone(),
+                        name: occurrence.course.name.clone(),
+                        meetings: Vec::new(),
+                    });
+                    teacher.courses.last_mut().unwrap()
+                }
+            };
+
+            let classroom = occurrence.course.classroom.clone();
+            let meeting = course.meetings.iter_mut().find(|m| {
+                m.weekday == weekday
+                    && m.start_slot == occurrence.start_slot
+                    && m.end_slot == occurrence.end_slot
+                    && m.classroom == classroom
+            });
+            let meeting = match meeting {
+                Some(m) => {
+                    if !m.weeks.contains(&week) {
+                        m.weeks.push(week);
+                    }
+                    m
+                }
+                None => {
+                    course.meetings.push(TeacherMeeting {
+                        weekday,
+                        start_slot: occurrence.start_slot,
+                        end_slot: occurrence.end_slot,
+                        start_time: lesson.start_time.clone(),
+                        end_time: lesson.end_time.clone(),
+                        classroom: classroom.clone(),
+                        location: occurrence.location.clone(),
+                        weeks: vec![week],
+                        makeup_dates: Vec::new(),
+                    });
+                    course.meetings.last_mut().unwrap()
+                }
+            };
+            meeting.makeup_dates.extend(makeup_date.clone());
+
+            if let Some(room) = classroom {
+                if !teacher.classrooms.contains(&room) {
+                    teacher.classrooms.push(room);
+                }
+            }
+            if upcoming && teacher.next.is_none() {
+                teacher.next = Some(occurrence.clone());
+            }
+        }
+    }
+
+    teachers
+        .into_values()
+        .map(|mut teacher| {
+            for course in &mut teacher.courses {
+                course.meetings.sort_by_key(|m| (m.weekday, m.start_slot));
+                course.meetings.iter_mut().for_each(|m| m.weeks.sort_unstable());
+            }
+            teacher
+        })
+        .collect()
+}
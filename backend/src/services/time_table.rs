@@ -0,0 +1,128 @@
+use chrono::NaiveDate;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, DbErr, EntityTrait, QueryOrder, Set};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::db::models::time_table_versions;
+use crate::utils::schedule::{self, current_time_tables, validate_lesson_times, TimeTableVersion};
+use crate::utils::time::now_millis;
+
+/// 时间表版本错误
+#[derive(Debug, thiserror::Error)]
+pub enum TimeTableVersionError {
+    #[error("Invalid time table version: {0}")]
+    Invalid(String),
+    #[error("Time table version not found")]
+    NotFound,
+    #[error(transparent)]
+    Db(#[from] DbErr),
+}
+
+/// 新建时间表版本的请求体
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NewTimeTableVersion {
+    /// 版本名称
+    #[schema(example = "期末考试周")]
+    pub name: String,
+    /// 生效起始日期（YYYY-MM-DD，含）
+    #[schema(example = "2025-01-06")]
+    pub effective_from: String,
+    /// 生效结束日期（YYYY-MM-DD，含）
+    #[schema(example = "2025-01-12")]
+    pub effective_to: String,
+    /// 每节课的起止时间（节次数不能超过每天课程数）
+    #[schema(example = json!([["08:30", "10:30"], ["10:50", "12:50"]]))]
+    pub times: Vec<(String, String)>,
+}
+
+fn to_version(model: time_table_versions::Model) -> TimeTableVersion {
+    TimeTableVersion {
+        id: model.id,
+        name: model.name,
+        effective_from: model.effective_from,
+        effective_to: model.effective_to,
+        times: serde_json::from_str(&model.times_json).unwrap_or_default(),
+        created_at: model.created_at,
+    }
+}
+
+/// 校验并规范化新版本（日期统一为 YYYY-MM-DD）
+pub fn validate_version(new: &NewTimeTableVersion) -> Result<NewTimeTableVersion, TimeTableVersionError> {
+    let invalid = |msg: String| TimeTableVersionError::Invalid(msg);
+
+    let name = new.name.trim();
+    if name.is_empty() {
+        return Err(invalid("name is required".to_string()));
+    }
+    let parse = |s: &str| {
+        NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d")
+            .map_err(|_| invalid(format!("invalid date '{}', expected YYYY-MM-DD", s)))
+    };
+    let from = parse(&new.effective_from)?;
+    let to = parse(&new.effective_to)?;
+    if from > to {
+        return Err(invalid("effective_from must not be after effective_to".to_string()));
+    }
+
+    let total_lessons = current_time_tables().total_lessons as usize;
+    if new.times.len() > total_lessons {
+        return Err(invalid(format!("at most {} lessons, got {}", total_lessons, new.times.len())));
+    }
+    validate_lesson_times(&new.times).map_err(invalid)?;
+
+    Ok(NewTimeTableVersion {
+        name: name.to_string(),
+        effective_from: from.format("%Y-%m-%d").to_string(),
+        effective_to: to.format("%Y-%m-%d").to_string(),
+        times: new.times.clone(),
+    })
+}
+
+/// 列出所有时间表版本（按生效起始日期排序）
+pub async fn list_versions(db: &DatabaseConnection) -> Result<Vec<TimeTableVersion>, DbErr> {
+    let models = time_table_versions::Entity::find()
+        .order_by_asc(time_table_versions::Column::EffectiveFrom)
+        .order_by_asc(time_table_versions::Column::Id)
+        .all(db)
+        .await?;
+    Ok(models.into_iter().map(to_version).collect())
+}
+
+/// 从数据库重新加载时间表版本到内存，供按日期查询时间表使用
+pub async fn refresh_versions(db: &DatabaseConnection) -> Result<usize, DbErr> {
+    let versions = list_versions(db).await?;
+    let count = versions.len();
+    schedule::set_time_table_versions(versions);
+    Ok(count)
+}
+
+/// 新建时间表版本
+pub async fn create_version(
+    db: &DatabaseConnection,
+    new: &NewTimeTableVersion,
+) -> Result<TimeTableVersion, TimeTableVersionError> {
+    let new = validate_version(new)?;
+    let model = time_table_versions::ActiveModel {
+        name: Set(new.name),
+        effective_from: Set(new.effective_from),
+        effective_to: Set(new.effective_to),
+        times_json: Set(serde_json::to_string(&new.times).unwrap_or_else(|_| "[]".to_string())),
+        created_at: Set(now_millis()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    refresh_versions(db).await?;
+    Ok(to_version(model))
+}
+
+/// 删除时间表版本
+pub async fn delete_version(db: &DatabaseConnection, id: i32) -> Result<(), TimeTableVersionError> {
+    let result = time_table_versions::Entity::delete_by_id(id).exec(db).await?;
+    if result.rows_affected == 0 {
+        return Err(TimeTableVersionError::NotFound);
+    }
+    refresh_versions(db).await?;
+    Ok(())
+}
@@ -0,0 +1,356 @@
+use hickory_resolver::config::{ResolverConfig, ResolverOpts};
+use hickory_resolver::TokioAsyncResolver;
+use reqwest::redirect::Policy;
+use url::Host;
+use reqwest::{Client, Url};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, Set,
+};
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+use utoipa::ToSchema;
+
+use crate::db::models::webhooks;
+use crate::services::diff::ScheduleDiff;
+use crate::utils::crypto::{decrypt_with_secret, encrypt_with_secret, hmac_sha256, random_token};
+use crate::utils::time::now_millis;
+
+/// 每个用户最多注册的 Webhook 数量
+pub const MAX_WEBHOOKS_PER_USER: u64 = 5;
+
+/// 连续失败多少次后自动停用
+const MAX_CONSECUTIVE_FAILURES: i32 = 10;
+
+/// 推送超时时间
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 签名时间戳请求头
+pub const TIMESTAMP_HEADER: &str = "X-FJCPC-Timestamp";
+/// 签名请求头（`sha256=<hex>`）
+pub const SIGNATURE_HEADER: &str = "X-FJCPC-Signature";
+
+/// Webhook 错误
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookError {
+    #[error("Invalid webhook URL: {0}")]
+    InvalidUrl(String),
+    #[error("Too many webhooks (at most {MAX_WEBHOOKS_PER_USER} per user)")]
+    LimitReached,
+    #[error("Webhook not found")]
+    NotFound,
+    /// 未配置 SESSION_SECRET 时签名密钥会用本进程的随机密钥加密，重启后无法解密
+    #[error("Webhooks require SESSION_SECRET to be configured")]
+    SecretNotPersistent,
+    #[error(transparent)]
+    Db(#[from] DbErr),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+/// Webhook 信息（不含签名密钥）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WebhookInfo {
+    pub id: i32,
+    /// 推送地址
+    #[schema(example = "https://example.com/hooks/schedule")]
+    pub url: String,
+    /// 是否启用（连续失败过多会被自动停用）
+    pub active: bool,
+    /// 创建时间（毫秒时间戳）
+    pub created_at: i64,
+    /// 最近一次推送时间（毫秒时间戳）
+    pub last_delivery_at: Option<i64>,
+    /// 最近一次推送的 HTTP 状态码（请求失败时为 0）
+    pub last_status: Option<i32>,
+    /// 连续失败次数
+    pub failure_count: i32,
+}
+
+/// 新建 Webhook 的结果（签名密钥只返回这一次）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreatedWebhook {
+    /// 签名密钥，用于校验 X-FJCPC-Signature
+    pub secret: String,
+    pub info: WebhookInfo,
+}
+
+/// 推送内容
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WebhookPayload {
+    /// 事件类型
+    #[schema(example = "schedule.changed")]
+    pub event: String,
+    pub webhook_id: i32,
+    /// 检测到变动的时间（毫秒时间戳）
+    pub detected_at: i64,
+    /// 课表变动
+    pub diff: ScheduleDiff,
+}
+
+fn to_info(model: webhooks::Model) -> WebhookInfo {
+    WebhookInfo {
+        id: model.id,
+        url: model.url,
+        active: model.active,
+        created_at: model.created_at,
+        last_delivery_at: model.last_delivery_at,
+        last_status: model.last_status,
+        failure_count: model.failure_count,
+    }
+}
+
+/// 是否为不允许推送的地址：本机、内网、链路本地、运营商级 NAT（100.64.0.0/10）、组播，
+/// 以及嵌入了这些 IPv4 地址的 IPv6 地址（如 `::ffff:127.0.0.1`）
+pub fn is_disallowed_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_multicast()
+                || a == 0
+                || (a == 100 && (b & 0xc0) == 64)
+        }
+        IpAddr::V6(v6) => {
+            // IPv4 映射 / 兼容地址以及 NAT64（64:ff9b::/96）按内嵌的 IPv4 地址判断
+            let segments = v6.segments();
+            let nat64 = segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0];
+            if let Some(v4) = v6.to_ipv4() {
+                return is_disallowed_ip(IpAddr::V4(v4));
+            }
+            if nat64 {
+                let [.., a, b, c, d] = v6.octets();
+                return is_disallowed_ip(IpAddr::from([a, b, c, d]));
+            }
+            v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                || (segments[0] & 0xfe00) == 0xfc00
+                || (segments[0] & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+/// 校验推送地址：只允许 http/https，拒绝本机和内网地址
+///
+/// 这里只能检查直接写成 IP 的地址，域名在推送前由 [`resolve_target`] 解析后再检查
+pub fn validate_url(raw: &str) -> Result<Url, WebhookError> {
+    let url = Url::parse(raw.trim()).map_err(|e| WebhookError::InvalidUrl(e.to_string()))?;
+
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(WebhookError::InvalidUrl("only http and https are supported".to_string()));
+    }
+
+    let ip = match url.host() {
+        None => return Err(WebhookError::InvalidUrl("missing host".to_string())),
+        Some(Host::Domain(host)) => {
+            let host = host.trim_end_matches('.');
+            if host.eq_ignore_ascii_case("localhost") || host.to_ascii_lowercase().ends_with(".localhost") {
+                return Err(WebhookError::InvalidUrl("local addresses are not allowed".to_string()));
+            }
+            None
+        }
+        Some(Host::Ipv4(v4)) => Some(IpAddr::V4(v4)),
+        Some(Host::Ipv6(v6)) => Some(IpAddr::V6(v6)),
+    };
+    if ip.is_some_and(is_disallowed_ip) {
+        return Err(WebhookError::InvalidUrl("private addresses are not allowed".to_string()));
+    }
+
+    Ok(url)
+}
+
+/// 检查域名的解析结果：任意一个是内网地址都拒绝（防止混入内网记录），否则返回第一个地址
+pub fn check_resolved(addrs: &[IpAddr]) -> Result<IpAddr, WebhookError> {
+    if addrs.iter().any(|ip| is_disallowed_ip(*ip)) {
+        return Err(WebhookError::InvalidUrl("host resolves to a private address".to_string()));
+    }
+    addrs
+        .first()
+        .copied()
+        .ok_or_else(|| WebhookError::InvalidUrl("host does not resolve".to_string()))
+}
+
+/// 推送前解析推送地址，返回检查过的连接地址
+///
+/// 推送时只连接这里检查过的地址，避免两次解析结果不同（DNS rebinding）绕过检查
+pub async fn resolve_target(url: &Url) -> Result<SocketAddr, WebhookError> {
+    let port = url.port_or_known_default().unwrap_or(80);
+    let ip = match url.host() {
+        Some(Host::Domain(domain)) => {
+            let resolver = TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default());
+            let lookup = resolver
+                .lookup_ip(domain)
+                .await
+                .map_err(|e| WebhookError::InvalidUrl(format!("failed to resolve host: {}", e)))?;
+            check_resolved(&lookup.iter().collect::<Vec<_>>())?
+        }
+        Some(Host::Ipv4(v4)) => check_resolved(&[IpAddr::V4(v4)])?,
+        Some(Host::Ipv6(v6)) => check_resolved(&[IpAddr::V6(v6)])?,
+        None => return Err(WebhookError::InvalidUrl("missing host".to_string())),
+    };
+    Ok(SocketAddr::new(ip, port))
+}
+
+/// 推送使用的客户端：不跟随重定向，域名固定连接到检查过的地址
+pub fn delivery_client(url: &Url, addr: SocketAddr) -> reqwest::Result<Client> {
+    let mut builder = Client::builder().timeout(DELIVERY_TIMEOUT).redirect(Policy::none());
+    if let Some(Host::Domain(domain)) = url.host() {
+        builder = builder.resolve(domain, addr);
+    }
+    builder.build()
+}
+
+/// 计算推送签名：`sha256=hex(HMAC-SHA256(secret, timestamp + "." + body))`
+pub fn sign_payload(secret: &str, timestamp: i64, body: &str) -> String {
+    let message = format!("{}.{}", timestamp, body);
+    format!("sha256={}", hex::encode(hmac_sha256(secret.as_bytes(), message.as_bytes())))
+}
+
+/// 注册 Webhook
+pub async fn register_webhook(
+    db: &DatabaseConnection,
+    ucode_hash: &str,
+    url: &str,
+    server_secret: &str,
+) -> Result<CreatedWebhook, WebhookError> {
+    let url = validate_url(url)?;
+
+    let existing = webhooks::Entity::find()
+        .filter(webhooks::Column::UcodeHash.eq(ucode_hash))
+        .count(db)
+        .await?;
+    if existing >= MAX_WEBHOOKS_PER_USER {
+        return Err(WebhookError::LimitReached);
+    }
+
+    let secret = random_token(32);
+    let model = webhooks::ActiveModel {
+        ucode_hash: Set(ucode_hash.to_string()),
+        url: Set(url.to_string()),
+        secret: Set(encrypt_with_secret(&secret, server_secret)?),
+        active: Set(true),
+        created_at: Set(now_millis()),
+        last_delivery_at: Set(None),
+        last_status: Set(None),
+        failure_count: Set(0),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    Ok(CreatedWebhook {
+        secret,
+        info: to_info(model),
+    })
+}
+
+/// 列出用户的 Webhook
+pub async fn list_webhooks(db: &DatabaseConnection, ucode_hash: &str) -> Result<Vec<WebhookInfo>, DbErr> {
+    let hooks = webhooks::Entity::find()
+        .filter(webhooks::Column::UcodeHash.eq(ucode_hash))
+        .order_by_asc(webhooks::Column::Id)
+        .all(db)
+        .await?;
+    Ok(hooks.into_iter().map(to_info).collect())
+}
+
+/// 删除用户的 Webhook
+pub async fn delete_webhook(db: &DatabaseConnection, ucode_hash: &str, id: i32) -> Result<(), WebhookError> {
+    let result = webhooks::Entity::delete_many()
+        .filter(webhooks::Column::Id.eq(id))
+        .filter(webhooks::Column::UcodeHash.eq(ucode_hash))
+        .exec(db)
+        .await?;
+    if result.rows_affected == 0 {
+        return Err(WebhookError::NotFound);
+    }
+    Ok(())
+}
+
+/// 解析并检查推送地址后发送一次签名的推送，返回 HTTP 状态码（重定向按失败处理）
+async fn post_signed(url: &str, secret: &str, timestamp: i64, body: String) -> anyhow::Result<u16> {
+    let url = validate_url(url)?;
+    let addr = resolve_target(&url).await?;
+    let resp = delivery_client(&url, addr)?
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(SIGNATURE_HEADER, sign_payload(secret, timestamp, &body))
+        .body(body)
+        .send()
+        .await?;
+    Ok(resp.status().as_u16())
+}
+
+/// 向用户所有启用的 Webhook 推送课表变动（逐个推送，失败只记录不重试）
+pub async fn deliver_schedule_change(
+    db: &DatabaseConnection,
+    ucode_hash: &str,
+    diff: &ScheduleDiff,
+    server_secret: &str,
+) -> anyhow::Result<()> {
+    let hooks = webhooks::Entity::find()
+        .filter(webhooks::Column::UcodeHash.eq(ucode_hash))
+        .filter(webhooks::Column::Active.eq(true))
+        .all(db)
+        .await?;
+    if hooks.is_empty() {
+        return Ok(());
+    }
+
+    let detected_at = now_millis();
+
+    for hook in hooks {
+        let payload = WebhookPayload {
+            event: "schedule.changed".to_string(),
+            webhook_id: hook.id,
+            detected_at,
+            diff: diff.clone(),
+        };
+        let body = serde_json::to_string(&payload)?;
+        let timestamp = now_millis() / 1000;
+
+        // 密钥无法解密是服务端配置问题（如 SESSION_SECRET 变了），不是推送地址的问题，不计入失败次数
+        let secret = match decrypt_with_secret(&hook.secret, server_secret) {
+            Ok(secret) => secret,
+            Err(e) => {
+                tracing::error!(
+                    "Webhook {} secret cannot be decrypted, check that SESSION_SECRET has not changed: {}",
+                    hook.id,
+                    e
+                );
+                continue;
+            }
+        };
+
+        let status = match post_signed(&hook.url, &secret, timestamp, body).await {
+            Ok(status) => status as i32,
+            Err(e) => {
+                tracing::warn!("Webhook {} delivery failed: {}", hook.id, e);
+                0
+            }
+        };
+
+        let succeeded = (200..300).contains(&status);
+        let failure_count = if succeeded { 0 } else { hook.failure_count + 1 };
+        let hook_id = hook.id;
+
+        let mut active: webhooks::ActiveModel = hook.into();
+        active.last_delivery_at = Set(Some(detected_at));
+        active.last_status = Set(Some(status));
+        active.failure_count = Set(failure_count);
+        if failure_count >= MAX_CONSECUTIVE_FAILURES {
+            tracing::warn!("Webhook {} disabled after {} consecutive failures", hook_id, failure_count);
+            active.active = Set(false);
+        }
+        active.update(db).await?;
+    }
+
+    Ok(())
+}
@@ -3,19 +3,24 @@ use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
+use utoipa::ToSchema;
 
-use crate::parser::schedule::DayCourse;
+use crate::parser::schedule::{DayCourse, WeekInfo};
+use crate::utils::crypto::hash_ucode;
 
 /// 缓存条目
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CacheEntry {
     pub data: HashMap<u32, Vec<DayCourse>>,
+    /// 学期周列表（周号与起止日期）
+    #[serde(default)]
+    pub semester_weeks: Vec<WeekInfo>,
     pub cached_at: u64, // Unix timestamp in seconds
 }
 
 /// 全局课表缓存（ucode -> 课表数据）
 /// 缓存有效期：24 小时
-static SCHEDULE_CACHE: Lazy<DashMap<String, CacheEntry>> = Lazy::new(|| DashMap::new());
+static SCHEDULE_CACHE: Lazy<DashMap<String, CacheEntry>> = Lazy::new(DashMap::new);
 
 const CACHE_TTL_SECONDS: u64 = 24 * 60 * 60; // 24 hours
 
@@ -47,18 +52,72 @@ pub fn get_cached_schedule(ucode: &str) -> Option<HashMap<u32, Vec<DayCourse>>>
     None
 }
 
+/// 从缓存获取学期周列表
+pub fn get_cached_semester_weeks(ucode: &str) -> Option<Vec<WeekInfo>> {
+    SCHEDULE_CACHE
+        .get(ucode)
+        .filter(|entry| is_cache_valid(entry.cached_at))
+        .map(|entry| entry.semester_weeks.clone())
+}
+
 /// 设置课表缓存
-pub fn set_cached_schedule(ucode: &str, data: HashMap<u32, Vec<DayCourse>>) {
+pub fn set_cached_schedule(ucode: &str, data: HashMap<u32, Vec<DayCourse>>, semester_weeks: Vec<WeekInfo>) {
     let entry = CacheEntry {
         data,
+        semester_weeks,
         cached_at: current_timestamp(),
     };
     SCHEDULE_CACHE.insert(ucode.to_string(), entry);
 }
 
-/// 清除指定用户的缓存
-pub fn clear_cache(ucode: &str) {
-    SCHEDULE_CACHE.remove(ucode);
+/// 清除指定用户的缓存，返回是否确实删除了条目
+pub fn clear_cache(ucode: &str) -> bool {
+    SCHEDULE_CACHE.remove(ucode).is_some()
+}
+
+/// 清空全部课表缓存，返回删除的条目数
+pub fn clear_all_cache() -> usize {
+    let removed = SCHEDULE_CACHE.len();
+    SCHEDULE_CACHE.clear();
+    removed
+}
+
+/// 淘汰所有已过期的缓存条目，返回删除的条目数
+pub fn evict_expired_cache() -> usize {
+    let before = SCHEDULE_CACHE.len();
+    SCHEDULE_CACHE.retain(|_, entry| is_cache_valid(entry.cached_at));
+    before - SCHEDULE_CACHE.len()
+}
+
+/// 缓存条目摘要（不暴露原始 UCode）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CacheEntrySummary {
+    /// UCode 的 SHA-256 哈希
+    pub ucode_hash: String,
+    /// 缓存时间（秒级时间戳）
+    pub cached_at: u64,
+    /// 过期时间（秒级时间戳）
+    pub expires_at: u64,
+    /// 缓存的周数
+    pub weeks: usize,
+    /// 是否仍在有效期内
+    pub valid: bool,
+}
+
+/// 列出所有缓存条目的摘要，按缓存时间倒序
+pub fn list_cache_entries() -> Vec<CacheEntrySummary> {
+    let mut entries: Vec<CacheEntrySummary> = SCHEDULE_CACHE
+        .iter()
+        .map(|entry| CacheEntrySummary {
+            ucode_hash: hash_ucode(entry.key()),
+            cached_at: entry.cached_at,
+            expires_at: entry.cached_at + CACHE_TTL_SECONDS,
+            weeks: entry.data.len(),
+            valid: is_cache_valid(entry.cached_at),
+        })
+        .collect();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.cached_at));
+    entries
 }
 
 /// 获取缓存统计信息
@@ -1,4 +1,7 @@
 use std::env;
+use std::fmt;
+
+use crate::utils::crypto::random_token;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum AppEnv {
@@ -7,6 +10,7 @@ pub enum AppEnv {
 }
 
 impl AppEnv {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Self {
         match s.to_lowercase().as_str() {
             "production" | "prod" => AppEnv::Production,
@@ -23,12 +27,36 @@ impl AppEnv {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct AppConfig {
     pub app_env: AppEnv,
     pub port: u16,
     pub college_app_base_url: String,
     pub test_student_ucode: Option<String>,
+    /// 管理接口令牌（未配置时管理接口不可用）
+    pub admin_token: Option<String>,
+    /// 每个客户端 IP 在一个限流窗口内的请求配额（0 表示不限）
+    pub rate_limit_per_ip: u32,
+    /// 每个 UCode 在一个限流窗口内的请求配额（0 表示不限）
+    pub rate_limit_per_ucode: u32,
+    /// 限流窗口长度（秒）
+    pub rate_limit_window_secs: u64,
+    /// 是否信任反向代理传来的 X-Forwarded-For / Forwarded 头来识别客户端 IP
+    pub trust_proxy_headers: bool,
+    /// 是否要求 /api 下带授权范围的接口必须携带 API Key
+    pub api_key_required: bool,
+    /// 会话令牌签名与 UCode 加密使用的密钥
+    pub session_secret: String,
+    /// 密钥是否来自 SESSION_SECRET（否则为本进程随机生成，重启后用它加密的数据都无法解密）
+    pub session_secret_persistent: bool,
+    /// 会话有效期（小时）
+    pub session_ttl_hours: i64,
+    /// 作息时间配置文件路径（TOML 或 JSON，未配置时使用内置配置）
+    pub time_table_path: Option<String>,
+    /// 节假日与调休配置文件路径（TOML 或 JSON，未配置时使用内置配置）
+    pub holiday_path: Option<String>,
+    /// 教室解析规则文件路径（TOML 或 JSON，未配置时使用内置配置）
+    pub location_path: Option<String>,
 }
 
 impl AppConfig {
@@ -42,12 +70,47 @@ impl AppConfig {
             .and_then(|s| s.parse::<u16>().ok())
             .unwrap_or(8080);
 
+        let configured_secret = env::var("SESSION_SECRET").ok().filter(|s| !s.trim().is_empty());
+
         Self {
             app_env,
             port,
             college_app_base_url: env::var("FJCPC_APP_BASE_URL")
                 .unwrap_or_else(|_| "https://app.fjcpc.edu.cn".to_string()),
             test_student_ucode: env::var("TEST_STUDENT_UCODE").ok(),
+            admin_token: env::var("ADMIN_TOKEN").ok().filter(|s| !s.trim().is_empty()),
+            rate_limit_per_ip: env::var("RATE_LIMIT_PER_IP")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(30),
+            rate_limit_per_ucode: env::var("RATE_LIMIT_PER_UCODE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(10),
+            rate_limit_window_secs: env::var("RATE_LIMIT_WINDOW_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(60),
+            trust_proxy_headers: env::var("TRUST_PROXY_HEADERS")
+                .map(|s| matches!(s.to_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(false),
+            api_key_required: env::var("API_KEY_REQUIRED")
+                .map(|s| matches!(s.to_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(false),
+            session_secret: configured_secret.clone().unwrap_or_else(|| {
+                    // 未配置时使用随机密钥，重启后已签发的会话全部失效
+                    tracing::warn!("SESSION_SECRET is not set, using a random secret for this process");
+                    random_token(32)
+                }),
+            session_secret_persistent: configured_secret.is_some(),
+            session_ttl_hours: env::var("SESSION_TTL_HOURS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|h| *h > 0)
+                .unwrap_or(24 * 7),
+            time_table_path: env::var("TIME_TABLE_CONFIG").ok().filter(|s| !s.trim().is_empty()),
+            holiday_path: env::var("HOLIDAY_CONFIG").ok().filter(|s| !s.trim().is_empty()),
+            location_path: env::var("LOCATION_CONFIG").ok().filter(|s| !s.trim().is_empty()),
         }
     }
 
@@ -60,6 +123,34 @@ impl AppConfig {
     }
 }
 
+/// 敏感字段只输出是否已配置，避免启动日志泄露令牌
+fn redact(value: &Option<String>) -> &'static str {
+    if value.is_some() { "<set>" } else { "<unset>" }
+}
+
+impl fmt::Debug for AppConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AppConfig")
+            .field("app_env", &self.app_env)
+            .field("port", &self.port)
+            .field("college_app_base_url", &self.college_app_base_url)
+            .field("test_student_ucode", &redact(&self.test_student_ucode))
+            .field("admin_token", &redact(&self.admin_token))
+            .field("rate_limit_per_ip", &self.rate_limit_per_ip)
+            .field("rate_limit_per_ucode", &self.rate_limit_per_ucode)
+            .field("rate_limit_window_secs", &self.rate_limit_window_secs)
+            .field("trust_proxy_headers", &self.trust_proxy_headers)
+            .field("api_key_required", &self.api_key_required)
+            .field("session_secret", &"<set>")
+            .field("session_secret_persistent", &self.session_secret_persistent)
+            .field("session_ttl_hours", &self.session_ttl_hours)
+            .field("time_table_path", &self.time_table_path)
+            .field("holiday_path", &self.holiday_path)
+            .field("location_path", &self.location_path)
+            .finish()
+    }
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self::from_env()
@@ -1,8 +1,9 @@
 use aes_gcm::{
-    aead::{Aead, KeyInit},
+    aead::{rand_core::RngCore, Aead, KeyInit, OsRng},
     Aes256Gcm, Nonce,
 };
 use anyhow::{anyhow, Result};
+use hmac::{Hmac, Mac};
 use sha2::{Digest, Sha256};
 
 /// 使用 timestamp 作为密钥加密学号
@@ -73,10 +74,89 @@ fn derive_nonce_from_timestamp(timestamp: i64) -> [u8; 12] {
     nonce
 }
 
+/// 计算文本的 SHA-256（hex 编码）
+pub fn sha256_hex(input: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(input.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
 /// 计算 ucode 的哈希值（用于唯一用户统计）
 pub fn hash_ucode(ucode: &str) -> String {
+    sha256_hex(ucode)
+}
+
+/// 使用服务端密钥加密任意文本（AES-256-GCM，随机 nonce）
+///
+/// 输出为 hex(nonce || ciphertext)，用于在服务端保存 UCode 等敏感数据
+pub fn encrypt_with_secret(plaintext: &str, secret: &str) -> Result<String> {
+    let key = derive_key_from_secret(secret);
+    let cipher = Aes256Gcm::new(&key.into());
+
+    let mut nonce_bytes = [0u8; 12];
+    OsRng.fill_bytes(&mut nonce_bytes);
+    let nonce = Nonce::from_slice(&nonce_bytes);
+
+    let ciphertext = cipher
+        .encrypt(nonce, plaintext.as_bytes())
+        .map_err(|e| anyhow!("Encryption failed: {}", e))?;
+
+    let mut out = nonce_bytes.to_vec();
+    out.extend_from_slice(&ciphertext);
+    Ok(hex::encode(out))
+}
+
+/// 解密 [`encrypt_with_secret`] 的输出
+pub fn decrypt_with_secret(encrypted_hex: &str, secret: &str) -> Result<String> {
+    let data = hex::decode(encrypted_hex)
+        .map_err(|e| anyhow!("Invalid hex string: {}", e))?;
+    if data.len() < 12 {
+        return Err(anyhow!("Ciphertext too short"));
+    }
+
+    let key = derive_key_from_secret(secret);
+    let cipher = Aes256Gcm::new(&key.into());
+    let (nonce_bytes, ciphertext) = data.split_at(12);
+
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce_bytes), ciphertext)
+        .map_err(|e| anyhow!("Decryption failed: {}", e))?;
+
+    String::from_utf8(plaintext)
+        .map_err(|e| anyhow!("Invalid UTF-8: {}", e))
+}
+
+/// 从服务端密钥派生 32 字节加密密钥
+fn derive_key_from_secret(secret: &str) -> [u8; 32] {
     let mut hasher = Sha256::new();
-    hasher.update(ucode.as_bytes());
-    hex::encode(hasher.finalize())
+    hasher.update(secret.as_bytes());
+    hasher.update(b"fjcpc-session-encryption");
+    hasher.finalize().into()
+}
+
+/// 计算 HMAC-SHA256
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// 计算 API Key 的哈希值（数据库只保存哈希）
+pub fn hash_api_key(key: &str) -> String {
+    sha256_hex(key)
+}
+
+/// 生成指定字节数的随机令牌（hex 编码）
+pub fn random_token(bytes: usize) -> String {
+    let mut buf = vec![0u8; bytes];
+    OsRng.fill_bytes(&mut buf);
+    hex::encode(buf)
 }
 
+/// 常量时间比较两个字节串（用于校验令牌，避免计时侧信道）
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b.iter()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,385 @@
+use futures::channel::mpsc::UnboundedSender;
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Mutex;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+use crate::utils::crypto::sha256_hex;
+use crate::utils::time::now_millis;
+
+/// 上游 JSON 结构指纹：字段路径 -> 类型
+///
+/// 路径形如 `data[].xn`，数组元素用 `[]` 表示；课程字符串的字段数记在 `<路径>|fields` 下
+pub type Fingerprint = BTreeMap<String, String>;
+
+/// 课程字符串字段数的路径后缀
+const FIELDS_SUFFIX: &str = "|fields";
+/// 内存中保留的漂移事件数
+const MAX_RECENT: usize = 50;
+/// 样本中每个数组保留的元素数
+const SAMPLE_ITEMS: usize = 2;
+
+/// 被监测的学校接口
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UpstreamEndpoint {
+    /// `/gateway/auth/oauth/token`
+    OauthToken,
+    /// `getXn`：学年列表
+    SchoolYear,
+    /// `getSemesterbyXn`：学期周列表
+    Semester,
+    /// `getListByNoWeek2`：周课程
+    WeekCourse,
+}
+
+impl UpstreamEndpoint {
+    pub const ALL: [UpstreamEndpoint; 4] = [
+        UpstreamEndpoint::OauthToken,
+        UpstreamEndpoint::SchoolYear,
+        UpstreamEndpoint::Semester,
+        UpstreamEndpoint::WeekCourse,
+    ];
+
+    /// 接口名（用作指纹和事件的键）
+    pub fn name(&self) -> &'static str {
+        match self {
+            UpstreamEndpoint::OauthToken => "oauth/token",
+            UpstreamEndpoint::SchoolYear => "getXn",
+            UpstreamEndpoint::Semester => "getSemesterbyXn",
+            UpstreamEndpoint::WeekCourse => "getListByNoWeek2",
+        }
+    }
+
+    /// 按接口名查找
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|e| e.name() == name)
+    }
+
+    /// 响应中的字符串是否为 `|` 分隔的课程字符串
+    fn has_course_strings(&self) -> bool {
+        matches!(self, UpstreamEndpoint::WeekCourse)
+    }
+}
+
+/// 字段变化类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldChangeKind {
+    /// 新增字段
+    Added,
+    /// 字段消失
+    Removed,
+    /// 类型或课程字符串字段数变化
+    Changed,
+}
+
+/// 一处结构变化
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FieldChange {
+    #[schema(example = "data[].dqxqbj")]
+    pub path: String,
+    pub kind: FieldChangeKind,
+    /// 指纹中的类型
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "string")]
+    pub expected: Option<String>,
+    /// 本次响应中的类型
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "number")]
+    pub actual: Option<String>,
+}
+
+/// 一次结构漂移（同一接口、同一新结构只记一条，重复出现时累加次数）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SchemaDriftEvent {
+    #[schema(example = "getXn")]
+    pub endpoint: String,
+    /// 新结构指纹的哈希
+    #[schema(example = "3f2a9c0d41b7e5a8")]
+    pub fingerprint: String,
+    pub changes: Vec<FieldChange>,
+    /// 脱敏后的响应样本（字符串替换为 `***`，课程字符串保留字段数）
+    #[schema(value_type = Object)]
+    pub sample: Value,
+    /// 出现次数
+    #[schema(example = 3)]
+    pub occurrences: i64,
+    /// 首次出现时间（毫秒时间戳）
+    #[schema(example = "1704067200000")]
+    pub first_seen_at: i64,
+    /// 最近出现时间（毫秒时间戳）
+    #[schema(example = "1704067200000")]
+    pub last_seen_at: i64,
+}
+
+/// 需要持久化的记录
+#[derive(Debug, Clone)]
+pub enum DriftRecord {
+    /// 新学到或补充后的指纹
+    Baseline { endpoint: String, fingerprint: Fingerprint },
+    /// 漂移事件（含累加后的次数）
+    Drift(SchemaDriftEvent),
+}
+
+/// 与指纹比较的结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DriftComparison {
+    /// 结构变化
+    pub changes: Vec<FieldChange>,
+    /// 指纹中尚未覆盖、可以直接补充的字段（例如之前只见过空数组或 null）
+    pub learned: Fingerprint,
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn child_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+/// 上一级路径（根为空字符串）
+fn parent_path(path: &str) -> &str {
+    if let Some(parent) = path.strip_suffix(FIELDS_SUFFIX) {
+        return parent;
+    }
+    if let Some(parent) = path.strip_suffix("[]") {
+        return parent;
+    }
+    path.rsplit_once('.').map_or("", |(parent, _)| parent)
+}
+
+fn walk(
+    value: &Value,
+    path: String,
+    course_strings: bool,
+    types: &mut BTreeMap<String, BTreeSet<&'static str>>,
+    field_counts: &mut BTreeMap<String, BTreeSet<usize>>,
+) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                walk(child, child_path(&path, key), course_strings, types, field_counts);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                walk(item, format!("{}[]", path), course_strings, types, field_counts);
+            }
+        }
+        Value::String(s) if course_strings && !s.is_empty() => {
+            field_counts
+                .entry(format!("{}{}", path, FIELDS_SUFFIX))
+                .or_default()
+                .insert(s.split('|').count());
+        }
+        _ => {}
+    }
+    types.entry(path).or_default().insert(type_name(value));
+}
+
+/// 计算响应的结构指纹
+///
+/// 同一路径出现多种类型时用 `|` 连接；null 只在没有其他类型时记录
+pub fn fingerprint(value: &Value, course_strings: bool) -> Fingerprint {
+    let mut types = BTreeMap::new();
+    let mut field_counts = BTreeMap::new();
+    walk(value, String::new(), course_strings, &mut types, &mut field_counts);
+
+    let mut fingerprint: Fingerprint = types
+        .into_iter()
+        .map(|(path, mut set)| {
+            if set.len() > 1 {
+                set.remove("null");
+            }
+            (path, set.into_iter().collect::<Vec<_>>().join("|"))
+        })
+        .collect();
+    for (path, counts) in field_counts {
+        let counts: Vec<String> = counts.iter().map(|c| c.to_string()).collect();
+        fingerprint.insert(path, format!("fields:{}", counts.join(",")));
+    }
+    fingerprint
+}
+
+/// 指纹的短哈希
+pub fn fingerprint_hash(fingerprint: &Fingerprint) -> String {
+    let json = serde_json::to_string(fingerprint).unwrap_or_default();
+    sha256_hex(&json)[..16].to_string()
+}
+
+/// 比较本次响应与指纹
+///
+/// 可选字段偶尔为 null、数组偶尔为空都不算漂移：
+/// 只有上一级在本次响应中出现时才判断字段消失，上一级不在指纹中的新字段直接补充到指纹
+pub fn compare(baseline: &Fingerprint, observed: &Fingerprint) -> DriftComparison {
+    let mut result = DriftComparison::default();
+
+    for (path, actual) in observed {
+        match baseline.get(path) {
+            Some(expected) if expected == actual || actual == "null" => {}
+            Some(expected) if expected == "null" => {
+                result.learned.insert(path.clone(), actual.clone());
+            }
+            Some(expected) => result.changes.push(FieldChange {
+                path: path.clone(),
+                kind: FieldChangeKind::Changed,
+                expected: Some(expected.clone()),
+                actual: Some(actual.clone()),
+            }),
+            None if baseline.contains_key(parent_path(path)) => result.changes.push(FieldChange {
+                path: path.clone(),
+                kind: FieldChangeKind::Added,
+                expected: None,
+                actual: Some(actual.clone()),
+            }),
+            None => {
+                result.learned.insert(path.clone(), actual.clone());
+            }
+        }
+    }
+
+    for (path, expected) in baseline {
+        // 空数组没有元素、整周没有课时没有课程字符串，这两种只比较不判断消失
+        if observed.contains_key(path) || path.ends_with("[]") || path.ends_with(FIELDS_SUFFIX) {
+            continue;
+        }
+        if observed.contains_key(parent_path(path)) {
+            result.changes.push(FieldChange {
+                path: path.clone(),
+                kind: FieldChangeKind::Removed,
+                expected: Some(expected.clone()),
+                actual: None,
+            });
+        }
+    }
+
+    result
+}
+
+/// 脱敏：字符串替换为 `***`（课程字符串替换每个字段，保留字段数），数组只保留前几个元素
+pub fn redact(value: &Value) -> Value {
+    match value {
+        Value::String(s) if s.is_empty() => Value::String(String::new()),
+        Value::String(s) if s.contains('|') => Value::String(s.split('|').map(|_| "*").collect::<Vec<_>>().join("|")),
+        Value::String(_) => Value::String("***".to_string()),
+        Value::Array(items) => Value::Array(items.iter().take(SAMPLE_ITEMS).map(redact).collect()),
+        Value::Object(map) => Value::Object(map.iter().map(|(k, v)| (k.clone(), redact(v))).collect()),
+        other => other.clone(),
+    }
+}
+
+#[derive(Default)]
+struct DriftState {
+    baselines: HashMap<String, Fingerprint>,
+    recent: Vec<SchemaDriftEvent>,
+}
+
+static STATE: Lazy<Mutex<DriftState>> = Lazy::new(|| Mutex::new(DriftState::default()));
+static SINK: OnceCell<UnboundedSender<DriftRecord>> = OnceCell::new();
+
+fn send(record: DriftRecord) {
+    if let Some(sink) = SINK.get() {
+        let _ = sink.unbounded_send(record);
+    }
+}
+
+/// 设置持久化通道（只能设置一次）
+pub fn set_sink(sink: UnboundedSender<DriftRecord>) -> bool {
+    SINK.set(sink).is_ok()
+}
+
+/// 替换内存中的指纹（启动时从数据库加载）
+pub fn set_baselines(baselines: HashMap<String, Fingerprint>) {
+    STATE.lock().unwrap().baselines = baselines;
+}
+
+/// 当前的指纹
+pub fn current_baselines() -> BTreeMap<String, Fingerprint> {
+    STATE.lock().unwrap().baselines.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+}
+
+/// 丢弃某个接口的指纹，下一次响应重新学习（确认上游变化后使用）
+pub fn clear_baseline(endpoint: UpstreamEndpoint) -> bool {
+    let mut state = STATE.lock().unwrap();
+    state.recent.retain(|e| e.endpoint != endpoint.name());
+    state.baselines.remove(endpoint.name()).is_some()
+}
+
+/// 本次运行以来的漂移事件（最近的在前）
+pub fn recent_drift() -> Vec<SchemaDriftEvent> {
+    let state = STATE.lock().unwrap();
+    let mut events = state.recent.clone();
+    events.sort_by_key(|e| std::cmp::Reverse(e.last_seen_at));
+    events
+}
+
+/// 检查一次上游响应
+///
+/// 第一次见到的接口记录为指纹；之后与指纹比较，有变化时记日志并交给持久化通道
+pub fn observe(endpoint: UpstreamEndpoint, value: &Value) {
+    let observed = fingerprint(value, endpoint.has_course_strings());
+    let name = endpoint.name();
+    let mut state = STATE.lock().unwrap();
+
+    let Some(baseline) = state.baselines.get_mut(name) else {
+        info!("Learned upstream schema fingerprint for {} ({} fields)", name, observed.len());
+        state.baselines.insert(name.to_string(), observed.clone());
+        send(DriftRecord::Baseline { endpoint: name.to_string(), fingerprint: observed });
+        return;
+    };
+
+    let comparison = compare(baseline, &observed);
+    if !comparison.learned.is_empty() {
+        baseline.extend(comparison.learned);
+        send(DriftRecord::Baseline { endpoint: name.to_string(), fingerprint: baseline.clone() });
+    }
+    if comparison.changes.is_empty() {
+        return;
+    }
+
+    let hash = fingerprint_hash(&observed);
+    let now = now_millis();
+    if let Some(event) = state.recent.iter_mut().find(|e| e.endpoint == name && e.fingerprint == hash) {
+        event.occurrences += 1;
+        event.last_seen_at = now;
+        send(DriftRecord::Drift(event.clone()));
+        return;
+    }
+
+    let summary: Vec<String> = comparison
+        .changes
+        .iter()
+        .map(|c| format!("{:?} {}", c.kind, c.path))
+        .collect();
+    warn!("Upstream schema drift on {}: {}", name, summary.join(", "));
+
+    let event = SchemaDriftEvent {
+        endpoint: name.to_string(),
+        fingerprint: hash,
+        changes: comparison.changes,
+        sample: redact(value),
+        occurrences: 1,
+        first_seen_at: now,
+        last_seen_at: now,
+    };
+    if state.recent.len() >= MAX_RECENT {
+        state.recent.remove(0);
+    }
+    state.recent.push(event.clone());
+    send(DriftRecord::Drift(event));
+}
@@ -0,0 +1,214 @@
+use chrono::{Datelike, NaiveDate};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use utoipa::ToSchema;
+
+/// 放假区间
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Holiday {
+    #[schema(example = "国庆节")]
+    pub name: String,
+    /// 起始日期（YYYY-MM-DD，含）
+    #[schema(example = "2024-10-01")]
+    pub start: String,
+    /// 结束日期（YYYY-MM-DD，含）
+    #[schema(example = "2024-10-07")]
+    pub end: String,
+}
+
+/// 调休上课日
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MakeupDay {
+    #[schema(example = "国庆节调休")]
+    pub name: String,
+    /// 调休上课的日期（YYYY-MM-DD）
+    #[schema(example = "2024-09-29")]
+    pub date: String,
+    /// 当天按哪一天的课表上课（YYYY-MM-DD）
+    #[schema(example = "2024-10-04")]
+    pub follows: String,
+}
+
+/// 法定节假日与调休配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct HolidayCalendar {
+    #[serde(default)]
+    pub holidays: Vec<Holiday>,
+    #[serde(default)]
+    pub makeup_days: Vec<MakeupDay>,
+}
+
+/// 节假日配置加载失败的原因
+#[derive(Debug, thiserror::Error)]
+pub enum HolidayCalendarError {
+    #[error("Read holiday config failed: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Parse holiday config failed: {0}")]
+    Parse(String),
+    #[error("Invalid holiday config: {0}")]
+    Invalid(String),
+}
+
+/// 特殊日期的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SpecialDayKind {
+    /// 放假，当天的课停上
+    Holiday,
+    /// 调休上课，当天按 `follows` 那天的课表上课
+    Makeup,
+}
+
+/// 某一天的节假日或调休说明
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SpecialDay {
+    /// 日期（YYYY-MM-DD）
+    #[schema(example = "2024-09-29")]
+    pub date: String,
+    pub kind: SpecialDayKind,
+    #[schema(example = "国庆节调休")]
+    pub name: String,
+    /// 调休时按哪一天的课表上课（YYYY-MM-DD）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "2024-10-04")]
+    pub follows: Option<String>,
+    /// 调休时按星期几的课表上课（1=周一，...，7=周日）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = 5)]
+    pub follows_weekday: Option<u32>,
+}
+
+/// 编译时内置的默认配置
+const BUILTIN_HOLIDAYS: &str = include_str!("../../config/holidays.toml");
+
+fn parse_date(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok()
+}
+
+impl HolidayCalendar {
+    /// 内置配置
+    pub fn builtin() -> Self {
+        Self::from_toml(BUILTIN_HOLIDAYS).expect("builtin holiday config is valid")
+    }
+
+    /// 从 TOML 文本解析并校验
+    pub fn from_toml(text: &str) -> Result<Self, HolidayCalendarError> {
+        let calendar: Self = toml::from_str(text).map_err(|e| HolidayCalendarError::Parse(e.to_string()))?;
+        calendar.validate()?;
+        Ok(calendar)
+    }
+
+    /// 从 JSON 文本解析并校验
+    pub fn from_json(text: &str) -> Result<Self, HolidayCalendarError> {
+        let calendar: Self = serde_json::from_str(text).map_err(|e| HolidayCalendarError::Parse(e.to_string()))?;
+        calendar.validate()?;
+        Ok(calendar)
+    }
+
+    /// 从文件加载（`.json` 按 JSON 解析，其余按 TOML 解析）
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, HolidayCalendarError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) {
+            Self::from_json(&text)
+        } else {
+            Self::from_toml(&text)
+        }
+    }
+
+    /// 校验配置：日期合法、放假区间首尾有序、调休日不在放假区间内
+    pub fn validate(&self) -> Result<(), HolidayCalendarError> {
+        let invalid = |msg: String| Err(HolidayCalendarError::Invalid(msg));
+
+        for holiday in &self.holidays {
+            let (Some(start), Some(end)) = (parse_date(&holiday.start), parse_date(&holiday.end)) else {
+                return invalid(format!("holiday '{}': invalid date, expected YYYY-MM-DD", holiday.name));
+            };
+            if start > end {
+                return invalid(format!("holiday '{}': start is after end", holiday.name));
+            }
+        }
+        for makeup in &self.makeup_days {
+            let (Some(date), Some(_)) = (parse_date(&makeup.date), parse_date(&makeup.follows)) else {
+                return invalid(format!("makeup day '{}': invalid date, expected YYYY-MM-DD", makeup.name));
+            };
+            if let Some(holiday) = self.holiday_on(date) {
+                return invalid(format!("makeup day {} falls on holiday '{}'", makeup.date, holiday.name));
+            }
+        }
+        Ok(())
+    }
+
+    fn holiday_on(&self, date: NaiveDate) -> Option<&Holiday> {
+        self.holidays.iter().find(|h| match (parse_date(&h.start), parse_date(&h.end)) {
+            (Some(start), Some(end)) => start <= date && date <= end,
+            _ => false,
+        })
+    }
+
+    /// 某一天的节假日或调休说明（普通日期为 None）
+    pub fn special_day(&self, date: NaiveDate) -> Option<SpecialDay> {
+        let date_str = date.format("%Y-%m-%d").to_string();
+        if let Some(holiday) = self.holiday_on(date) {
+            return Some(SpecialDay {
+                date: date_str,
+                kind: SpecialDayKind::Holiday,
+                name: holiday.name.clone(),
+                follows: None,
+                follows_weekday: None,
+            });
+        }
+        self.makeup_days
+            .iter()
+            .find(|m| parse_date(&m.date) == Some(date))
+            .and_then(|m| {
+                let follows = parse_date(&m.follows)?;
+                Some(SpecialDay {
+                    date: date_str,
+                    kind: SpecialDayKind::Makeup,
+                    name: m.name.clone(),
+                    follows: Some(follows.format("%Y-%m-%d").to_string()),
+                    follows_weekday: Some(follows.weekday().number_from_monday()),
+                })
+            })
+    }
+
+    /// 区间内（含首尾）所有的节假日与调休日
+    pub fn special_days_between(&self, from: NaiveDate, to: NaiveDate) -> Vec<SpecialDay> {
+        from.iter_days()
+            .take_while(|d| *d <= to)
+            .filter_map(|d| self.special_day(d))
+            .collect()
+    }
+}
+
+static HOLIDAYS: Lazy<RwLock<Arc<HolidayCalendar>>> = Lazy::new(|| RwLock::new(Arc::new(HolidayCalendar::builtin())));
+
+/// 当前生效的节假日配置
+pub fn current_holidays() -> Arc<HolidayCalendar> {
+    HOLIDAYS.read().unwrap().clone()
+}
+
+/// 替换当前生效的节假日配置（先校验，失败时保留原配置）
+pub fn set_holidays(calendar: HolidayCalendar) -> Result<Arc<HolidayCalendar>, HolidayCalendarError> {
+    calendar.validate()?;
+    let calendar = Arc::new(calendar);
+    *HOLIDAYS.write().unwrap() = calendar.clone();
+    Ok(calendar)
+}
+
+/// 重新加载节假日配置：配置了文件路径时读取文件，否则恢复内置配置
+pub fn reload_holidays(path: Option<&str>) -> Result<Arc<HolidayCalendar>, HolidayCalendarError> {
+    let calendar = match path {
+        Some(path) => HolidayCalendar::load(path)?,
+        None => HolidayCalendar::builtin(),
+    };
+    set_holidays(calendar)
+}
+
+/// 某一天的节假日或调休说明
+pub fn special_day(date: NaiveDate) -> Option<SpecialDay> {
+    current_holidays().special_day(date)
+}
@@ -4,7 +4,9 @@ use reqwest::Client;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime};
+use serde::{Deserialize, Serialize};
 use tracing::info;
+use utoipa::ToSchema;
 
 /// DNS 缓存条目
 #[derive(Clone, Debug)]
@@ -77,6 +79,39 @@ async fn resolve_ipv4(domain: &str) -> anyhow::Result<String> {
     Ok(ipv4)
 }
 
+/// DNS 缓存条目信息（用于管理接口展示）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DnsCacheInfo {
+    /// 域名
+    pub domain: String,
+    /// 解析到的 IPv4 地址
+    pub ip: String,
+    /// 已缓存时长（秒）
+    pub age_secs: u64,
+}
+
+/// 列出当前 DNS 缓存
+pub fn get_dns_cache_entries() -> Vec<DnsCacheInfo> {
+    let cache = DNS_CACHE.lock().unwrap();
+    cache
+        .iter()
+        .map(|(domain, entry)| DnsCacheInfo {
+            domain: domain.clone(),
+            ip: entry.ip.clone(),
+            age_secs: entry.cached_at.elapsed().map(|d| d.as_secs()).unwrap_or(0),
+        })
+        .collect()
+}
+
+/// 清空 DNS 缓存，下次创建客户端时重新解析，返回删除的条目数
+pub fn clear_dns_cache() -> usize {
+    let mut cache = DNS_CACHE.lock().unwrap();
+    let removed = cache.len();
+    cache.clear();
+    info!("DNS cache flushed ({} entries)", removed);
+    removed
+}
+
 /// 创建 HTTP 客户端（强制 IPv4）
 ///
 /// 为了解决船政那个神必 DNS 服务器加了 AAAA 却没法解析的问题，
@@ -0,0 +1,266 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use utoipa::ToSchema;
+
+/// 解析后的上课地点
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Location {
+    /// 原始教室字符串
+    #[schema(example = "南校区教学楼A101")]
+    pub raw: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "南校区")]
+    pub campus: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "教学楼A")]
+    pub building: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = 1)]
+    pub floor: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "101")]
+    pub room: Option<String>,
+    /// 线上课程
+    pub online: bool,
+    /// 实验室、机房
+    pub lab: bool,
+    /// 体育场地
+    pub sports: bool,
+}
+
+/// 一条教室解析规则
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LocationRule {
+    #[schema(example = "楼 + 房间号")]
+    pub name: String,
+    /// 正则，命名分组 campus / building / floor / room 填入对应字段
+    #[schema(example = "^(?P<building>\\S+?)(?P<room>\\d{3,4})$")]
+    pub pattern: String,
+}
+
+/// 两栋楼之间的步行时间（不分方向）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WalkingTime {
+    #[schema(example = "教学楼A")]
+    pub from: String,
+    #[schema(example = "图书馆")]
+    pub to: String,
+    /// 步行分钟数
+    #[schema(example = 8)]
+    pub minutes: u32,
+}
+
+/// 教室解析配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct LocationConfig {
+    /// 规则没有识别出校区时使用的校区
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_campus: Option<String>,
+    #[serde(default)]
+    pub online_keywords: Vec<String>,
+    #[serde(default)]
+    pub lab_keywords: Vec<String>,
+    #[serde(default)]
+    pub sports_keywords: Vec<String>,
+    /// 按顺序匹配，第一条匹配的规则生效
+    #[serde(default)]
+    pub rules: Vec<LocationRule>,
+    /// 不同校区之间的步行时间（分钟）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cross_campus_minutes: Option<u32>,
+    /// 未列出的两栋楼之间的步行时间（分钟，不设置则不提醒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_walk_minutes: Option<u32>,
+    /// 楼宇之间的步行时间
+    #[serde(default)]
+    pub walking: Vec<WalkingTime>,
+}
+
+/// 教室解析配置加载失败的原因
+#[derive(Debug, thiserror::Error)]
+pub enum LocationConfigError {
+    #[error("Read location config failed: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Parse location config failed: {0}")]
+    Parse(String),
+    #[error("Invalid location config: {0}")]
+    Invalid(String),
+}
+
+/// 编译时内置的默认配置
+const BUILTIN_LOCATIONS: &str = include_str!("../../config/locations.toml");
+
+/// 编译好的教室解析器
+#[derive(Debug, Clone)]
+pub struct LocationParser {
+    config: LocationConfig,
+    patterns: Vec<Regex>,
+}
+
+impl LocationConfig {
+    /// 内置配置
+    pub fn builtin() -> Self {
+        Self::from_toml(BUILTIN_LOCATIONS).expect("builtin location config is valid")
+    }
+
+    /// 从 TOML 文本解析并校验
+    pub fn from_toml(text: &str) -> Result<Self, LocationConfigError> {
+        let config: Self = toml::from_str(text).map_err(|e| LocationConfigError::Parse(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// 从 JSON 文本解析并校验
+    pub fn from_json(text: &str) -> Result<Self, LocationConfigError> {
+        let config: Self = serde_json::from_str(text).map_err(|e| LocationConfigError::Parse(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// 从文件加载（`.json` 按 JSON 解析，其余按 TOML 解析）
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LocationConfigError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) {
+            Self::from_json(&text)
+        } else {
+            Self::from_toml(&text)
+        }
+    }
+
+    /// 校验配置：正则都能编译，步行时间的楼名不为空
+    pub fn validate(&self) -> Result<(), LocationConfigError> {
+        if let Some(walk) = self.walking.iter().find(|w| w.from.trim().is_empty() || w.to.trim().is_empty()) {
+            return Err(LocationConfigError::Invalid(format!(
+                "walking time '{}' -> '{}': building name is required",
+                walk.from, walk.to
+            )));
+        }
+        self.compile().map(|_| ())
+    }
+
+    fn compile(&self) -> Result<Vec<Regex>, LocationConfigError> {
+        self.rules
+            .iter()
+            .map(|rule| {
+                Regex::new(&rule.pattern)
+                    .map_err(|e| LocationConfigError::Invalid(format!("rule '{}': {}", rule.name, e)))
+            })
+            .collect()
+    }
+}
+
+/// 由房间号推断楼层：取数字部分去掉最后两位（如 1205 -> 12）
+fn floor_from_room(room: &str) -> Option<i32> {
+    let digits: String = room.chars().filter(|c| c.is_ascii_digit()).collect();
+    if digits.len() < 3 {
+        return None;
+    }
+    digits[..digits.len() - 2].parse().ok()
+}
+
+impl LocationParser {
+    pub fn new(config: LocationConfig) -> Result<Self, LocationConfigError> {
+        config.validate()?;
+        let patterns = config.compile()?;
+        Ok(Self { config, patterns })
+    }
+
+    pub fn config(&self) -> &LocationConfig {
+        &self.config
+    }
+
+    /// 解析教室字符串；线上课程和没有规则匹配的教室只设置标记
+    pub fn parse(&self, raw: &str) -> Location {
+        let text = raw.trim();
+        let has_any = |keywords: &[String]| keywords.iter().any(|k| !k.is_empty() && text.contains(k.as_str()));
+        let mut location = Location {
+            raw: raw.to_string(),
+            campus: None,
+            building: None,
+            floor: None,
+            room: None,
+            online: has_any(&self.config.online_keywords),
+            lab: has_any(&self.config.lab_keywords),
+            sports: has_any(&self.config.sports_keywords),
+        };
+
+        if !location.online && !text.is_empty() {
+            if let Some(caps) = self.patterns.iter().find_map(|re| re.captures(text)) {
+                let group = |name: &str| {
+                    caps.name(name)
+                        .map(|m| m.as_str().trim().to_string())
+                        .filter(|s| !s.is_empty())
+                };
+                location.campus = group("campus");
+                location.building = group("building");
+                location.room = group("room");
+                location.floor = group("floor")
+                    .and_then(|f| f.parse().ok())
+                    .or_else(|| location.room.as_deref().and_then(floor_from_room));
+            }
+            if location.campus.is_none() {
+                location.campus = self.config.default_campus.clone();
+            }
+        }
+        location
+    }
+
+    /// 两个地点之间的步行分钟数；线上课程、无法识别楼宇或没有配置时为 None
+    pub fn walk_minutes(&self, from: &Location, to: &Location) -> Option<u32> {
+        if from.online || to.online {
+            return None;
+        }
+        let (a, b) = (from.building.as_deref()?, to.building.as_deref()?);
+        if a == b && from.campus == to.campus {
+            return Some(0);
+        }
+        let listed = self
+            .config
+            .walking
+            .iter()
+            .find(|w| (w.from == a && w.to == b) || (w.from == b && w.to == a));
+        if let Some(walk) = listed {
+            return Some(walk.minutes);
+        }
+        match (&from.campus, &to.campus) {
+            (Some(x), Some(y)) if x != y => self.config.cross_campus_minutes,
+            _ => self.config.default_walk_minutes,
+        }
+    }
+}
+
+static LOCATIONS: Lazy<RwLock<Arc<LocationParser>>> = Lazy::new(|| {
+    let parser = LocationParser::new(LocationConfig::builtin()).expect("builtin location config is valid");
+    RwLock::new(Arc::new(parser))
+});
+
+/// 当前生效的教室解析器
+pub fn current_locations() -> Arc<LocationParser> {
+    LOCATIONS.read().unwrap().clone()
+}
+
+/// 替换当前生效的教室解析配置（先校验，失败时保留原配置）
+pub fn set_locations(config: LocationConfig) -> Result<Arc<LocationParser>, LocationConfigError> {
+    let parser = Arc::new(LocationParser::new(config)?);
+    *LOCATIONS.write().unwrap() = parser.clone();
+    Ok(parser)
+}
+
+/// 重新加载教室解析配置：配置了文件路径时读取文件，否则恢复内置配置
+pub fn reload_locations(path: Option<&str>) -> Result<Arc<LocationParser>, LocationConfigError> {
+    let config = match path {
+        Some(path) => LocationConfig::load(path)?,
+        None => LocationConfig::builtin(),
+    };
+    set_locations(config)
+}
+
+/// 按当前配置解析教室（教室为空时返回 None）
+pub fn parse_location(classroom: Option<&str>) -> Option<Location> {
+    let raw = classroom.filter(|c| !c.trim().is_empty())?;
+    Some(current_locations().parse(raw))
+}
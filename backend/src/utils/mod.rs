@@ -1,9 +1,17 @@
 pub mod cache;
 pub mod config;
 pub mod crypto;
+pub mod drift;
+pub mod holiday;
 pub mod http;
+pub mod location;
 pub mod log;
+pub mod pinyin;
+pub mod rate_limit;
 pub mod response;
 pub mod schedule;
 pub mod simulator;
+pub mod time;
+pub mod toggles;
+pub mod token;
 
@@ -0,0 +1,187 @@
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+
+/// GB2312 一级汉字（3755 个常用字）的拼音，每行 `拼音:汉字`（不带声调，ü 记作 v）
+///
+/// 一级汉字在 GB2312 中按拼音排列，多音字取它在 GB2312 中所在的读音
+const GB2312_LEVEL1: &str = include_str!("../../config/pinyin_gb2312.txt");
+
+/// 一级汉字以外、课表里常见的字（主要是教师姓名用字）
+const SUPPLEMENT: &str = "\
+bei:蓓
+ben:犇
+can:璨
+cen:岑
+chen:琛宸
+chong:翀
+dai:黛
+duo:铎
+fei:霏
+hao:昊皓濠灏颢
+han:晗
+hong:泓
+hui:晖荟蕙
+jia:伽
+jie:婕
+jin:瑾槿
+jing:菁婧璟
+jue:珏
+jun:珺
+ke:珂
+kun:琨
+kuang:邝
+lan:岚
+li:郦
+lin:蔺
+ling:翎
+lu:璐
+miao:淼
+min:闵
+ming:茗
+nan:楠
+qi:琪琦祺
+qian:倩芊茜骞
+qin:覃
+ran:苒
+ren:饪
+rong:榕嵘
+rui:睿芮
+sa:飒
+shen:燊莘
+sheng:晟
+song:崧
+tang:瑭
+tao:韬
+ti:缇
+ting:婷
+wei:玮炜薇
+wen:雯
+xi:曦
+xiao:潇骁
+xin:鑫昕馨
+xu:诩
+xuan:璇萱暄瑄炫
+xun:洵荀
+ya:娅
+yan:闫妍嫣琰焱
+yao:垚
+ye:烨晔
+yi:怡祎羿翊熠苡
+ying:滢瑛
+you:宥
+yu:瑜钰煜昱毓聿
+yue:玥樾
+yun:芸筠赟
+zhang:璋
+zhao:钊
+zhen:祯蓁
+zheng:峥
+zi:梓
+";
+
+/// 多音字在课表里最常见的读音，优先于上面两张表（如 “音乐” 的 “乐”）
+const OVERRIDES: &str = "\
+le:了
+yue:乐
+";
+
+static PINYIN: Lazy<HashMap<char, &'static str>> = Lazy::new(|| {
+    let mut map = HashMap::new();
+    for line in [OVERRIDES, GB2312_LEVEL1, SUPPLEMENT].iter().flat_map(|table| table.lines()) {
+        let Some((syllable, chars)) = line.split_once(':').filter(|_| !line.starts_with('#')) else {
+            continue;
+        };
+        for c in chars.chars() {
+            map.entry(c).or_insert(syllable);
+        }
+    }
+    map
+});
+
+/// 单个汉字的拼音（不在表中时为 None）
+pub fn syllable(c: char) -> Option<&'static str> {
+    PINYIN.get(&c).copied()
+}
+
+/// 把文本切成用于拼音匹配的音节：汉字转拼音，连续的字母数字合成一段（转小写），
+/// 不在表中的字保留原字，空白和标点丢弃
+pub fn syllables(text: &str) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    let mut word = String::new();
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            word.push(c.to_ascii_lowercase());
+            continue;
+        }
+        if !word.is_empty() {
+            result.push(std::mem::take(&mut word));
+        }
+        if let Some(s) = syllable(c) {
+            result.push(s.to_string());
+        } else if c.is_alphanumeric() {
+            result.push(c.to_string());
+        }
+    }
+    if !word.is_empty() {
+        result.push(word);
+    }
+    result
+}
+
+/// 拼音匹配方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinyinMatch {
+    /// 每个音节都完整输入（如 gaodengshuxue、shuxue）
+    Full,
+    /// 音节只输入了开头几个字母（如 gdsx、gaodsx）
+    Initials,
+}
+
+/// 只保留字母数字的小写关键字（含其他字符时为 None）
+fn ascii_query(query: &str) -> Option<Vec<u8>> {
+    let query: String = query.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_lowercase();
+    (!query.is_empty() && query.chars().all(|c| c.is_ascii_alphanumeric())).then(|| query.into_bytes())
+}
+
+/// 从第 i 个音节开始匹配 query[pos..]，返回是否每个用到的音节都完整输入；
+/// `skip` 为 true 时匹配开始后可以跳过中间的音节
+fn walk(syllables: &[String], i: usize, query: &[u8], pos: usize, skip: bool) -> Option<bool> {
+    if pos == query.len() {
+        return Some(true);
+    }
+    let syllable = syllables.get(i)?.as_bytes();
+    let common = syllable.iter().zip(&query[pos..]).take_while(|(a, b)| a == b).count();
+    // 优先整段匹配，再尝试更短的前缀
+    (1..=common)
+        .rev()
+        .find_map(|n| walk(syllables, i + 1, query, pos + n, skip).map(|full| full && n == syllable.len()))
+        .or_else(|| (skip && pos > 0).then(|| walk(syllables, i + 1, query, pos, skip).map(|_| false)).flatten())
+}
+
+/// 输入是否按拼音匹配文本中连续的几个音节
+///
+/// 每个音节可以只输入开头的若干字母，例如 “gdsx”、“gaodsx”、“shux” 都能匹配 “高等数学”
+pub fn match_pinyin(query: &str, text: &str) -> Option<PinyinMatch> {
+    let query = ascii_query(query)?;
+    let syllables = syllables(text);
+    let mut best = None;
+    for start in 0..syllables.len() {
+        match walk(&syllables, start, &query, 0, false) {
+            Some(true) => return Some(PinyinMatch::Full),
+            Some(false) => best = Some(PinyinMatch::Initials),
+            None => {}
+        }
+    }
+    best
+}
+
+/// 输入是否按拼音匹配文本中按顺序出现的几个音节（可以跳过中间的字）
+///
+/// 用于简称，例如 “gaoshu”、“gs” 匹配 “高等数学”
+pub fn match_pinyin_skipping(query: &str, text: &str) -> bool {
+    let Some(query) = ascii_query(query) else {
+        return false;
+    };
+    let syllables = syllables(text);
+    (0..syllables.len()).any(|start| walk(&syllables, start, &query, 0, true).is_some())
+}
@@ -0,0 +1,79 @@
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 固定窗口计数
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    /// 窗口开始时间（秒级时间戳）
+    started_at: u64,
+    /// 窗口内已放行的请求数
+    count: u32,
+}
+
+/// 全局限流计数器（限流键 -> 当前窗口）
+///
+/// 限流键形如 `ip:1.2.3.4` 或 `ucode:<sha256>`，不保存原始 UCode
+static RATE_LIMITS: Lazy<DashMap<String, Window>> = Lazy::new(DashMap::new);
+
+/// 计数器数量超过该值时顺带清理已结束的窗口
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// 上一次清理的时间（秒级时间戳），每个窗口最多清理一次，避免高负载下每个请求都全表扫描
+static LAST_PRUNE: AtomicU64 = AtomicU64::new(0);
+
+/// 获取当前时间戳（秒）
+fn current_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// 记录一次请求并检查是否超出配额
+///
+/// `limit` 为 0 时不限流。超出配额时返回 `Err(retry_after_secs)`，
+/// 即距离当前窗口结束还需等待的秒数（至少为 1）
+pub fn check(key: &str, limit: u32, window_secs: u64) -> Result<(), u64> {
+    check_at(key, limit, window_secs, current_timestamp())
+}
+
+/// 同 [`check`]，使用给定的当前时间（秒级时间戳）
+pub fn check_at(key: &str, limit: u32, window_secs: u64, now: u64) -> Result<(), u64> {
+    if limit == 0 || window_secs == 0 {
+        return Ok(());
+    }
+
+    if RATE_LIMITS.len() > PRUNE_THRESHOLD {
+        let last = LAST_PRUNE.load(Ordering::Relaxed);
+        if now >= last + window_secs
+            && LAST_PRUNE.compare_exchange(last, now, Ordering::Relaxed, Ordering::Relaxed).is_ok()
+        {
+            RATE_LIMITS.retain(|_, w| now < w.started_at + window_secs);
+        }
+    }
+
+    let mut window = RATE_LIMITS
+        .entry(key.to_string())
+        .or_insert(Window { started_at: now, count: 0 });
+
+    if now >= window.started_at + window_secs {
+        *window = Window { started_at: now, count: 0 };
+    }
+
+    if window.count >= limit {
+        let retry_after = (window.started_at + window_secs).saturating_sub(now).max(1);
+        return Err(retry_after);
+    }
+
+    window.count += 1;
+    Ok(())
+}
+
+/// 清空全部限流计数器，返回删除的条目数
+pub fn reset_all() -> usize {
+    let removed = RATE_LIMITS.len();
+    RATE_LIMITS.clear();
+    removed
+}
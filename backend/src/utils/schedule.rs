@@ -1,10 +1,14 @@
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use chrono::{NaiveDate, Datelike, Utc, FixedOffset};
+use chrono::{NaiveDate, NaiveTime, Datelike, Utc, FixedOffset};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use utoipa::ToSchema;
 
 /// 日程表对象
 ///
-/// 根据学校作息时间表删减后手动转储为对象文件，
-/// 考虑到项目只服务于我们船政所以就写死成导出模块到项目里方便直接用了
+/// 每天课程数与课时长来自作息时间配置（见 `config/time_tables.toml`）
 ///
 /// @author AurLemon
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,10 +26,11 @@ pub struct BasicInfo {
 
 impl Default for Schedule {
     fn default() -> Self {
+        let config = current_time_tables();
         Self {
             basic_info: BasicInfo {
-                total_lessons: 10,
-                lesson_duration: 45,
+                total_lessons: config.total_lessons,
+                lesson_duration: config.lesson_duration,
             },
         }
     }
@@ -41,94 +46,318 @@ pub struct CourseTimeTable {
     pub times: Vec<(String, String)>, // (开始时间, 结束时间)
 }
 
-/// 判断日期是否为夏季作息时间
-/// 夏季：6月1日 - 9月30日
-/// 冬季：10月1日 - 次年5月31日
-pub fn is_summer_schedule(date: &str) -> bool {
-    if let Ok(parsed_date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
-        let month = parsed_date.month();
-        let day = parsed_date.day();
+/// 时令区间（MM-DD，含首尾；起始晚于结束时表示跨年）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SeasonRange {
+    /// 作息名，必须在 `time_tables` 中存在
+    #[schema(example = "summer")]
+    pub name: String,
+    #[schema(example = "06-01")]
+    pub start: String,
+    #[schema(example = "09-30")]
+    pub end: String,
+}
+
+/// 某个校区与默认作息不同的时间表
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct CampusTimeTables {
+    /// 作息名 -> 时间表（未写出的作息沿用默认时间表）
+    #[serde(default)]
+    pub time_tables: HashMap<String, Vec<(String, String)>>,
+}
+
+/// 作息时间配置
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TimeTableConfig {
+    /// 每天课程数
+    #[schema(example = 10)]
+    pub total_lessons: u32,
+    /// 每节课时长，单位分钟
+    #[schema(example = 45)]
+    pub lesson_duration: u32,
+    /// 不在任何时令区间内的日期使用的作息
+    #[schema(example = "winter")]
+    pub default_season: String,
+    /// 时令区间，按顺序匹配第一个
+    #[serde(default)]
+    pub seasons: Vec<SeasonRange>,
+    /// 作息名 -> 每节课的起止时间
+    pub time_tables: HashMap<String, Vec<(String, String)>>,
+    /// 校区 -> 与默认不同的时间表
+    #[serde(default)]
+    pub campuses: HashMap<String, CampusTimeTables>,
+}
+
+/// 作息时间配置加载失败的原因
+#[derive(Debug, thiserror::Error)]
+pub enum TimeTableConfigError {
+    #[error("Read time table config failed: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Parse time table config failed: {0}")]
+    Parse(String),
+    #[error("Invalid time table config: {0}")]
+    Invalid(String),
+}
+
+/// 编译时内置的默认配置
+const BUILTIN_TIME_TABLES: &str = include_str!("../../config/time_tables.toml");
+
+/// 解析 MM-DD（按闰年计算，允许 02-29）
+fn parse_month_day(s: &str) -> Option<(u32, u32)> {
+    let date = NaiveDate::parse_from_str(&format!("2000-{}", s.trim()), "%Y-%m-%d").ok()?;
+    Some((date.month(), date.day()))
+}
+
+fn parse_time(s: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(s, "%H:%M").ok()
+}
+
+impl TimeTableConfig {
+    /// 内置配置
+    pub fn builtin() -> Self {
+        Self::from_toml(BUILTIN_TIME_TABLES).expect("builtin time table config is valid")
+    }
+
+    /// 从 TOML 文本解析并校验
+    pub fn from_toml(text: &str) -> Result<Self, TimeTableConfigError> {
+        let config: Self = toml::from_str(text).map_err(|e| TimeTableConfigError::Parse(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// 从 JSON 文本解析并校验
+    pub fn from_json(text: &str) -> Result<Self, TimeTableConfigError> {
+        let config: Self = serde_json::from_str(text).map_err(|e| TimeTableConfigError::Parse(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// 从文件加载（`.json` 按 JSON 解析，其余按 TOML 解析）
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TimeTableConfigError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) {
+            Self::from_json(&text)
+        } else {
+            Self::from_toml(&text)
+        }
+    }
+
+    /// 校验配置：作息名都有对应时间表，节次数一致，时间按顺序且不重叠
+    pub fn validate(&self) -> Result<(), TimeTableConfigError> {
+        let invalid = |msg: String| Err(TimeTableConfigError::Invalid(msg));
+
+        if self.total_lessons == 0 {
+            return invalid("total_lessons must be greater than 0".to_string());
+        }
+        if !self.time_tables.contains_key(&self.default_season) {
+            return invalid(format!("default_season '{}' has no time table", self.default_season));
+        }
+        for season in &self.seasons {
+            if !self.time_tables.contains_key(&season.name) {
+                return invalid(format!("season '{}' has no time table", season.name));
+            }
+            for bound in [&season.start, &season.end] {
+                if parse_month_day(bound).is_none() {
+                    return invalid(format!("season '{}': invalid date '{}', expected MM-DD", season.name, bound));
+                }
+            }
+        }
+        for (name, times) in &self.time_tables {
+            self.validate_table(name, times)?;
+        }
+        for (campus, tables) in &self.campuses {
+            for (name, times) in &tables.time_tables {
+                if !self.time_tables.contains_key(name) {
+                    return invalid(format!("campus '{}': unknown season '{}'", campus, name));
+                }
+                self.validate_table(&format!("{}/{}", campus, name), times)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_table(&self, name: &str, times: &[(String, String)]) -> Result<(), TimeTableConfigError> {
+        let invalid = |msg: String| Err(TimeTableConfigError::Invalid(format!("time table '{}': {}", name, msg)));
+
+        if times.len() != self.total_lessons as usize {
+            return invalid(format!("expected {} lessons, got {}", self.total_lessons, times.len()));
+        }
+        validate_lesson_times(times).or_else(invalid)
+    }
 
-        // 6月1日 - 9月30日为夏季
-        (month == 6 && day >= 1) || (month >= 7 && month <= 9)
-    } else {
-        // 解析失败，默认冬季
-        false
+    /// 日期使用的作息名（日期不合法时为默认作息）
+    pub fn season_for_date(&self, date: &str) -> &str {
+        let Ok(parsed) = NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+            return &self.default_season;
+        };
+        let today = (parsed.month(), parsed.day());
+        self.seasons
+            .iter()
+            .find(|season| match (parse_month_day(&season.start), parse_month_day(&season.end)) {
+                (Some(start), Some(end)) if start <= end => start <= today && today <= end,
+                (Some(start), Some(end)) => today >= start || today <= end,
+                _ => false,
+            })
+            .map(|season| season.name.as_str())
+            .unwrap_or(&self.default_season)
+    }
+
+    /// 某个作息的时间表（指定校区且该校区有差异时使用校区时间表）
+    pub fn time_table(&self, season: &str, campus: Option<&str>) -> Vec<(String, String)> {
+        campus
+            .and_then(|c| self.campuses.get(c))
+            .and_then(|c| c.time_tables.get(season))
+            .or_else(|| self.time_tables.get(season))
+            .or_else(|| self.time_tables.get(&self.default_season))
+            .cloned()
+            .unwrap_or_default()
     }
 }
 
-/// 获取课程时间表（根据日期自动判断冬夏季）
-pub fn get_course_time_table(date: &str) -> CourseTimeTable {
-    if is_summer_schedule(date) {
-        // 夏季作息时间
-        CourseTimeTable {
-            times: vec![
-                ("08:00".to_string(), "08:45".to_string()), // 第1节课
-                ("08:55".to_string(), "09:40".to_string()), // 第2节课
-                ("10:00".to_string(), "10:45".to_string()), // 第3节课
-                ("10:55".to_string(), "11:40".to_string()), // 第4节课
-                ("14:30".to_string(), "15:15".to_string()), // 第5节课
-                ("15:25".to_string(), "16:10".to_string()), // 第6节课
-                ("16:30".to_string(), "17:15".to_string()), // 第7节课
-                ("17:25".to_string(), "18:10".to_string()), // 第8节课
-                ("19:20".to_string(), "20:05".to_string()), // 第9节课
-                ("20:15".to_string(), "21:00".to_string()), // 第10节课
-            ],
+/// 校验每节课的起止时间：格式为 HH:MM，下课晚于上课，且不早于上一节下课
+pub fn validate_lesson_times(times: &[(String, String)]) -> Result<(), String> {
+    if times.is_empty() {
+        return Err("at least one lesson is required".to_string());
+    }
+    let mut previous_end: Option<NaiveTime> = None;
+    for (i, (start, end)) in times.iter().enumerate() {
+        let lesson = i + 1;
+        let (Some(s), Some(e)) = (parse_time(start), parse_time(end)) else {
+            return Err(format!("lesson {}: invalid time, expected HH:MM", lesson));
+        };
+        if s >= e {
+            return Err(format!("lesson {}: starts at {} but ends at {}", lesson, start, end));
         }
-    } else {
-        // 冬季作息时间
-        CourseTimeTable {
-            times: vec![
-                ("08:00".to_string(), "08:45".to_string()), // 第1节课
-                ("08:55".to_string(), "09:40".to_string()), // 第2节课
-                ("10:00".to_string(), "10:45".to_string()), // 第3节课
-                ("10:55".to_string(), "11:40".to_string()), // 第4节课
-                ("14:00".to_string(), "14:45".to_string()), // 第5节课
-                ("14:55".to_string(), "15:40".to_string()), // 第6节课
-                ("16:00".to_string(), "16:45".to_string()), // 第7节课
-                ("16:55".to_string(), "17:40".to_string()), // 第8节课
-                ("19:00".to_string(), "19:45".to_string()), // 第9节课
-                ("19:55".to_string(), "20:40".to_string()), // 第10节课
-            ],
+        if previous_end.is_some_and(|p| s < p) {
+            return Err(format!("lesson {}: overlaps the previous lesson", lesson));
         }
+        previous_end = Some(e);
     }
+    Ok(())
+}
+
+/// 带生效日期的时间表版本（管理员在数据库中维护，用于考试周、节前调整等临时作息）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TimeTableVersion {
+    pub id: i32,
+    /// 版本名称
+    #[schema(example = "期末考试周")]
+    pub name: String,
+    /// 生效起始日期（YYYY-MM-DD，含）
+    #[schema(example = "2025-01-06")]
+    pub effective_from: String,
+    /// 生效结束日期（YYYY-MM-DD，含）
+    #[schema(example = "2025-01-12")]
+    pub effective_to: String,
+    /// 每节课的起止时间
+    pub times: Vec<(String, String)>,
+    /// 创建时间（毫秒时间戳）
+    pub created_at: i64,
 }
 
+impl TimeTableVersion {
+    /// 日期是否在生效范围内（日期均为 YYYY-MM-DD，可直接按字符串比较）
+    pub fn covers(&self, date: &str) -> bool {
+        self.effective_from.as_str() <= date && date <= self.effective_to.as_str()
+    }
+
+    /// 在 `time_tables` / `week_seasons` 中引用该版本使用的键
+    pub fn key(&self) -> String {
+        format!("version:{}", self.id)
+    }
+}
+
+/// 从多个版本中选出某天生效的版本：范围最短的优先（更具体），相同时后创建的优先
+pub fn select_time_table_version<'a>(versions: &'a [TimeTableVersion], date: &str) -> Option<&'a TimeTableVersion> {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?.format("%Y-%m-%d").to_string();
+    versions
+        .iter()
+        .filter(|v| v.covers(&date))
+        .min_by_key(|v| {
+            let span = match (
+                NaiveDate::parse_from_str(&v.effective_from, "%Y-%m-%d"),
+                NaiveDate::parse_from_str(&v.effective_to, "%Y-%m-%d"),
+            ) {
+                (Ok(from), Ok(to)) => (to - from).num_days(),
+                _ => i64::MAX,
+            };
+            (span, std::cmp::Reverse(v.id))
+        })
+}
+
+static TIME_TABLE_VERSIONS: Lazy<RwLock<Arc<Vec<TimeTableVersion>>>> = Lazy::new(|| RwLock::new(Arc::new(Vec::new())));
+
+/// 当前已加载的时间表版本
+pub fn current_time_table_versions() -> Arc<Vec<TimeTableVersion>> {
+    TIME_TABLE_VERSIONS.read().unwrap().clone()
+}
+
+/// 替换已加载的时间表版本（数据库变更后调用）
+pub fn set_time_table_versions(versions: Vec<TimeTableVersion>) {
+    *TIME_TABLE_VERSIONS.write().unwrap() = Arc::new(versions);
+}
+
+/// 某天生效的时间表版本
+pub fn time_table_version_for_date(date: &str) -> Option<TimeTableVersion> {
+    select_time_table_version(&current_time_table_versions(), date).cloned()
+}
 
+static TIME_TABLES: Lazy<RwLock<Arc<TimeTableConfig>>> =
+    Lazy::new(|| RwLock::new(Arc::new(TimeTableConfig::builtin())));
 
-/// 获取冬季作息时间表
-pub fn get_winter_course_time_table() -> CourseTimeTable {
+/// 当前生效的作息时间配置
+pub fn current_time_tables() -> Arc<TimeTableConfig> {
+    TIME_TABLES.read().unwrap().clone()
+}
+
+/// 替换当前生效的作息时间配置（先校验，失败时保留原配置）
+pub fn set_time_tables(config: TimeTableConfig) -> Result<Arc<TimeTableConfig>, TimeTableConfigError> {
+    config.validate()?;
+    let config = Arc::new(config);
+    *TIME_TABLES.write().unwrap() = config.clone();
+    Ok(config)
+}
+
+/// 重新加载作息时间配置：配置了文件路径时读取文件，否则恢复内置配置
+pub fn reload_time_tables(path: Option<&str>) -> Result<Arc<TimeTableConfig>, TimeTableConfigError> {
+    let config = match path {
+        Some(path) => TimeTableConfig::load(path)?,
+        None => TimeTableConfig::builtin(),
+    };
+    set_time_tables(config)
+}
+
+/// 判断日期是否为夏季作息时间（时令区间见作息时间配置）
+pub fn is_summer_schedule(date: &str) -> bool {
+    season_for_date(date) == "summer"
+}
+
+/// 日期使用的作息名
+pub fn season_for_date(date: &str) -> String {
+    current_time_tables().season_for_date(date).to_string()
+}
+
+/// 获取课程时间表（当天有生效的时间表版本时使用该版本，否则根据日期判断时令）
+pub fn get_course_time_table(date: &str) -> CourseTimeTable {
+    get_campus_course_time_table(date, None)
+}
+
+/// 获取指定校区的课程时间表（时间表版本对所有校区生效）
+pub fn get_campus_course_time_table(date: &str, campus: Option<&str>) -> CourseTimeTable {
+    if let Some(version) = time_table_version_for_date(date) {
+        return CourseTimeTable { times: version.times };
+    }
+    let config = current_time_tables();
     CourseTimeTable {
-        times: vec![
-            ("08:00".to_string(), "08:45".to_string()), // 第1节课
-            ("08:55".to_string(), "09:40".to_string()), // 第2节课
-            ("10:00".to_string(), "10:45".to_string()), // 第3节课
-            ("10:55".to_string(), "11:40".to_string()), // 第4节课
-            ("14:00".to_string(), "14:45".to_string()), // 第5节课
-            ("14:55".to_string(), "15:40".to_string()), // 第6节课
-            ("16:00".to_string(), "16:45".to_string()), // 第7节课
-            ("16:55".to_string(), "17:40".to_string()), // 第8节课
-            ("19:00".to_string(), "19:45".to_string()), // 第9节课
-            ("19:55".to_string(), "20:40".to_string()), // 第10节课
-        ],
-    }
-}
-
-/// 获取夏季作息时间表
-pub fn get_summer_course_time_table() -> CourseTimeTable {
+        times: config.time_table(config.season_for_date(date), campus),
+    }
+}
+
+/// 获取某个作息的时间表
+pub fn get_season_course_time_table(season: &str) -> CourseTimeTable {
     CourseTimeTable {
-        times: vec![
-            ("08:00".to_string(), "08:45".to_string()), // 第1节课
-            ("08:55".to_string(), "09:40".to_string()), // 第2节课
-            ("10:00".to_string(), "10:45".to_string()), // 第3节课
-            ("10:55".to_string(), "11:40".to_string()), // 第4节课
-            ("14:30".to_string(), "15:15".to_string()), // 第5节课
-            ("15:25".to_string(), "16:10".to_string()), // 第6节课
-            ("16:30".to_string(), "17:15".to_string()), // 第7节课
-            ("17:25".to_string(), "18:10".to_string()), // 第8节课
-            ("19:20".to_string(), "20:05".to_string()), // 第9节课
-            ("20:15".to_string(), "21:00".to_string()), // 第10节课
-        ],
+        times: current_time_tables().time_table(season, None),
     }
 }
 
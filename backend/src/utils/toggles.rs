@@ -0,0 +1,64 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+use utoipa::ToSchema;
+
+/// 运行时开关
+///
+/// 通过管理接口修改，仅保存在内存里，重启后恢复默认值
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RuntimeToggles {
+    /// 是否启用课表缓存（关闭后既不读缓存也不写缓存）
+    pub schedule_cache: bool,
+    /// 静态 Basic Auth 返回 401 时是否回退到浏览器模拟器
+    pub simulator_fallback: bool,
+    /// 维护模式（开启后需要请求学校服务器的接口直接返回 503）
+    pub maintenance: bool,
+    /// 是否对需要请求学校服务器的接口限流
+    pub rate_limit: bool,
+}
+
+impl Default for RuntimeToggles {
+    fn default() -> Self {
+        Self {
+            schedule_cache: true,
+            simulator_fallback: true,
+            maintenance: false,
+            rate_limit: true,
+        }
+    }
+}
+
+/// 运行时开关的局部更新（未传的字段保持不变）
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct RuntimeTogglesPatch {
+    pub schedule_cache: Option<bool>,
+    pub simulator_fallback: Option<bool>,
+    pub maintenance: Option<bool>,
+    pub rate_limit: Option<bool>,
+}
+
+static TOGGLES: Lazy<RwLock<RuntimeToggles>> = Lazy::new(|| RwLock::new(RuntimeToggles::default()));
+
+/// 获取当前运行时开关
+pub fn get_toggles() -> RuntimeToggles {
+    TOGGLES.read().unwrap().clone()
+}
+
+/// 更新运行时开关，返回更新后的完整开关
+pub fn update_toggles(patch: &RuntimeTogglesPatch) -> RuntimeToggles {
+    let mut toggles = TOGGLES.write().unwrap();
+    if let Some(v) = patch.schedule_cache {
+        toggles.schedule_cache = v;
+    }
+    if let Some(v) = patch.simulator_fallback {
+        toggles.simulator_fallback = v;
+    }
+    if let Some(v) = patch.maintenance {
+        toggles.maintenance = v;
+    }
+    if let Some(v) = patch.rate_limit {
+        toggles.rate_limit = v;
+    }
+    toggles.clone()
+}
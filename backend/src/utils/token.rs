@@ -0,0 +1,91 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::utils::crypto::{constant_time_eq, hmac_sha256};
+
+/// 固定的 JWT 头（只签发 HS256）
+const JWT_HEADER: &str = r#"{"alg":"HS256","typ":"JWT"}"#;
+
+/// 会话令牌载荷
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionClaims {
+    /// UCode 哈希（用于限流等无需解密 UCode 的场景）
+    pub sub: String,
+    /// 会话 ID（对应 sessions 表）
+    pub sid: String,
+    /// 签发时间（秒级时间戳）
+    pub iat: i64,
+    /// 过期时间（秒级时间戳）
+    pub exp: i64,
+}
+
+/// 令牌校验错误
+#[derive(Debug, thiserror::Error)]
+pub enum TokenError {
+    #[error("Malformed token")]
+    Malformed,
+    #[error("Invalid token signature")]
+    BadSignature,
+    #[error("Token has expired")]
+    Expired,
+}
+
+/// 获取当前时间戳（秒）
+fn current_timestamp() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+/// 签发 HS256 JWT
+pub fn sign(claims: &SessionClaims, secret: &str) -> String {
+    let header = URL_SAFE_NO_PAD.encode(JWT_HEADER);
+    let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(claims).expect("claims are serializable"));
+    let signing_input = format!("{}.{}", header, payload);
+    let signature = URL_SAFE_NO_PAD.encode(hmac_sha256(secret.as_bytes(), signing_input.as_bytes()));
+    format!("{}.{}", signing_input, signature)
+}
+
+/// 校验 HS256 JWT 的签名和有效期
+pub fn verify(token: &str, secret: &str) -> Result<SessionClaims, TokenError> {
+    let mut parts = token.split('.');
+    let (Some(header), Some(payload), Some(signature), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(TokenError::Malformed);
+    };
+
+    let header_json = URL_SAFE_NO_PAD.decode(header).map_err(|_| TokenError::Malformed)?;
+    let header_value: serde_json::Value =
+        serde_json::from_slice(&header_json).map_err(|_| TokenError::Malformed)?;
+    if header_value.get("alg").and_then(|v| v.as_str()) != Some("HS256") {
+        return Err(TokenError::Malformed);
+    }
+
+    let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| TokenError::Malformed)?;
+    let signing_input = format!("{}.{}", header, payload);
+    let expected = hmac_sha256(secret.as_bytes(), signing_input.as_bytes());
+    if !constant_time_eq(&signature, &expected) {
+        return Err(TokenError::BadSignature);
+    }
+
+    let payload = URL_SAFE_NO_PAD.decode(payload).map_err(|_| TokenError::Malformed)?;
+    let claims: SessionClaims = serde_json::from_slice(&payload).map_err(|_| TokenError::Malformed)?;
+    if claims.exp <= current_timestamp() {
+        return Err(TokenError::Expired);
+    }
+
+    Ok(claims)
+}
+
+/// 从 Authorization 头中取出 Bearer 令牌
+pub fn bearer_token(authorization: &str) -> Option<&str> {
+    let (scheme, token) = authorization.trim().split_once(' ')?;
+    if scheme.eq_ignore_ascii_case("bearer") && !token.trim().is_empty() {
+        Some(token.trim())
+    } else {
+        None
+    }
+}
@@ -0,0 +1,37 @@
+// tests/api_key_test.rs
+// API Key 每日配额测试（使用内存数据库，不依赖学校服务器）
+use backend::db::connection::init_db;
+use backend::services::api_key::{authorize, create_api_key, list_api_keys, ApiKeyError, ApiScope};
+
+#[tokio::test]
+async fn test_daily_quota_is_enforced_atomically() {
+    std::env::set_var("DATABASE_URL", "sqlite::memory:");
+    let db = init_db().await.expect("初始化数据库失败");
+
+    let created = create_api_key(&db, "bot", &[ApiScope::Schedule], Some(3), None).await.unwrap();
+
+    // 并发请求也只有配额内的几次能通过
+    let attempts = futures::future::join_all((0..10).map(|_| authorize(&db, &created.key, ApiScope::Schedule))).await;
+    let passed = attempts.iter().filter(|r| r.is_ok()).count();
+    assert_eq!(passed, 3);
+    assert!(attempts
+        .iter()
+        .filter_map(|r| r.as_ref().err())
+        .all(|e| matches!(e, ApiKeyError::QuotaExceeded { .. })));
+
+    let keys = list_api_keys(&db).await.unwrap();
+    assert_eq!(keys.iter().find(|k| k.id == created.info.id).unwrap().usage_today, 3);
+
+    // 配额为 0 时一次都不能用
+    let blocked = create_api_key(&db, "blocked", &[ApiScope::Schedule], Some(0), None).await.unwrap();
+    assert!(matches!(
+        authorize(&db, &blocked.key, ApiScope::Schedule).await,
+        Err(ApiKeyError::QuotaExceeded { .. })
+    ));
+
+    // 不限配额
+    let unlimited = create_api_key(&db, "unlimited", &[ApiScope::Schedule], None, None).await.unwrap();
+    for _ in 0..5 {
+        authorize(&db, &unlimited.key, ApiScope::Schedule).await.unwrap();
+    }
+}
@@ -0,0 +1,148 @@
+// tests/calendar_test.rs
+// 按日期查询课表测试（不依赖学校服务器）
+mod common;
+
+use backend::parser::schedule::{DayCourse, WeekInfo};
+use backend::services::calendar::{
+    day_schedule, find_week, next_lesson, parse_date, resolve_week, season_time_tables, week_seasons, WeekStatus,
+};
+use chrono::NaiveDate;
+use common::{course, day};
+use std::collections::HashMap;
+
+/// 2024-09-02（周一）开始的两周
+fn fixture() -> (HashMap<u32, Vec<DayCourse>>, Vec<WeekInfo>) {
+    let semester_weeks = vec![
+        WeekInfo { week: 1, start_time: "2024-09-02".to_string(), end_time: "2024-09-08".to_string() },
+        WeekInfo { week: 2, start_time: "2024-09-09".to_string(), end_time: "2024-09-15".to_string() },
+    ];
+    let mut weeks = HashMap::new();
+    weeks.insert(1, vec![day(1, vec![course("A", 1, 1, 2)]), day(2, vec![course("B", 2, 5, 2)])]);
+    weeks.insert(2, vec![day(3, vec![course("C", 3, 3, 2)])]);
+    (weeks, semester_weeks)
+}
+
+fn at(date: &str, time: &str) -> chrono::NaiveDateTime {
+    chrono::NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M").unwrap()
+}
+
+#[test]
+fn test_find_week() {
+    let (_, semester_weeks) = fixture();
+    let date = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+    assert_eq!(find_week(&semester_weeks, date("2024-09-02")).map(|w| w.week), Some(1));
+    assert_eq!(find_week(&semester_weeks, date("2024-09-15")).map(|w| w.week), Some(2));
+    assert!(find_week(&semester_weeks, date("2024-09-01")).is_none());
+    assert!(find_week(&semester_weeks, date("2024-09-16")).is_none());
+}
+
+#[test]
+fn test_day_schedule_uses_time_table() {
+    let (weeks, semester_weeks) = fixture();
+
+    // 周二第 5-6 节（9 月为夏季作息）
+    let day = day_schedule(&weeks, &semester_weeks, parse_date("2024-09-03").unwrap());
+    assert_eq!(day.week, Some(1));
+    assert_eq!(day.weekday, 2);
+    assert_eq!(day.season, "summer");
+    assert_eq!(day.lessons.len(), 1);
+    assert_eq!(day.lessons[0].start_time.as_deref(), Some("14:30"));
+    assert_eq!(day.lessons[0].end_time.as_deref(), Some("16:10"));
+
+    // 学期外的日期没有课
+    let outside = day_schedule(&weeks, &semester_weeks, parse_date("2024-10-01").unwrap());
+    assert_eq!(outside.week, None);
+    assert!(outside.lessons.is_empty());
+}
+
+#[test]
+fn test_next_lesson() {
+    let (weeks, semester_weeks) = fixture();
+
+    let next = next_lesson(&weeks, &semester_weeks, at("2024-09-02", "07:30")).unwrap();
+    assert_eq!(next.lesson.course.code, "A");
+    assert_eq!(next.minutes_until, 30);
+    assert_eq!(next.starts_at, "2024-09-02T08:00:00+08:00");
+
+    // 第一节已经开始，下一节是周二下午
+    let next = next_lesson(&weeks, &semester_weeks, at("2024-09-02", "08:01")).unwrap();
+    assert_eq!(next.lesson.course.code, "B");
+    assert_eq!(next.date, "2024-09-03");
+
+    // 跨周
+    let next = next_lesson(&weeks, &semester_weeks, at("2024-09-04", "12:00")).unwrap();
+    assert_eq!((next.week, next.weekday), (2, 3));
+
+    assert!(next_lesson(&weeks, &semester_weeks, at("2024-09-11", "12:00")).is_none());
+
+    // 开学前
+    let next = next_lesson(&weeks, &semester_weeks, at("2024-08-01", "12:00")).unwrap();
+    assert_eq!(next.date, "2024-09-02");
+}
+
+#[test]
+fn test_next_lesson_several_weeks_ahead() {
+    // 十周的学期只有第 9 周有课
+    let semester_weeks: Vec<WeekInfo> = (1..=10)
+        .map(|n| {
+            let start = parse_date("2024-09-02").unwrap() + chrono::Duration::weeks(n - 1);
+            WeekInfo {
+                week: n as u32,
+                start_time: start.format("%Y-%m-%d").to_string(),
+                end_time: (start + chrono::Duration::days(6)).format("%Y-%m-%d").to_string(),
+            }
+        })
+        .collect();
+    let mut weeks = HashMap::new();
+    weeks.insert(9, vec![day(5, vec![course("D", 5, 1, 2)])]);
+
+    let next = next_lesson(&weeks, &semester_weeks, at("2024-09-04", "12:00")).unwrap();
+    assert_eq!((next.week, next.date.as_str()), (9, "2024-11-01"));
+    assert!(next_lesson(&weeks, &semester_weeks, at("2024-11-01", "12:00")).is_none());
+}
+
+#[test]
+fn test_resolve_week() {
+    let (weeks, mut semester_weeks) = fixture();
+    let date = |s: &str| parse_date(s).unwrap();
+
+    let current = resolve_week(&weeks, &semester_weeks, date("2024-09-10"));
+    assert_eq!(current.status, WeekStatus::InSemester);
+    assert_eq!(current.week, Some(2));
+    assert_eq!(current.display.unwrap().days.len(), 1);
+
+    let before = resolve_week(&weeks, &semester_weeks, date("2024-08-30"));
+    assert_eq!(before.status, WeekStatus::BeforeSemester);
+    assert_eq!(before.days_until_start, Some(3));
+    assert_eq!(before.display.unwrap().week.week, 1);
+
+    let after = resolve_week(&weeks, &semester_weeks, date("2024-09-20"));
+    assert_eq!(after.status, WeekStatus::AfterSemester);
+    assert_eq!(after.days_since_end, Some(5));
+    assert_eq!(after.display.unwrap().week.week, 2);
+
+    // 第 2 周整周放假，周列表中间有缺口
+    semester_weeks[1] = WeekInfo { week: 3, start_time: "2024-09-16".to_string(), end_time: "2024-09-22".to_string() };
+    let gap = resolve_week(&weeks, &semester_weeks, date("2024-09-10"));
+    assert_eq!(gap.status, WeekStatus::Gap);
+    assert_eq!(gap.week, None);
+    assert_eq!(gap.display.unwrap().week.week, 3);
+
+    assert_eq!(resolve_week(&weeks, &[], date("2024-09-10")).status, WeekStatus::NoSemester);
+}
+
+#[test]
+fn test_week_seasons_cross_june_first() {
+    // 2025-05-26（周一）至 2025-06-01（周日）：周日起改用夏季作息
+    let semester_weeks = vec![
+        WeekInfo { week: 14, start_time: "2025-05-26".to_string(), end_time: "2025-06-01".to_string() },
+        WeekInfo { week: 15, start_time: "2025-06-02".to_string(), end_time: "2025-06-08".to_string() },
+    ];
+    let seasons = week_seasons(&semester_weeks);
+    assert_eq!(seasons[&14][..6], vec!["winter"; 6][..]);
+    assert_eq!(seasons[&14][6], "summer");
+    assert!(seasons[&15].iter().all(|s| s == "summer"));
+
+    let tables = season_time_tables();
+    assert!(tables.contains_key("winter") && tables.contains_key("summer"));
+}
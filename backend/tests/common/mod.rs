@@ -0,0 +1,63 @@
+// tests/common/mod.rs
+// 测试共用的课表构造函数（课程信息与按天排好的节次）
+#![allow(dead_code)]
+
+use backend::parser::schedule::{CourseInfo, CourseSlot, DayCourse, WeekInfo};
+use std::collections::HashMap;
+
+/// 构造一门课：名称为“课程{code}”，默认在 A101 由张老师上课
+pub fn course(code: &str, weekday: u32, number: u32, continuous: u32) -> CourseInfo {
+    CourseInfo {
+        name: format!("课程{}", code),
+        classroom: Some("A101".to_string()),
+        class: "计算机2401".to_string(),
+        teacher: vec!["张老师".to_string()],
+        course_number: number,
+        weekday,
+        color: "#FF5733".to_string(),
+        continuous_course: continuous,
+        code: code.to_string(),
+    }
+}
+
+/// 替换课程的教室和任课教师
+pub fn taught(info: CourseInfo, classroom: &str, teachers: &[&str]) -> CourseInfo {
+    CourseInfo {
+        classroom: Some(classroom.to_string()),
+        teacher: teachers.iter().map(|t| t.to_string()).collect(),
+        ..info
+    }
+}
+
+/// 一天共 `slots` 节，每门课从起始节次开始按 `continuous_course` 占满连续节次
+pub fn day_with_slots(weekday: u32, slots: u32, courses: Vec<CourseInfo>) -> DayCourse {
+    let mut course: Vec<CourseSlot> = (1..=slots)
+        .map(|n| CourseSlot { course_number: n, course_info: None })
+        .collect();
+    for info in courses {
+        for n in info.course_number..info.course_number + info.continuous_course.max(1) {
+            course[(n - 1) as usize].course_info = Some(info.clone());
+        }
+    }
+    DayCourse { weekday, course }
+}
+
+/// 一天共 10 节，见 [`day_with_slots`]
+pub fn day(weekday: u32, courses: Vec<CourseInfo>) -> DayCourse {
+    day_with_slots(weekday, 10, courses)
+}
+
+/// 2024-10-14（周一）开始的第 7、8 两周，`days(周次)` 给出每周的课程
+pub fn semester_with(days: impl Fn(u32) -> Vec<DayCourse>) -> (HashMap<u32, Vec<DayCourse>>, Vec<WeekInfo>) {
+    let semester_weeks = vec![
+        WeekInfo { week: 7, start_time: "2024-10-14".to_string(), end_time: "2024-10-20".to_string() },
+        WeekInfo { week: 8, start_time: "2024-10-21".to_string(), end_time: "2024-10-27".to_string() },
+    ];
+    let weeks = semester_weeks.iter().map(|w| (w.week, days(w.week))).collect();
+    (weeks, semester_weeks)
+}
+
+/// 见 [`semester_with`]，每周一 1-2 节 MATH101、周三 5-6 节 PHY201
+pub fn semester_fixture() -> (HashMap<u32, Vec<DayCourse>>, Vec<WeekInfo>) {
+    semester_with(|_| vec![day(1, vec![course("MATH101", 1, 1, 2)]), day(3, vec![course("PHY201", 3, 5, 2)])])
+}
@@ -0,0 +1,95 @@
+// tests/compact_test.rs
+// 紧凑格式课表编码与解码测试（不依赖学校服务器）
+mod common;
+
+use backend::parser::schedule::DayCourse;
+use backend::services::compact::{decode, encode, SlotRef};
+use common::{course, day_with_slots};
+use std::collections::HashMap;
+
+/// 一周 7 天、每天 12 节，`courses` 为 (星期, 起始节次, 课程代码)
+fn week(courses: &[(u32, u32, &str)]) -> Vec<DayCourse> {
+    (1..=7)
+        .map(|weekday| {
+            let infos = courses
+                .iter()
+                .filter(|(d, _, _)| *d == weekday)
+                .map(|(d, start, code)| course(code, *d, *start, 2))
+                .collect();
+            day_with_slots(weekday, 12, infos)
+        })
+        .collect()
+}
+
+/// 16 周：第 5 周周一停课，第 9 周周三换成 PE101，第 12 周周五加一节 LAB301
+fn semester() -> HashMap<u32, Vec<DayCourse>> {
+    (1..=16)
+        .map(|n| {
+            let mut courses = vec![(1, 1, "MATH101"), (3, 3, "ENG102"), (5, 5, "CS201")];
+            match n {
+                5 => courses.retain(|c| c.0 != 1),
+                9 => courses[1] = (3, 3, "PE101"),
+                12 => courses.push((5, 7, "LAB301")),
+                _ => {}
+            }
+            (n, week(&courses))
+        })
+        .collect()
+}
+
+#[test]
+fn test_encode_template_and_exceptions() {
+    let compact = encode(&semester());
+
+    assert_eq!(compact.weeks, (1..=16).collect::<Vec<_>>());
+    assert_eq!(compact.courses.len(), 5);
+    assert_eq!(compact.shape.len(), 7);
+    assert_eq!(compact.template.len(), 6);
+    assert_eq!(compact.exceptions.keys().copied().collect::<Vec<_>>(), vec![5, 9, 12]);
+
+    let removed = &compact.exceptions[&5];
+    assert_eq!(removed.removed, vec![SlotRef { weekday: 1, course_number: 1 }, SlotRef { weekday: 1, course_number: 2 }]);
+    assert!(removed.added.is_empty() && removed.changed.is_empty() && removed.shape.is_none());
+
+    let changed = &compact.exceptions[&9];
+    assert_eq!(changed.changed.len(), 2);
+    assert_eq!(compact.courses[changed.changed[0].course].code, "PE101");
+
+    let added = &compact.exceptions[&12];
+    assert_eq!(added.added.iter().map(|s| s.course_number).collect::<Vec<_>>(), vec![7, 8]);
+
+    // 紧凑格式明显更小
+    let full = serde_json::to_string(&semester()).unwrap().len();
+    let small = serde_json::to_string(&compact).unwrap().len();
+    assert!(small * 5 < full, "compact {} vs full {}", small, full);
+}
+
+#[test]
+fn test_decode_round_trip() {
+    let weeks = semester();
+    let compact = encode(&weeks);
+    assert_eq!(decode(&compact), weeks);
+
+    // 经过 JSON 序列化后仍能还原
+    let json = serde_json::to_string(&compact).unwrap();
+    assert_eq!(decode(&serde_json::from_str(&json).unwrap()), weeks);
+}
+
+#[test]
+fn test_round_trip_irregular_weeks() {
+    let mut weeks = semester();
+    // 某一周只有 5 天、每天 10 节
+    let mut short = week(&[(2, 1, "MATH101")]);
+    short.truncate(5);
+    short.iter_mut().for_each(|d| d.course.truncate(10));
+    weeks.insert(17, short);
+    // 空周
+    weeks.insert(18, week(&[]));
+
+    let compact = encode(&weeks);
+    assert!(compact.exceptions[&17].shape.is_some());
+    assert!(compact.exceptions[&18].shape.is_none());
+    assert_eq!(decode(&compact), weeks);
+
+    assert!(decode(&encode(&HashMap::new())).is_empty());
+}
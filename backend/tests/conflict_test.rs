@@ -0,0 +1,68 @@
+// tests/conflict_test.rs
+// 上课冲突检测测试（不依赖学校服务器）
+mod common;
+
+use backend::parser::schedule::parse_week_course;
+use backend::services::course::{detect_conflicts, detect_week_conflicts, ConflictKind};
+use common::{course, day};
+use std::collections::HashMap;
+
+#[test]
+fn test_no_conflicts() {
+    // 上游在每一节都重复给出同一门课时不算冲突
+    let mut repeated = day(1, vec![course("A", 1, 1, 2)]);
+    repeated.course[1].course_info = Some(course("A", 1, 1, 2));
+    let days = vec![repeated, day(2, vec![course("B", 2, 3, 2), course("C", 2, 5, 2)])];
+    assert!(detect_week_conflicts(1, &days).is_empty());
+}
+
+#[test]
+fn test_same_slot_conflict() {
+    // 同一天出现两条记录，第 3-4 节分别排了不同的课
+    let days = vec![day(3, vec![course("A", 3, 3, 2)]), day(3, vec![course("B", 3, 3, 2)])];
+    let conflicts = detect_week_conflicts(5, &days);
+    assert_eq!(conflicts.len(), 1);
+    let conflict = &conflicts[0];
+    assert_eq!((conflict.week, conflict.weekday, conflict.start, conflict.end), (5, 3, 3, 4));
+    assert_eq!(conflict.kind, ConflictKind::SameSlot);
+    let codes: Vec<&str> = conflict.courses.iter().map(|c| c.code.as_str()).collect();
+    assert_eq!(codes, vec!["A", "B"]);
+}
+
+#[test]
+fn test_span_overlap_conflict() {
+    // A 从第 1 节连上 3 节，B 排在第 3 节
+    let days = vec![day(2, vec![course("A", 2, 1, 3), course("B", 2, 3, 2)])];
+    let conflicts = detect_week_conflicts(1, &days);
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!((conflicts[0].start, conflicts[0].end), (3, 3));
+    assert_eq!(conflicts[0].kind, ConflictKind::SpanOverlap);
+
+    let mut weeks = HashMap::new();
+    weeks.insert(2, days.clone());
+    weeks.insert(1, days);
+    let all = detect_conflicts(&weeks);
+    assert_eq!(all.iter().map(|c| c.week).collect::<Vec<_>>(), vec![1, 2]);
+}
+
+#[test]
+fn test_same_slot_conflict_from_upstream_rows() {
+    // 周二第 3-4 节排了 A 和 B 两门课，上游把 B 挪到了第 5-6 节，但记录里仍然声明第 3 节
+    let a = "课程A|A101|计算机2401|张老师|3|2|#FF5733|2|A";
+    let b = "课程B|B201|计算机2401|李老师|3|2|#00FF00|2|B";
+    let mut tuesday = vec![String::new(); 10];
+    tuesday[2] = a.to_string();
+    tuesday[3] = a.to_string();
+    tuesday[4] = b.to_string();
+    tuesday[5] = b.to_string();
+    let week = parse_week_course(vec![vec![String::new(); 10], tuesday]);
+    assert!(week.diagnostics.is_empty());
+
+    let conflicts = detect_week_conflicts(3, &week.days);
+    assert_eq!(conflicts.len(), 1);
+    let conflict = &conflicts[0];
+    assert_eq!((conflict.weekday, conflict.start, conflict.end), (2, 3, 4));
+    assert_eq!(conflict.kind, ConflictKind::SameSlot);
+    let codes: Vec<&str> = conflict.courses.iter().map(|c| c.code.as_str()).collect();
+    assert_eq!(codes, vec!["A", "B"]);
+}
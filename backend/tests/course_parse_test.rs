@@ -0,0 +1,79 @@
+// tests/course_parse_test.rs
+// 课程字符串严格解析测试（不依赖学校服务器）
+use backend::parser::schedule::{parse_course_string_lenient, parse_course_string_strict, parse_week_course, CourseParseError};
+
+const VALID: &str = "高等数学|A101|计算机2401|张老师;李老师|1|1|#FF5733|2|MATH101";
+
+#[test]
+fn test_parse_valid_and_empty() {
+    let info = parse_course_string_strict(VALID).unwrap().unwrap();
+    assert_eq!(info.name, "高等数学");
+    assert_eq!(info.classroom.as_deref(), Some("A101"));
+    assert_eq!(info.teacher, vec!["张老师".to_string(), "李老师".to_string()]);
+    assert_eq!((info.course_number, info.weekday, info.continuous_course), (1, 1, 2));
+    assert_eq!(info.code, "MATH101");
+
+    // 空字符串表示无课
+    assert_eq!(parse_course_string_strict(""), Ok(None));
+    let no_room = parse_course_string_strict("体育|无|计算机2401|王老师|3|2|#00FF00|2|PE101").unwrap().unwrap();
+    assert_eq!(no_room.classroom, None);
+}
+
+#[test]
+fn test_parse_errors_carry_field() {
+    let err = parse_course_string_strict("高等数学|A101|计算机2401").unwrap_err();
+    assert_eq!(err, CourseParseError::MissingFields { expected: 9, found: 3 });
+    assert_eq!(err.field_index(), None);
+
+    let err = parse_course_string_strict("高等数学|A101|计算机2401|张老师|三|1|#FF5733|2|MATH101").unwrap_err();
+    assert_eq!(err.field_index(), Some(4));
+    assert_eq!(err.value(), Some("三"));
+    assert_eq!(err.to_string(), "field 4 (course_number): invalid number '三'");
+
+    let err = parse_course_string_strict("高等数学|A101|计算机2401|张老师|1|1|#FF5733||MATH101").unwrap_err();
+    assert_eq!(err.field_index(), Some(7));
+}
+
+#[test]
+fn test_parse_week_collects_diagnostics() {
+    let data = vec![
+        vec![VALID.to_string(), VALID.to_string(), String::new()],
+        vec![String::new(), "坏数据".to_string(), "物理|B201|计算机2401|赵老师|3|x|#0000FF|1|PHY201".to_string()],
+    ];
+    let week = parse_week_course(data);
+
+    assert_eq!(week.days.len(), 2);
+    assert!(week.days[0].course[0].course_info.is_some());
+    // 字段数不足的时段按无课处理
+    assert!(week.days[1].course[1].course_info.is_none());
+    // 数字字段出错的课程仍保留，星期取所在位置
+    let kept = week.days[1].course[2].course_info.as_ref().unwrap();
+    assert_eq!((kept.code.as_str(), kept.weekday, kept.course_number), ("PHY201", 2, 3));
+
+    assert_eq!(week.diagnostics.len(), 2);
+    let first = &week.diagnostics[0];
+    assert_eq!((first.weekday, first.course_number), (2, 2));
+    assert_eq!(first.raw, "坏数据");
+    assert_eq!(first.field_index, None);
+    let second = &week.diagnostics[1];
+    assert_eq!((second.weekday, second.course_number), (2, 3));
+    assert_eq!((second.field_index, second.value.as_deref()), (Some(5), Some("x")));
+}
+
+#[test]
+fn test_bad_numbers_keep_course_with_fallbacks() {
+    let raw = "高等数学|A101|计算机2401|张老师|三|一|#FF5733|两|MATH101";
+    let (info, errors) = parse_course_string_lenient(raw, 4, 7).unwrap();
+    let info = info.unwrap();
+    assert_eq!((info.course_number, info.weekday, info.continuous_course), (7, 4, 0));
+    assert_eq!(info.name, "高等数学");
+    assert_eq!(errors.iter().map(|e| e.field_index()).collect::<Vec<_>>(), vec![Some(4), Some(5), Some(7)]);
+    assert!(parse_course_string_lenient("高等数学|A101", 4, 7).is_err());
+
+    // 经过 parse_week_course 后课程和诊断都在
+    let week = parse_week_course(vec![vec![String::new(), "高等数学|A101|计算机2401|张老师|1|1|#FF5733|x|MATH101".to_string()]]);
+    let course = week.days[0].course[1].course_info.as_ref().unwrap();
+    assert_eq!((course.code.as_str(), course.continuous_course), ("MATH101", 0));
+    assert_eq!(week.diagnostics.len(), 1);
+    assert_eq!((week.diagnostics[0].course_number, week.diagnostics[0].field_index), (2, Some(7)));
+}
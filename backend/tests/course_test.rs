@@ -71,6 +71,7 @@ async fn test_course_service() {
         &semester,
         &client,
         &config,
+        true,
     ).await {
         Ok(all_courses) => {
             // 按周数排序，保证输出一致性
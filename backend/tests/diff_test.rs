@@ -0,0 +1,158 @@
+// tests/diff_test.rs
+// 课表变动检测与 Webhook 签名测试（不依赖学校服务器）
+mod common;
+
+use backend::db::connection::init_db;
+use backend::parser::schedule::DayCourse;
+use backend::services::course::collect_lessons;
+use backend::services::diff::{diff_weeks, record_and_diff, LessonChange};
+use backend::services::webhook::{sign_payload, validate_url};
+use common::{course, day, taught};
+use sea_orm::{ConnectionTrait, DatabaseBackend, Statement};
+use std::collections::HashMap;
+
+fn week(days: Vec<DayCourse>) -> HashMap<u32, Vec<DayCourse>> {
+    HashMap::from([(1, days)])
+}
+
+#[test]
+fn test_collect_lessons_merges_continuous_slots() {
+    let days = vec![day(
+        1,
+        vec![
+            course("A", 1, 1, 2),
+            course("A", 1, 3, 2),
+        ],
+    )];
+    let lessons = collect_lessons(&days);
+    // 同一门课连着上 4 节，但每次只声明 2 节，应拆成两节课
+    assert_eq!(lessons.len(), 2);
+    assert_eq!((lessons[0].start, lessons[0].end), (1, 2));
+    assert_eq!((lessons[1].start, lessons[1].end), (3, 4));
+}
+
+#[test]
+fn test_identical_schedule_has_no_changes() {
+    let old = week(vec![day(1, vec![course("A", 1, 1, 2)])]);
+    assert!(diff_weeks(&old, &old.clone()).is_empty());
+}
+
+#[test]
+fn test_detects_added_removed_and_moved() {
+    let old = week(vec![
+        day(1, vec![course("A", 1, 1, 2), taught(course("B", 1, 5, 2), "B201", &["李老师"])]),
+        day(2, vec![course("A", 2, 1, 2)]),
+    ]);
+    let new = week(vec![
+        day(1, vec![course("A", 1, 1, 2)]),
+        day(2, vec![course("A", 2, 3, 2), taught(course("C", 2, 7, 2), "C301", &["王老师"])]),
+    ]);
+
+    let diff = diff_weeks(&old, &new);
+    let kinds: Vec<&str> = diff
+        .changes
+        .iter()
+        .map(|c| match c {
+            LessonChange::Added { .. } => "added",
+            LessonChange::Removed { .. } => "removed",
+            LessonChange::Moved { .. } => "moved",
+            LessonChange::ClassroomChanged { .. } => "classroom",
+            LessonChange::TeacherChanged { .. } => "teacher",
+        })
+        .collect();
+    // B 课取消；周二 A 课从 1-2 节调到 3-4 节；C 课新增
+    assert_eq!(kinds, vec!["removed", "moved", "added"]);
+
+    match &diff.changes[1] {
+        LessonChange::Moved { from, to, .. } => {
+            assert_eq!((from.weekday, from.start, from.end), (2, 1, 2));
+            assert_eq!((to.weekday, to.start, to.end), (2, 3, 4));
+        }
+        other => panic!("应为调课，实际为 {:?}", other),
+    }
+}
+
+#[test]
+fn test_detects_classroom_and_teacher_changes() {
+    let old = week(vec![day(3, vec![course("A", 3, 1, 2)])]);
+    let new = week(vec![day(3, vec![taught(course("A", 3, 1, 2), "A202", &["赵老师"])])]);
+
+    let diff = diff_weeks(&old, &new);
+    assert_eq!(diff.changes.len(), 2);
+    assert!(matches!(
+        &diff.changes[0],
+        LessonChange::ClassroomChanged { old, new, .. }
+            if old.as_deref() == Some("A101") && new.as_deref() == Some("A202")
+    ));
+    assert!(matches!(&diff.changes[1], LessonChange::TeacherChanged { .. }));
+}
+
+#[test]
+fn test_missing_weeks_are_not_reported_as_removed() {
+    let mut old = week(vec![day(1, vec![course("A", 1, 1, 2)])]);
+    old.insert(2, vec![day(1, vec![course("A", 1, 1, 2)])]);
+    let new = week(vec![day(1, vec![course("A", 1, 1, 2)])]);
+
+    assert!(diff_weeks(&old, &new).is_empty());
+}
+
+#[tokio::test]
+async fn test_unreadable_state_is_reset_without_changes() {
+    std::env::set_var("DATABASE_URL", "sqlite::memory:");
+    let db = init_db().await.expect("初始化数据库失败");
+
+    let old = week(vec![day(1, vec![course("A", 1, 1, 2)])]);
+    assert!(record_and_diff(&db, "user", "2024-2025", 1, &old).await.unwrap().is_none());
+
+    db.execute(Statement::from_string(
+        DatabaseBackend::Sqlite,
+        "UPDATE schedule_states SET weeks_json = 'not json'".to_string(),
+    ))
+    .await
+    .unwrap();
+
+    // 读不出旧课表时不把整张课表当成新增
+    let new = week(vec![day(1, vec![course("A", 1, 1, 2), course("B", 1, 5, 2)])]);
+    assert!(record_and_diff(&db, "user", "2024-2025", 1, &new).await.unwrap().is_none());
+
+    // 覆盖后的状态可以正常比较
+    let diff = record_and_diff(&db, "user", "2024-2025", 1, &old).await.unwrap().unwrap();
+    assert_eq!(diff.changes.len(), 1);
+    assert!(matches!(&diff.changes[0], LessonChange::Removed { .. }));
+}
+
+#[tokio::test]
+async fn test_new_semester_resets_state_without_changes() {
+    std::env::set_var("DATABASE_URL", "sqlite::memory:");
+    let db = init_db().await.expect("初始化数据库失败");
+
+    let mut old = week(vec![day(1, vec![course("A", 1, 1, 2)])]);
+    old.insert(18, vec![day(1, vec![course("A", 1, 1, 2)])]);
+    assert!(record_and_diff(&db, "user", "2024-2025", 1, &old).await.unwrap().is_none());
+
+    // 新学期的第 1 周不与上学期的第 1 周比较
+    let new = week(vec![day(2, vec![course("B", 2, 3, 2)])]);
+    assert!(record_and_diff(&db, "user", "2024-2025", 2, &new).await.unwrap().is_none());
+
+    // 上学期的第 18 周不再保留，新学期出现第 18 周时算新增
+    let mut later = new.clone();
+    later.insert(18, vec![day(1, vec![course("A", 1, 1, 2)])]);
+    let diff = record_and_diff(&db, "user", "2024-2025", 2, &later).await.unwrap().unwrap();
+    assert_eq!(diff.changes.len(), 1);
+    assert!(matches!(&diff.changes[0], LessonChange::Added { week: 18, .. }));
+}
+
+#[test]
+fn test_webhook_signature_and_url_validation() {
+    let signature = sign_payload("secret", 1700000000, "{}");
+    assert!(signature.starts_with("sha256="));
+    assert_eq!(signature, sign_payload("secret", 1700000000, "{}"));
+    assert_ne!(signature, sign_payload("secret", 1700000001, "{}"));
+
+    assert!(validate_url("https://example.com/hook").is_ok());
+    assert!(validate_url("ftp://example.com/hook").is_err());
+    assert!(validate_url("http://localhost:8080/hook").is_err());
+    assert!(validate_url("http://127.0.0.1/hook").is_err());
+    assert!(validate_url("http://192.168.1.10/hook").is_err());
+    assert!(validate_url("http://[::1]/hook").is_err());
+}
@@ -0,0 +1,129 @@
+// tests/drift_test.rs
+// 上游结构指纹与漂移检测测试（不依赖学校服务器）
+use backend::db::connection::init_db;
+use backend::services::drift::{list_events, persist};
+use backend::utils::drift::{
+    compare, fingerprint, observe, recent_drift, redact, DriftRecord, FieldChangeKind, UpstreamEndpoint,
+};
+use serde_json::json;
+
+fn school_year(data: serde_json::Value) -> serde_json::Value {
+    json!({ "code": 200, "msg": null, "data": data })
+}
+
+#[test]
+fn test_fingerprint_paths_and_field_counts() {
+    let body = school_year(json!([
+        { "xn": "2024-2025", "xq": "1", "dqxqbj": "1", "qsrq": "2024-09-01", "jsrq": null }
+    ]));
+    let fp = fingerprint(&body, false);
+    assert_eq!(fp.get("code").map(String::as_str), Some("number"));
+    assert_eq!(fp.get("msg").map(String::as_str), Some("null"));
+    assert_eq!(fp.get("data").map(String::as_str), Some("array"));
+    assert_eq!(fp.get("data[]").map(String::as_str), Some("object"));
+    assert_eq!(fp.get("data[].dqxqbj").map(String::as_str), Some("string"));
+
+    let week = json!({ "code": 200, "data": [["高等数学|A101|计算机2401|张老师|1|1|#FF5733|2|MATH101", ""]] });
+    let fp = fingerprint(&week, true);
+    assert_eq!(fp.get("data[][]").map(String::as_str), Some("string"));
+    assert_eq!(fp.get("data[][]|fields").map(String::as_str), Some("fields:9"));
+}
+
+#[test]
+fn test_compare_detects_renames_and_ignores_optional_shapes() {
+    let baseline = fingerprint(
+        &school_year(json!([{ "xn": "2024-2025", "xq": "1", "dqxqbj": "1", "qsrq": null, "jsrq": null }])),
+        false,
+    );
+
+    // 可选字段从 null 变成字符串、数组为空都不算漂移
+    let filled = fingerprint(
+        &school_year(json!([{ "xn": "2024-2025", "xq": "1", "dqxqbj": "1", "qsrq": "2024-09-01", "jsrq": "2025-01-15" }])),
+        false,
+    );
+    let result = compare(&baseline, &filled);
+    assert!(result.changes.is_empty());
+    assert_eq!(result.learned.get("data[].qsrq").map(String::as_str), Some("string"));
+    assert!(compare(&baseline, &fingerprint(&school_year(json!([])), false)).changes.is_empty());
+
+    // 字段改名、类型变化
+    let renamed = fingerprint(
+        &school_year(json!([{ "xn": "2024-2025", "xq": 1, "current": "1", "qsrq": null, "jsrq": null }])),
+        false,
+    );
+    let changes = compare(&baseline, &renamed).changes;
+    let kinds: Vec<_> = changes.iter().map(|c| (c.path.as_str(), c.kind)).collect();
+    assert!(kinds.contains(&("data[].current", FieldChangeKind::Added)));
+    assert!(kinds.contains(&("data[].dqxqbj", FieldChangeKind::Removed)));
+    assert!(kinds.contains(&("data[].xq", FieldChangeKind::Changed)));
+}
+
+#[test]
+fn test_course_field_count_change() {
+    let baseline = fingerprint(&json!({ "data": [["a|b|c|d|1|1|#fff|1|X"]] }), true);
+    let observed = fingerprint(&json!({ "data": [["a|b|c|d|1|1|#fff|1|X|extra"]] }), true);
+    let changes = compare(&baseline, &observed).changes;
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].path, "data[][]|fields");
+    assert_eq!(changes[0].expected.as_deref(), Some("fields:9"));
+    assert_eq!(changes[0].actual.as_deref(), Some("fields:10"));
+
+    // 整周没课不算字段消失
+    let empty = fingerprint(&json!({ "data": [["", ""]] }), true);
+    assert!(compare(&baseline, &empty).changes.is_empty());
+}
+
+#[test]
+fn test_redact_sample() {
+    let body = json!({
+        "access_token": "secret",
+        "user_info": { "username": "245800001", "phone": "13800001234" },
+        "data": [["高等数学|A101|计算机2401|张老师|1|1|#FF5733|2|MATH101", "", "x", "y"]],
+        "code": 200
+    });
+    let sample = redact(&body);
+    assert_eq!(sample["access_token"], "***");
+    assert_eq!(sample["user_info"]["phone"], "***");
+    assert_eq!(sample["code"], 200);
+    // 数组只保留前两个，课程字符串保留字段数
+    assert_eq!(sample["data"][0], json!(["*|*|*|*|*|*|*|*|*", ""]));
+}
+
+#[test]
+fn test_observe_learns_then_records_drift_once() {
+    let body = json!({ "code": 200, "data": [["1", "2024-09-01", "2024-09-07"]] });
+    observe(UpstreamEndpoint::Semester, &body);
+    observe(UpstreamEndpoint::Semester, &body);
+    assert!(recent_drift().iter().all(|e| e.endpoint != "getSemesterbyXn"));
+
+    let drifted = json!({ "code": 200, "data": [{ "week": 1, "start": "2024-09-01" }] });
+    observe(UpstreamEndpoint::Semester, &drifted);
+    observe(UpstreamEndpoint::Semester, &drifted);
+    let events: Vec<_> = recent_drift().into_iter().filter(|e| e.endpoint == "getSemesterbyXn").collect();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].occurrences, 2);
+    assert!(events[0].changes.iter().any(|c| c.path == "data[]" && c.kind == FieldChangeKind::Changed));
+    assert_eq!(events[0].sample["data"][0]["start"], "***");
+}
+
+#[tokio::test]
+async fn test_persist_merges_repeated_drift() {
+    std::env::set_var("DATABASE_URL", "sqlite::memory:");
+    let db = init_db().await.expect("初始化数据库失败");
+
+    let baseline = fingerprint(&school_year(json!([{ "xn": "2024-2025" }])), false);
+    let record = DriftRecord::Baseline { endpoint: "getXn".to_string(), fingerprint: baseline.clone() };
+    persist(&db, record.clone()).await.unwrap();
+    persist(&db, record).await.unwrap();
+
+    observe(UpstreamEndpoint::SchoolYear, &school_year(json!([{ "xn": "2024-2025" }])));
+    observe(UpstreamEndpoint::SchoolYear, &school_year(json!([{ "xn": 2024 }])));
+    let event = recent_drift().into_iter().find(|e| e.endpoint == "getXn").expect("应记录漂移");
+    persist(&db, DriftRecord::Drift(event.clone())).await.unwrap();
+    persist(&db, DriftRecord::Drift(event)).await.unwrap();
+
+    let events = list_events(&db, 10).await.unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].occurrences, 2);
+    assert_eq!(events[0].changes[0].path, "data[].xn");
+}
@@ -0,0 +1,96 @@
+// tests/holiday_test.rs
+// 法定节假日与调休测试（不依赖学校服务器）
+mod common;
+
+use backend::parser::schedule::{DayCourse, WeekInfo};
+use backend::services::calendar::{day_schedule, next_lesson, parse_date};
+use backend::services::occurrence::{expand_occurrences, OccurrenceRange};
+use backend::utils::holiday::{set_holidays, HolidayCalendar, HolidayCalendarError, SpecialDayKind};
+use common::{course, day};
+use std::collections::HashMap;
+
+const CALENDAR: &str = r#"
+[[holidays]]
+name = "国庆节"
+start = "2024-10-01"
+end = "2024-10-07"
+
+[[makeup_days]]
+name = "国庆节调休"
+date = "2024-09-29"
+follows = "2024-10-04"
+"#;
+
+/// 第 1 周 2024-09-23 ~ 09-29，第 2 周 2024-09-30 ~ 10-06
+fn fixture() -> (HashMap<u32, Vec<DayCourse>>, Vec<WeekInfo>) {
+    let semester_weeks = vec![
+        WeekInfo { week: 1, start_time: "2024-09-23".to_string(), end_time: "2024-09-29".to_string() },
+        WeekInfo { week: 2, start_time: "2024-09-30".to_string(), end_time: "2024-10-06".to_string() },
+    ];
+    let mut weeks = HashMap::new();
+    weeks.insert(1, vec![day(1, vec![course("A", 1, 1, 2)])]);
+    weeks.insert(
+        2,
+        vec![day(1, vec![course("B", 1, 1, 2)]), day(2, vec![course("C", 2, 1, 2)]), day(5, vec![course("F", 5, 1, 2)])],
+    );
+    (weeks, semester_weeks)
+}
+
+#[test]
+fn test_builtin_calendar() {
+    let calendar = HolidayCalendar::builtin();
+    let national_day = calendar.special_day(parse_date("2024-10-01").unwrap()).unwrap();
+    assert_eq!(national_day.kind, SpecialDayKind::Holiday);
+
+    let makeup = calendar.special_day(parse_date("2024-09-29").unwrap()).unwrap();
+    assert_eq!(makeup.kind, SpecialDayKind::Makeup);
+    assert_eq!(makeup.follows_weekday, Some(5));
+
+    assert!(calendar.special_day(parse_date("2024-10-08").unwrap()).is_none());
+    let week = calendar.special_days_between(parse_date("2024-09-30").unwrap(), parse_date("2024-10-06").unwrap());
+    assert_eq!(week.len(), 6);
+}
+
+#[test]
+fn test_invalid_calendar_is_rejected() {
+    let reversed = CALENDAR.replace("end = \"2024-10-07\"", "end = \"2024-09-07\"");
+    assert!(matches!(HolidayCalendar::from_toml(&reversed), Err(HolidayCalendarError::Invalid(_))));
+
+    let on_holiday = CALENDAR.replace("date = \"2024-09-29\"", "date = \"2024-10-02\"");
+    assert!(matches!(HolidayCalendar::from_toml(&on_holiday), Err(HolidayCalendarError::Invalid(_))));
+}
+
+#[test]
+fn test_day_schedule_applies_holidays() {
+    set_holidays(HolidayCalendar::from_toml(CALENDAR).unwrap()).unwrap();
+    let (weeks, semester_weeks) = fixture();
+
+    // 国庆节当天的课停上
+    let holiday = day_schedule(&weeks, &semester_weeks, parse_date("2024-09-30").unwrap());
+    assert_eq!(holiday.special, None);
+    let holiday = day_schedule(&weeks, &semester_weeks, parse_date("2024-10-01").unwrap());
+    assert_eq!(holiday.special.as_ref().map(|s| s.kind), Some(SpecialDayKind::Holiday));
+    assert!(holiday.lessons.is_empty());
+    assert_eq!(holiday.cancelled[0].course.code, "C");
+
+    // 周日调休上 10 月 4 日（第 2 周周五）的课
+    let makeup = day_schedule(&weeks, &semester_weeks, parse_date("2024-09-29").unwrap());
+    assert_eq!(makeup.week, Some(1));
+    assert_eq!(makeup.weekday, 7);
+    assert_eq!(makeup.lessons[0].course.code, "F");
+
+    // 下一节课跳过假期，落在调休日
+    let at = |s: &str| chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+    let next = next_lesson(&weeks, &semester_weeks, at("2024-09-24 12:00")).unwrap();
+    assert_eq!(next.date, "2024-09-29");
+    let next = next_lesson(&weeks, &semester_weeks, at("2024-09-30 12:00"));
+    assert!(next.is_none());
+
+    // 展开上课列表时，停上的课只在 include_cancelled 时出现
+    let range = |include_cancelled| OccurrenceRange { include_cancelled, ..Default::default() };
+    let occurrences = expand_occurrences(&weeks, &semester_weeks, range(false));
+    assert!(occurrences.iter().all(|o| !o.cancelled));
+    let with_cancelled = expand_occurrences(&weeks, &semester_weeks, range(true));
+    let cancelled: Vec<_> = with_cancelled.iter().filter(|o| o.cancelled).map(|o| o.course.code.as_str()).collect();
+    assert_eq!(cancelled, vec!["C", "F"]);
+}
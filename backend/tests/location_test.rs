@@ -0,0 +1,99 @@
+// tests/location_test.rs
+// 教室解析测试（不依赖学校服务器）
+mod common;
+
+use backend::utils::location::{LocationConfig, LocationParser, LocationRule};
+
+fn builtin() -> LocationParser {
+    LocationParser::new(LocationConfig::builtin()).unwrap()
+}
+
+#[test]
+fn test_parse_building_and_room() {
+    let parser = builtin();
+
+    let loc = parser.parse("南校区教学楼A101");
+    assert_eq!(loc.raw, "南校区教学楼A101");
+    assert_eq!(loc.campus.as_deref(), Some("南校区"));
+    assert_eq!(loc.building.as_deref(), Some("教学楼A"));
+    assert_eq!(loc.room.as_deref(), Some("101"));
+    assert_eq!(loc.floor, Some(1));
+    assert!(!loc.online && !loc.lab && !loc.sports);
+
+    let loc = parser.parse("图书馆1205");
+    assert_eq!((loc.building.as_deref(), loc.floor), (Some("图书馆"), Some(12)));
+    assert_eq!(loc.campus, None);
+
+    let loc = parser.parse("实验楼B-302");
+    assert_eq!((loc.building.as_deref(), loc.room.as_deref()), (Some("实验楼B"), Some("302")));
+    assert!(loc.lab);
+
+    let loc = parser.parse("综合楼5层多媒体教室");
+    assert_eq!((loc.building.as_deref(), loc.floor, loc.room.as_deref()), (Some("综合楼"), Some(5), Some("多媒体教室")));
+}
+
+#[test]
+fn test_flags_and_fallback() {
+    let parser = builtin();
+
+    let loc = parser.parse("东校区田径场");
+    assert!(loc.sports);
+    assert_eq!((loc.campus.as_deref(), loc.building.as_deref()), (Some("东校区"), Some("田径场")));
+    assert_eq!(loc.floor, None);
+
+    // 线上课程不解析楼宇
+    let loc = parser.parse("线上（腾讯会议）");
+    assert!(loc.online);
+    assert_eq!(loc.building, None);
+
+    let loc = parser.parse("计算机机房3");
+    assert!(loc.lab);
+    assert_eq!(loc.building.as_deref(), Some("计算机机房3"));
+}
+
+#[test]
+fn test_custom_rules_and_validation() {
+    let config = LocationConfig {
+        default_campus: Some("主校区".to_string()),
+        rules: vec![LocationRule {
+            name: "楼号-房间".to_string(),
+            pattern: r"^(?P<building>\d+)-(?P<floor>\d+)-(?P<room>\d+)$".to_string(),
+        }],
+        ..Default::default()
+    };
+    let parser = LocationParser::new(config).unwrap();
+    let loc = parser.parse("7-3-12");
+    assert_eq!(loc.campus.as_deref(), Some("主校区"));
+    assert_eq!((loc.building.as_deref(), loc.floor, loc.room.as_deref()), (Some("7"), Some(3), Some("12")));
+    // 没有规则匹配时只保留原始字符串和校区
+    let loc = parser.parse("操场");
+    assert_eq!(loc.building, None);
+
+    assert!(LocationConfig::from_toml("[[rules]]\nname = \"bad\"\npattern = \"(\"\n").is_err());
+}
+
+#[test]
+fn test_day_schedule_groups_by_building() {
+    use backend::parser::schedule::{DayCourse, WeekInfo};
+    use backend::services::calendar::{day_schedule, parse_date};
+    use backend::services::course::collect_locations;
+    use common::{course, day, taught};
+    use std::collections::HashMap;
+
+    let courses = vec![
+        taught(course("A", 1, 1, 1), "教学楼A101", &["张老师"]),
+        taught(course("B", 1, 2, 1), "图书馆1205", &["张老师"]),
+        taught(course("C", 1, 3, 1), "教学楼A305", &["张老师"]),
+    ];
+    let semester_weeks = vec![WeekInfo { week: 7, start_time: "2024-10-14".to_string(), end_time: "2024-10-20".to_string() }];
+    let weeks: HashMap<u32, Vec<DayCourse>> = HashMap::from([(7, vec![day(1, courses)])]);
+
+    let schedule = day_schedule(&weeks, &semester_weeks, parse_date("2024-10-14").unwrap());
+    assert_eq!(schedule.lessons[1].location.as_ref().and_then(|l| l.building.as_deref()), Some("图书馆"));
+    let groups: Vec<_> = schedule.buildings.iter().map(|g| (g.building.as_str(), g.lessons.clone())).collect();
+    assert_eq!(groups, vec![("教学楼A", vec![1, 3]), ("图书馆", vec![2])]);
+
+    let locations = collect_locations(&weeks);
+    assert_eq!(locations.len(), 3);
+    assert_eq!(locations["教学楼A305"].floor, Some(3));
+}
@@ -0,0 +1,51 @@
+// tests/occurrence_test.rs
+// 上课列表展开测试（不依赖学校服务器）
+mod common;
+
+use backend::services::calendar::parse_date;
+use backend::services::occurrence::{expand_occurrences, OccurrenceRange};
+use common::semester_fixture;
+
+#[test]
+fn test_expand_all_occurrences() {
+    let (weeks, semester_weeks) = semester_fixture();
+    let occurrences = expand_occurrences(&weeks, &semester_weeks, OccurrenceRange::default());
+    assert_eq!(occurrences.len(), 4);
+
+    // 连续节次合并为一次上课，按上课时刻排序
+    let first = &occurrences[0];
+    assert_eq!(first.id, "MATH101-7-1-1");
+    assert_eq!((first.start_slot, first.end_slot), (1, 2));
+    assert_eq!(first.start, "2024-10-14T08:00:00+08:00");
+    assert_eq!(first.end, "2024-10-14T09:40:00+08:00");
+    assert_eq!(occurrences[1].id, "PHY201-7-3-5");
+    assert_eq!(occurrences[1].start, "2024-10-16T14:00:00+08:00");
+    assert_eq!(occurrences[3].id, "PHY201-8-3-5");
+
+    // 重复展开时 ID 不变
+    let again = expand_occurrences(&weeks, &semester_weeks, OccurrenceRange::default());
+    let ids = |list: &[backend::services::occurrence::LessonOccurrence]| list.iter().map(|o| o.id.clone()).collect::<Vec<_>>();
+    assert_eq!(ids(&occurrences), ids(&again));
+}
+
+#[test]
+fn test_range_filtering() {
+    let (weeks, semester_weeks) = semester_fixture();
+    let range = |from: &str, to: &str| OccurrenceRange {
+        from: parse_date(from),
+        to: parse_date(to),
+        include_cancelled: false,
+    };
+
+    let week8 = expand_occurrences(&weeks, &semester_weeks, range("2024-10-21", "2024-10-27"));
+    assert_eq!(week8.len(), 2);
+    assert!(week8.iter().all(|o| o.week == 8));
+
+    let single = expand_occurrences(&weeks, &semester_weeks, range("2024-10-16", "2024-10-16"));
+    assert_eq!(single.len(), 1);
+    assert_eq!(single[0].course.code, "PHY201");
+
+    // 超出学期的范围被截断
+    assert_eq!(expand_occurrences(&weeks, &semester_weeks, range("2024-01-01", "2024-12-31")).len(), 4);
+    assert!(expand_occurrences(&weeks, &semester_weeks, range("2024-11-01", "2024-11-30")).is_empty());
+}
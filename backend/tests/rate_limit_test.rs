@@ -0,0 +1,46 @@
+// tests/rate_limit_test.rs
+// 固定窗口限流测试（不依赖学校服务器）
+use backend::utils::rate_limit::{check, check_at};
+
+#[test]
+fn test_limit_within_window() {
+    let key = "test:limit";
+    for _ in 0..3 {
+        assert!(check_at(key, 3, 60, 1_000).is_ok());
+    }
+    assert_eq!(check_at(key, 3, 60, 1_000), Err(60));
+    // 其他键不受影响
+    assert!(check_at("test:limit-other", 3, 60, 1_000).is_ok());
+}
+
+#[test]
+fn test_retry_after_counts_down() {
+    let key = "test:retry-after";
+    assert!(check_at(key, 1, 60, 2_000).is_ok());
+    assert_eq!(check_at(key, 1, 60, 2_010), Err(50));
+    assert_eq!(check_at(key, 1, 60, 2_059), Err(1));
+}
+
+#[test]
+fn test_window_resets() {
+    let key = "test:reset";
+    assert!(check_at(key, 2, 60, 3_000).is_ok());
+    assert!(check_at(key, 2, 60, 3_030).is_ok());
+    assert!(check_at(key, 2, 60, 3_059).is_err());
+
+    // 窗口结束后重新计数
+    assert!(check_at(key, 2, 60, 3_060).is_ok());
+    assert!(check_at(key, 2, 60, 3_061).is_ok());
+    assert_eq!(check_at(key, 2, 60, 3_061), Err(59));
+}
+
+#[test]
+fn test_zero_limit_is_unlimited() {
+    for _ in 0..100 {
+        assert!(check("test:unlimited", 0, 60).is_ok());
+    }
+    // 窗口长度为 0 同样不限流
+    for _ in 0..100 {
+        assert!(check("test:no-window", 1, 0).is_ok());
+    }
+}
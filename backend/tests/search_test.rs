@@ -0,0 +1,109 @@
+// tests/search_test.rs
+// 课表搜索与拼音匹配测试（不依赖学校服务器）
+mod common;
+
+use backend::parser::schedule::{CourseInfo, DayCourse, WeekInfo};
+use backend::services::occurrence::OccurrenceRange;
+use backend::services::search::{match_text, search, MatchKind, SearchField};
+use backend::utils::pinyin::{match_pinyin, match_pinyin_skipping, syllable, syllables, PinyinMatch};
+use common::{course, day, semester_with, taught};
+use std::collections::HashMap;
+
+/// 每周一、二、四各一门课
+fn fixture() -> (HashMap<u32, Vec<DayCourse>>, Vec<WeekInfo>) {
+    semester_with(|_| {
+        [
+            (1, "高等数学", "MATH101", 1, "教学楼A101", "张伟"),
+            (2, "大学英语", "ENG102", 3, "教学楼B203", "李芳"),
+            (4, "数据结构", "CS201", 5, "实验楼301", "王磊"),
+        ]
+        .into_iter()
+        .map(|(weekday, name, code, number, classroom, teacher)| {
+            let info = taught(course(code, weekday, number, 2), classroom, &[teacher]);
+            day(weekday, vec![CourseInfo { name: name.to_string(), ..info }])
+        })
+        .collect()
+    })
+}
+
+#[test]
+fn test_pinyin_matching() {
+    assert_eq!(syllables("高等数学A"), vec!["gao", "deng", "shu", "xue", "a"]);
+    assert_eq!(syllables("教学楼 A101"), vec!["jiao", "xue", "lou", "a101"]);
+
+    assert_eq!(match_pinyin("gaodengshuxue", "高等数学"), Some(PinyinMatch::Full));
+    assert_eq!(match_pinyin("shuxue", "高等数学"), Some(PinyinMatch::Full));
+    assert_eq!(match_pinyin("gdsx", "高等数学"), Some(PinyinMatch::Initials));
+    assert_eq!(match_pinyin("GaoShu", "高等数学"), None);
+    assert!(match_pinyin_skipping("GaoShu", "高等数学"));
+    assert!(!match_pinyin_skipping("dgsx", "高等数学"));
+    assert_eq!(match_pinyin("gaodsx", "高等数学"), Some(PinyinMatch::Initials));
+    assert_eq!(match_pinyin("zw", "张伟"), Some(PinyinMatch::Initials));
+    assert_eq!(match_pinyin("gs", "高等数学"), None);
+    assert_eq!(match_pinyin("高数", "高等数学"), None);
+}
+
+#[test]
+fn test_pinyin_covers_common_characters() {
+    assert_eq!(
+        syllables("马克思主义基本原理"),
+        vec!["ma", "ke", "si", "zhu", "yi", "ji", "ben", "yuan", "li"]
+    );
+    assert_eq!(match_pinyin("mkszy", "马克思主义基本原理"), Some(PinyinMatch::Initials));
+    assert_eq!(match_text("mkszyjbyl", "马克思主义基本原理"), Some(MatchKind::PinyinInitials));
+    assert_eq!(match_text("makesi", "马克思主义基本原理"), Some(MatchKind::Pinyin));
+
+    let expected = ["yi", "yu", "xiang", "ha", "xi", "xian", "jun", "jiao", "wang", "lu"];
+    for (c, s) in "义宇翔哈喜险峻娇旺璐".chars().zip(expected) {
+        assert_eq!(syllable(c), Some(s), "{}", c);
+    }
+    // 多音字取课表里常见的读音
+    assert_eq!(syllables("音乐长重"), vec!["yin", "yue", "chang", "zhong"]);
+}
+
+#[test]
+fn test_match_kinds() {
+    assert_eq!(match_text("math101", "MATH101"), Some(MatchKind::Exact));
+    assert_eq!(match_text("数学", "高等数学"), Some(MatchKind::Substring));
+    assert_eq!(match_text("zhangwei", "张伟"), Some(MatchKind::Pinyin));
+    assert_eq!(match_text("gdsx", "高等数学"), Some(MatchKind::PinyinInitials));
+    assert_eq!(match_text("高数", "高等数学"), Some(MatchKind::Fuzzy));
+    assert_eq!(match_text("gaoshu", "高等数学"), Some(MatchKind::Fuzzy));
+    assert_eq!(match_text("mth101", "MATH101"), Some(MatchKind::Fuzzy));
+    // 拼音打错一个字母
+    assert_eq!(match_text("gaodengshuxeu", "高等数学"), Some(MatchKind::Fuzzy));
+    assert_eq!(match_text("xyz", "高等数学"), None);
+    assert_eq!(match_text("ab", "A101B"), None);
+}
+
+#[test]
+fn test_search_returns_occurrences() {
+    let (weeks, semester_weeks) = fixture();
+
+    let results = search(&weeks, &semester_weeks, "gdsx", OccurrenceRange::default(), 50);
+    assert_eq!(results.total, 2);
+    let dates: Vec<&str> = results.hits.iter().map(|h| h.occurrence.date.as_str()).collect();
+    assert_eq!(dates, vec!["2024-10-14", "2024-10-21"]);
+    assert_eq!(results.hits[0].matched.field, SearchField::Name);
+    assert_eq!(results.hits[0].matched.kind, MatchKind::PinyinInitials);
+
+    // 按教师、教室搜索
+    let results = search(&weeks, &semester_weeks, "wl", OccurrenceRange::default(), 50);
+    assert!(results.hits.iter().all(|h| h.occurrence.course.code == "CS201"));
+    assert_eq!(results.hits[0].matched.field, SearchField::Teacher);
+    let results = search(&weeks, &semester_weeks, "B203", OccurrenceRange::default(), 50);
+    assert_eq!(results.hits[0].matched.field, SearchField::Classroom);
+    assert_eq!(results.total, 2);
+
+    // 按相关度排序，同一相关度内按时间
+    let results = search(&weeks, &semester_weeks, "数", OccurrenceRange::default(), 3);
+    assert_eq!(results.total, 4);
+    assert_eq!(results.hits.len(), 3);
+    let codes: Vec<&str> = results.hits.iter().map(|h| h.occurrence.course.code.as_str()).collect();
+    assert_eq!(codes, vec!["MATH101", "CS201", "MATH101"]);
+
+    let range = OccurrenceRange { from: backend::services::calendar::parse_date("2024-10-21"), ..Default::default() };
+    let results = search(&weeks, &semester_weeks, "计算机", range, 50);
+    assert_eq!(results.total, 3);
+    assert_eq!(results.hits[0].matched.field, SearchField::Class);
+}
@@ -0,0 +1,63 @@
+// tests/semester_parse_test.rs
+// 学期周列表校验测试（不依赖学校服务器）
+use backend::parser::schedule::{parse_semester_weeks, SemesterParseError};
+
+fn row(week: &str, start: &str, end: &str) -> Vec<String> {
+    vec![week.to_string(), start.to_string(), end.to_string()]
+}
+
+#[test]
+fn test_valid_rows_sorted_and_deduplicated() {
+    let rows = vec![
+        row("2", "2024-09-09", "2024-09-15"),
+        row("1", "2024-09-02", "2024-09-08"),
+        row("2", "2024-09-09", "2024-09-15"),
+        row("3", "2024-9-16", "2024-9-22"),
+    ];
+    let weeks = parse_semester_weeks(&rows).unwrap();
+    let summary: Vec<_> = weeks.iter().map(|w| (w.week, w.start_time.as_str(), w.end_time.as_str())).collect();
+    assert_eq!(
+        summary,
+        vec![(1, "2024-09-02", "2024-09-08"), (2, "2024-09-09", "2024-09-15"), (3, "2024-09-16", "2024-09-22")]
+    );
+}
+
+#[test]
+fn test_invalid_rows_dropped_and_gaps_filled() {
+    let rows = vec![
+        row("1", "2024-09-02", "2024-09-08"),
+        row("", "2024-09-09", "2024-09-15"),
+        row("3", "", ""),
+        vec!["4".to_string()],
+        row("5", "2024-09-30", "2024-10-06"),
+        row("6", "2024-10-07", "2024-10-20"),
+    ];
+    let weeks = parse_semester_weeks(&rows).unwrap();
+    let numbers: Vec<u32> = weeks.iter().map(|w| w.week).collect();
+    assert_eq!(numbers, vec![1, 2, 3, 4, 5]);
+    assert_eq!(weeks[1].start_time, "2024-09-09");
+    assert_eq!(weeks[3].end_time, "2024-09-29");
+    // 不会出现空的开始日期
+    assert!(weeks.iter().all(|w| !w.start_time.is_empty()));
+}
+
+#[test]
+fn test_unusable_data_errors() {
+    assert_eq!(parse_semester_weeks(&[]).unwrap_err(), SemesterParseError::Empty);
+    assert_eq!(
+        parse_semester_weeks(&[row("0", "2024-09-02", "2024-09-08"), row("x", "", "")]).unwrap_err(),
+        SemesterParseError::NoValidRows { rejected: 2 }
+    );
+    assert_eq!(
+        parse_semester_weeks(&[row("1", "2024-09-02", "2024-09-08"), row("1", "2024-09-09", "2024-09-15")]).unwrap_err(),
+        SemesterParseError::ConflictingDuplicate { week: 1 }
+    );
+    assert!(matches!(
+        parse_semester_weeks(&[row("1", "2024-09-09", "2024-09-15"), row("2", "2024-09-02", "2024-09-08")]),
+        Err(SemesterParseError::NonMonotonic { week: 2, previous: 1, .. })
+    ));
+    assert_eq!(
+        parse_semester_weeks(&[row("1", "2024-09-02", "2024-09-08"), row("3", "2024-09-09", "2024-09-15")]).unwrap_err(),
+        SemesterParseError::Discontinuous { from: 1, to: 3, days: 14 }
+    );
+}
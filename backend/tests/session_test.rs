@@ -0,0 +1,63 @@
+// tests/session_test.rs
+// 会话令牌与 UCode 加密测试（不依赖学校服务器）
+use backend::utils::crypto::{decrypt_with_secret, encrypt_with_secret};
+use backend::utils::token::{bearer_token, sign, verify, SessionClaims, TokenError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn now() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64
+}
+
+fn claims(exp_offset: i64) -> SessionClaims {
+    SessionClaims {
+        sub: "ucode-hash".to_string(),
+        sid: "session-id".to_string(),
+        iat: now(),
+        exp: now() + exp_offset,
+    }
+}
+
+#[test]
+fn test_token_roundtrip() {
+    let token = sign(&claims(3600), "secret");
+    let verified = verify(&token, "secret").expect("令牌应校验通过");
+    assert_eq!(verified.sub, "ucode-hash");
+    assert_eq!(verified.sid, "session-id");
+}
+
+#[test]
+fn test_token_rejects_wrong_secret_and_tampering() {
+    let token = sign(&claims(3600), "secret");
+    assert!(matches!(verify(&token, "other"), Err(TokenError::BadSignature)));
+
+    // 篡改载荷后签名不再匹配
+    let mut parts: Vec<&str> = token.split('.').collect();
+    let forged = sign(&SessionClaims { sub: "someone-else".to_string(), ..claims(3600) }, "other");
+    let forged_payload = forged.split('.').nth(1).unwrap().to_string();
+    parts[1] = &forged_payload;
+    assert!(matches!(verify(&parts.join("."), "secret"), Err(TokenError::BadSignature)));
+
+    assert!(matches!(verify("not-a-token", "secret"), Err(TokenError::Malformed)));
+}
+
+#[test]
+fn test_token_expired() {
+    let token = sign(&claims(-10), "secret");
+    assert!(matches!(verify(&token, "secret"), Err(TokenError::Expired)));
+}
+
+#[test]
+fn test_bearer_token() {
+    assert_eq!(bearer_token("Bearer abc"), Some("abc"));
+    assert_eq!(bearer_token("bearer   abc "), Some("abc"));
+    assert_eq!(bearer_token("Basic abc"), None);
+    assert_eq!(bearer_token("Bearer "), None);
+}
+
+#[test]
+fn test_ucode_encryption_roundtrip() {
+    let encrypted = encrypt_with_secret("ABC123", "secret").unwrap();
+    assert_ne!(encrypted, encrypt_with_secret("ABC123", "secret").unwrap(), "每次加密应使用不同的 nonce");
+    assert_eq!(decrypt_with_secret(&encrypted, "secret").unwrap(), "ABC123");
+    assert!(decrypt_with_secret(&encrypted, "other").is_err());
+}
@@ -0,0 +1,65 @@
+// tests/snapshot_test.rs
+// 课表快照测试（使用内存数据库，不依赖学校服务器）
+mod common;
+
+use backend::db::connection::init_db;
+use backend::parser::schedule::{CourseInfo, DayCourse};
+use backend::services::snapshot::{content_hash, get_snapshot_as_of, list_snapshots, parse_as_of, record_snapshot};
+use common::{course, day, taught};
+use std::collections::HashMap;
+
+fn weeks(classroom: &str, count: u32) -> HashMap<u32, Vec<DayCourse>> {
+    let info = CourseInfo { name: "高等数学".to_string(), ..taught(course("MATH101", 2, 1, 1), classroom, &["张老师"]) };
+    (1..=count).map(|w| (w, vec![day(2, vec![info.clone()])])).collect()
+}
+
+#[test]
+fn test_content_hash_is_stable() {
+    let a = weeks("B203", 10);
+    let b = weeks("B203", 10);
+    assert_eq!(content_hash(&a).unwrap(), content_hash(&b).unwrap());
+    assert_ne!(content_hash(&a).unwrap(), content_hash(&weeks("B204", 10)).unwrap());
+}
+
+#[test]
+fn test_parse_as_of() {
+    assert_eq!(parse_as_of("1700000000000"), Some(1_700_000_000_000));
+    // 2024-10-08T08:00:00+08:00
+    let morning = 1_728_345_600_000;
+    assert_eq!(parse_as_of("2024-10-08T08:00:00+08:00"), Some(morning));
+    assert_eq!(parse_as_of("2024-10-08T08:00:00"), Some(morning));
+    // 东八区当天结束时（08:00 再过 16 小时的前一毫秒）
+    assert_eq!(parse_as_of("2024-10-08"), Some(morning + 16 * 3_600_000 - 1));
+    assert_eq!(parse_as_of("last week"), None);
+    // 不是 13 位的数字不当作毫秒时间戳
+    assert_eq!(parse_as_of("20241008"), None);
+    assert_eq!(parse_as_of("1700000000"), None);
+    assert_eq!(parse_as_of("-1700000000000"), None);
+}
+
+#[tokio::test]
+async fn test_snapshots_deduplicate_and_resolve_as_of() {
+    std::env::set_var("DATABASE_URL", "sqlite::memory:");
+    let db = init_db().await.expect("初始化数据库失败");
+
+    let first = record_snapshot(&db, "user", "2024-2025", 1, &weeks("B203", 2)).await.unwrap();
+    let again = record_snapshot(&db, "user", "2024-2025", 1, &weeks("B203", 2)).await.unwrap();
+    assert_eq!(first.id, again.id);
+    assert_eq!(again.fetch_count, 2);
+
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    let changed = record_snapshot(&db, "user", "2024-2025", 1, &weeks("B204", 2)).await.unwrap();
+    assert_ne!(changed.id, first.id);
+
+    let versions = list_snapshots(&db, "user", 50).await.unwrap();
+    assert_eq!(versions.len(), 2);
+    assert_eq!(versions[0].id, changed.id);
+
+    let past = get_snapshot_as_of(&db, "user", first.first_seen_at).await.unwrap().unwrap();
+    assert_eq!(past.info.id, first.id);
+    let classroom = past.weeks[&1][0].course[0].course_info.as_ref().unwrap().classroom.clone();
+    assert_eq!(classroom.as_deref(), Some("B203"));
+
+    assert!(get_snapshot_as_of(&db, "user", first.first_seen_at - 1).await.unwrap().is_none());
+    assert!(list_snapshots(&db, "other", 50).await.unwrap().is_empty());
+}
@@ -0,0 +1,16 @@
+// tests/stats_test.rs
+// 访问统计计数测试（使用内存数据库，不依赖学校服务器）
+use backend::db::connection::init_db;
+use backend::services::stats::{get_stats, record_parse_failures};
+
+#[tokio::test]
+async fn test_parse_failures_accumulate_concurrently() {
+    std::env::set_var("DATABASE_URL", "sqlite::memory:");
+    let db = init_db().await.expect("初始化数据库失败");
+
+    let results = futures::future::join_all((1..=5).map(|n| record_parse_failures(&db, n))).await;
+    assert!(results.iter().all(|r| r.is_ok()));
+    record_parse_failures(&db, 0).await.unwrap();
+
+    assert_eq!(get_stats(&db).await.unwrap().parse_failures, 15);
+}
@@ -0,0 +1,69 @@
+// tests/summary_test.rs
+// 课时与进度统计测试（不依赖学校服务器）
+mod common;
+
+use backend::services::calendar::parse_date;
+use backend::services::summary::summarize;
+use chrono::DateTime;
+use common::semester_fixture;
+
+#[test]
+fn test_summary_totals_and_progress() {
+    let (weeks, semester_weeks) = semester_fixture();
+    let now = DateTime::parse_from_rfc3339("2024-10-16T12:00:00+08:00").unwrap();
+    let summary = summarize(&weeks, &semester_weeks, now, None);
+
+    assert_eq!(summary.lesson_duration, 45);
+    assert_eq!(summary.total_hours, 6.0);
+    assert_eq!(summary.weekly_hours.get(&7), Some(&3.0));
+    assert_eq!(summary.courses.len(), 2);
+
+    let math = &summary.courses[0];
+    assert_eq!(math.code, "MATH101");
+    assert_eq!(math.teachers, vec!["张老师".to_string()]);
+    assert_eq!((math.total_sessions, math.total_lessons), (2, 4));
+    assert_eq!(math.total_hours, 3.0);
+    assert_eq!((math.completed_sessions, math.remaining_sessions), (1, 1));
+    assert_eq!(math.remaining_hours, 1.5);
+    assert_eq!(math.first_date, "2024-10-14");
+    assert_eq!(math.last_date, "2024-10-21");
+    assert_eq!(math.next_date.as_deref(), Some("2024-10-21"));
+
+    // 当天下午的课还没上
+    let phy = &summary.courses[1];
+    assert_eq!((phy.completed_sessions, phy.remaining_sessions), (0, 2));
+    assert_eq!(phy.next_date.as_deref(), Some("2024-10-16"));
+}
+
+#[test]
+fn test_summary_counts_lesson_in_progress_as_remaining() {
+    let (weeks, semester_weeks) = semester_fixture();
+    // 周一第 1-2 节正在上
+    let now = DateTime::parse_from_rfc3339("2024-10-14T08:30:00+08:00").unwrap();
+    let summary = summarize(&weeks, &semester_weeks, now, None);
+
+    let math = &summary.courses[0];
+    assert_eq!((math.completed_sessions, math.remaining_sessions), (0, 2));
+    assert_eq!(math.completed_sessions + math.remaining_sessions, math.total_sessions);
+    assert_eq!(math.remaining_hours, 3.0);
+    assert_eq!(math.next_date.as_deref(), Some("2024-10-21"));
+}
+
+#[test]
+fn test_summary_remaining_until() {
+    let (weeks, semester_weeks) = semester_fixture();
+    let now = DateTime::parse_from_rfc3339("2024-10-16T12:00:00+08:00").unwrap();
+    let until = parse_date("2024-10-21");
+    let summary = summarize(&weeks, &semester_weeks, now, until);
+
+    assert_eq!(summary.until.as_deref(), Some("2024-10-21"));
+    let remaining: Vec<_> = summary.courses.iter().map(|c| (c.code.as_str(), c.remaining_sessions)).collect();
+    assert_eq!(remaining, vec![("MATH101", 1), ("PHY201", 1)]);
+    // 截止日期不影响总量
+    assert_eq!(summary.total_hours, 6.0);
+
+    // 学期结束后全部上完
+    let after = DateTime::parse_from_rfc3339("2024-10-28T00:00:00+08:00").unwrap();
+    let summary = summarize(&weeks, &semester_weeks, after, None);
+    assert!(summary.courses.iter().all(|c| c.completed_sessions == 2 && c.remaining_sessions == 0 && c.next_date.is_none()));
+}
@@ -0,0 +1,100 @@
+// tests/teacher_test.rs
+// 教师规范化与按教师整理课表测试（不依赖学校服务器）
+mod common;
+
+use backend::parser::schedule::{normalize_teachers, parse_course_string_strict, DayCourse, WeekInfo};
+use backend::services::teacher::teacher_index;
+use chrono::DateTime;
+use common::{course, day, semester_with, taught};
+use std::collections::HashMap;
+
+/// 第 8 周周三换了教室
+fn fixture() -> (HashMap<u32, Vec<DayCourse>>, Vec<WeekInfo>) {
+    semester_with(|week| {
+        let room = if week == 7 { "教学楼A101" } else { "实验楼201" };
+        vec![
+            day(1, vec![taught(course("MATH101", 1, 1, 2), "教学楼A101", &["张老师", "李老师"])]),
+            // 未规范化的旧缓存数据
+            day(3, vec![taught(course("PHY201", 3, 5, 2), room, &[" 张老师， 王老师 "])]),
+        ]
+    })
+}
+
+#[test]
+fn test_normalize_teachers() {
+    assert_eq!(normalize_teachers("张老师;李老师"), vec!["张老师", "李老师"]);
+    assert_eq!(normalize_teachers(" 张老师 ，李老师、王老师/张老师；"), vec!["张老师", "李老师", "王老师"]);
+    assert!(normalize_teachers("").is_empty());
+    assert!(normalize_teachers(" ; 无 ").is_empty());
+
+    let course = parse_course_string_strict("高等数学|A101|计算机2401|张老师, 李老师;张老师|1|1|#FF5733|2|MATH101")
+        .unwrap()
+        .unwrap();
+    assert_eq!(course.teacher, vec!["张老师", "李老师"]);
+}
+
+#[test]
+fn test_teacher_index() {
+    let (weeks, semester_weeks) = fixture();
+    let now = DateTime::parse_from_rfc3339("2024-10-16T20:00:00+08:00").unwrap();
+    let teachers = teacher_index(&weeks, &semester_weeks, now, None);
+
+    let names: Vec<&str> = teachers.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, vec!["张老师", "李老师", "王老师"]);
+
+    let zhang = &teachers[0];
+    assert_eq!(zhang.courses.len(), 2);
+    assert_eq!(zhang.classrooms, vec!["教学楼A101", "实验楼201"]);
+
+    let math = zhang.courses.iter().find(|c| c.code == "MATH101").unwrap();
+    assert_eq!(math.meetings.len(), 1);
+    let meeting = &math.meetings[0];
+    assert_eq!((meeting.weekday, meeting.start_slot, meeting.end_slot), (1, 1, 2));
+    assert_eq!(meeting.weeks, vec![7, 8]);
+    assert!(meeting.start_time.is_some());
+
+    // 换教室的那周单独列出
+    let physics = zhang.courses.iter().find(|c| c.code == "PHY201").unwrap();
+    let rooms: Vec<(Option<&str>, Vec<u32>)> =
+        physics.meetings.iter().map(|m| (m.classroom.as_deref(), m.weeks.clone())).collect();
+    assert_eq!(rooms, vec![(Some("教学楼A101"), vec![7]), (Some("实验楼201"), vec![8])]);
+
+    // 周三晚上，下一次见到张老师是第 8 周周一
+    let next = zhang.next.as_ref().unwrap();
+    assert_eq!((next.date.as_str(), next.course.code.as_str()), ("2024-10-21", "MATH101"));
+    let wang = &teachers[2];
+    assert_eq!(wang.next.as_ref().unwrap().date, "2024-10-23");
+}
+
+#[test]
+fn test_teacher_index_filter_and_semester_end() {
+    let (weeks, semester_weeks) = fixture();
+    let now = DateTime::parse_from_rfc3339("2024-10-28T08:00:00+08:00").unwrap();
+
+    let teachers = teacher_index(&weeks, &semester_weeks, now, Some(" 王 "));
+    assert_eq!(teachers.len(), 1);
+    assert_eq!(teachers[0].name, "王老师");
+    assert!(teachers[0].next.is_none());
+}
+
+#[test]
+fn test_makeup_day_joins_weekly_meeting() {
+    // 第 4-6 周每周五有课；2024-09-29（周日）调休上 10-04（第 5 周周五，国庆放假）的课
+    let semester_weeks = vec![
+        WeekInfo { week: 4, start_time: "2024-09-23".to_string(), end_time: "2024-09-29".to_string() },
+        WeekInfo { week: 5, start_time: "2024-09-30".to_string(), end_time: "2024-10-06".to_string() },
+        WeekInfo { week: 6, start_time: "2024-10-07".to_string(), end_time: "2024-10-13".to_string() },
+    ];
+    let weeks = semester_weeks
+        .iter()
+        .map(|w| (w.week, vec![day(5, vec![taught(course("MATH101", 5, 1, 2), "教学楼A101", &["张老师"])])]))
+        .collect();
+    let now = DateTime::parse_from_rfc3339("2024-09-23T08:00:00+08:00").unwrap();
+    let teachers = teacher_index(&weeks, &semester_weeks, now, None);
+
+    let meetings = &teachers[0].courses[0].meetings;
+    assert_eq!(meetings.len(), 1);
+    assert_eq!(meetings[0].weekday, 5);
+    assert_eq!(meetings[0].weeks, vec![4, 5, 6]);
+    assert_eq!(meetings[0].makeup_dates, vec!["2024-09-29"]);
+}
@@ -0,0 +1,70 @@
+// tests/time_table_test.rs
+// 作息时间配置测试（不依赖学校服务器）
+use backend::utils::schedule::{TimeTableConfig, TimeTableConfigError};
+
+/// 内置配置改成指定内容后的 TOML
+fn builtin_with(replace: &str, with: &str) -> String {
+    let text = include_str!("../config/time_tables.toml");
+    assert!(text.contains(replace), "fixture 不包含 {}", replace);
+    text.replacen(replace, with, 1)
+}
+
+#[test]
+fn test_builtin_seasons() {
+    let config = TimeTableConfig::builtin();
+    assert_eq!(config.total_lessons, 10);
+    assert_eq!(config.season_for_date("2025-05-31"), "winter");
+    assert_eq!(config.season_for_date("2025-06-01"), "summer");
+    assert_eq!(config.season_for_date("2025-09-30"), "summer");
+    assert_eq!(config.season_for_date("2025-10-01"), "winter");
+    // 日期不合法时使用默认作息
+    assert_eq!(config.season_for_date("not a date"), "winter");
+    assert_eq!(config.time_table("summer", None)[4], ("14:30".to_string(), "15:15".to_string()));
+}
+
+#[test]
+fn test_season_range_across_new_year_and_campus() {
+    let text = builtin_with("start = \"06-01\"\nend = \"09-30\"", "start = \"11-15\"\nend = \"02-28\"")
+        + "\n[campuses.north.time_tables]\nwinter = [\n    [\"08:10\", \"08:55\"], [\"09:05\", \"09:50\"],\n    [\"10:10\", \"10:55\"], [\"11:05\", \"11:50\"],\n    [\"14:00\", \"14:45\"], [\"14:55\", \"15:40\"],\n    [\"16:00\", \"16:45\"], [\"16:55\", \"17:40\"],\n    [\"19:00\", \"19:45\"], [\"19:55\", \"20:40\"],\n]\n";
+    let config = TimeTableConfig::from_toml(&text).unwrap();
+    assert_eq!(config.season_for_date("2024-12-31"), "summer");
+    assert_eq!(config.season_for_date("2025-01-10"), "summer");
+    assert_eq!(config.season_for_date("2025-03-01"), "winter");
+
+    // 校区只覆盖写出的作息，其余沿用默认
+    assert_eq!(config.time_table("winter", Some("north"))[0].0, "08:10");
+    assert_eq!(config.time_table("summer", Some("north"))[0].0, "08:00");
+    assert_eq!(config.time_table("winter", Some("unknown"))[0].0, "08:00");
+}
+
+#[test]
+fn test_invalid_configs_are_rejected() {
+    let invalid = |text: String| matches!(TimeTableConfig::from_toml(&text), Err(TimeTableConfigError::Invalid(_)));
+
+    // 节次数与 total_lessons 不一致
+    assert!(invalid(builtin_with("total_lessons = 10", "total_lessons = 11")));
+    // 时令没有对应的时间表
+    assert!(invalid(builtin_with("name = \"summer\"", "name = \"autumn\"")));
+    // 日期格式不合法
+    assert!(invalid(builtin_with("end = \"09-30\"", "end = \"09-31\"")));
+    // 下课早于上课
+    assert!(invalid(builtin_with("[\"14:30\", \"15:15\"]", "[\"15:30\", \"15:15\"]")));
+    // 与上一节重叠
+    assert!(invalid(builtin_with("[\"14:30\", \"15:15\"]", "[\"11:00\", \"15:15\"]")));
+
+    assert!(matches!(TimeTableConfig::from_toml("total_lessons = "), Err(TimeTableConfigError::Parse(_))));
+}
+
+#[test]
+fn test_load_json_file() {
+    let builtin = TimeTableConfig::builtin();
+    let path = std::env::temp_dir().join(format!("time_tables_{}.json", std::process::id()));
+    std::fs::write(&path, serde_json::to_string(&builtin).unwrap()).unwrap();
+
+    let loaded = TimeTableConfig::load(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+    assert_eq!(loaded.time_tables, builtin.time_tables);
+    assert_eq!(loaded.season_for_date("2025-07-01"), "summer");
+
+    assert!(matches!(TimeTableConfig::load("/nonexistent/time_tables.toml"), Err(TimeTableConfigError::Io(_))));
+}
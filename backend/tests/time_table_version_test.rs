@@ -0,0 +1,86 @@
+// tests/time_table_version_test.rs
+// 带生效日期的时间表版本测试（使用内存数据库，不依赖学校服务器）
+use backend::db::connection::init_db;
+use backend::services::calendar::{day_schedule, parse_date};
+use backend::services::time_table::{create_version, delete_version, list_versions, validate_version, NewTimeTableVersion, TimeTableVersionError};
+use backend::utils::schedule::{get_course_time_table, select_time_table_version, TimeTableVersion};
+
+fn times(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs.iter().map(|(s, e)| (s.to_string(), e.to_string())).collect()
+}
+
+fn version(id: i32, from: &str, to: &str) -> TimeTableVersion {
+    TimeTableVersion {
+        id,
+        name: format!("版本{}", id),
+        effective_from: from.to_string(),
+        effective_to: to.to_string(),
+        times: times(&[("08:30", "10:30")]),
+        created_at: 0,
+    }
+}
+
+fn new_version(from: &str, to: &str, lessons: &[(&str, &str)]) -> NewTimeTableVersion {
+    NewTimeTableVersion {
+        name: "期末考试周".to_string(),
+        effective_from: from.to_string(),
+        effective_to: to.to_string(),
+        times: times(lessons),
+    }
+}
+
+#[test]
+fn test_select_most_specific_version() {
+    // 整个防控期的临时作息，其中一周为考试周
+    let versions = vec![version(1, "2025-03-01", "2025-04-30"), version(2, "2025-04-07", "2025-04-13")];
+    assert_eq!(select_time_table_version(&versions, "2025-03-15").map(|v| v.id), Some(1));
+    assert_eq!(select_time_table_version(&versions, "2025-04-10").map(|v| v.id), Some(2));
+    assert_eq!(select_time_table_version(&versions, "2025-04-13").map(|v| v.id), Some(2));
+    assert!(select_time_table_version(&versions, "2025-05-01").is_none());
+
+    // 范围相同时后创建的优先
+    let same = vec![version(3, "2025-06-01", "2025-06-01"), version(4, "2025-06-01", "2025-06-01")];
+    assert_eq!(select_time_table_version(&same, "2025-06-01").map(|v| v.id), Some(4));
+}
+
+#[test]
+fn test_validate_version() {
+    let ok = validate_version(&new_version("2025-1-6", "2025-01-12", &[("08:30", "10:30")])).unwrap();
+    assert_eq!(ok.effective_from, "2025-01-06");
+
+    let invalid = |v: NewTimeTableVersion| matches!(validate_version(&v), Err(TimeTableVersionError::Invalid(_)));
+    assert!(invalid(new_version("2025-01-12", "2025-01-06", &[("08:30", "10:30")])));
+    assert!(invalid(new_version("2025-01-06", "2025-01-12", &[])));
+    assert!(invalid(new_version("2025-01-06", "2025-01-12", &[("10:30", "08:30")])));
+    assert!(invalid(new_version("2025-01-06", "2025-01-12", &[("08:00", "09:00"); 11])));
+}
+
+#[tokio::test]
+async fn test_versions_override_time_table() {
+    std::env::set_var("DATABASE_URL", "sqlite::memory:");
+    let db = init_db().await.expect("初始化数据库失败");
+
+    let default_first = get_course_time_table("2025-01-08").times[0].clone();
+    assert_eq!(default_first.0, "08:00");
+
+    let created = create_version(&db, &new_version("2025-01-06", "2025-01-12", &[("08:30", "10:30"), ("10:50", "12:50")]))
+        .await
+        .unwrap();
+    assert_eq!(list_versions(&db).await.unwrap().len(), 1);
+
+    // 生效范围内使用版本时间表，范围外不受影响
+    let table = get_course_time_table("2025-01-08").times;
+    assert_eq!(table, times(&[("08:30", "10:30"), ("10:50", "12:50")]));
+    assert_eq!(get_course_time_table("2025-01-13").times[0], default_first);
+
+    // 按日期查询的课表报告实际使用的时间表
+    let weeks = std::collections::HashMap::new();
+    let day = day_schedule(&weeks, &[], parse_date("2025-01-08").unwrap());
+    assert_eq!(day.season, format!("version:{}", created.id));
+    let day = day_schedule(&weeks, &[], parse_date("2025-01-13").unwrap());
+    assert_eq!(day.season, "winter");
+
+    delete_version(&db, created.id).await.unwrap();
+    assert_eq!(get_course_time_table("2025-01-08").times[0], default_first);
+    assert!(matches!(delete_version(&db, created.id).await, Err(TimeTableVersionError::NotFound)));
+}
@@ -0,0 +1,106 @@
+// tests/walking_test.rs
+// 课间换楼步行时间提醒测试（不依赖学校服务器）
+mod common;
+
+use backend::parser::schedule::CourseInfo;
+use backend::services::calendar::{walk_warnings, TimedLesson};
+use backend::utils::location::{set_locations, LocationConfig, LocationParser, LocationRule, WalkingTime};
+use common::course;
+
+fn config() -> LocationConfig {
+    LocationConfig {
+        default_campus: Some("南校区".to_string()),
+        online_keywords: vec!["线上".to_string()],
+        rules: vec![LocationRule {
+            name: "校区 + 楼 + 房间号".to_string(),
+            pattern: r"^(?P<campus>南校区|东校区)?(?P<building>\D+?)(?P<room>\d{3,4})$".to_string(),
+        }],
+        cross_campus_minutes: Some(30),
+        default_walk_minutes: Some(5),
+        walking: vec![WalkingTime {
+            from: "教学楼A".to_string(),
+            to: "实验楼".to_string(),
+            minutes: 12,
+        }],
+        ..Default::default()
+    }
+}
+
+fn lesson(parser: &LocationParser, name: &str, start: u32, times: (&str, &str), classroom: &str) -> TimedLesson {
+    TimedLesson {
+        start,
+        end: start + 1,
+        start_time: Some(times.0.to_string()),
+        end_time: Some(times.1.to_string()),
+        course: CourseInfo {
+            name: name.to_string(),
+            classroom: Some(classroom.to_string()),
+            ..course(name, 1, start, 2)
+        },
+        location: Some(parser.parse(classroom)),
+    }
+}
+
+#[test]
+fn test_walk_minutes() {
+    let parser = LocationParser::new(config()).unwrap();
+    let walk = |a: &str, b: &str| parser.walk_minutes(&parser.parse(a), &parser.parse(b));
+
+    assert_eq!(walk("教学楼A101", "教学楼A305"), Some(0));
+    // 配置的步行时间不分方向
+    assert_eq!(walk("教学楼A101", "实验楼201"), Some(12));
+    assert_eq!(walk("实验楼201", "教学楼A101"), Some(12));
+    assert_eq!(walk("教学楼A101", "东校区教学楼A101"), Some(30));
+    assert_eq!(walk("教学楼A101", "图书馆101"), Some(5));
+    assert_eq!(walk("教学楼A101", "线上教学"), None);
+
+    let no_default = LocationParser::new(LocationConfig { default_walk_minutes: None, ..config() }).unwrap();
+    let (a, b) = (no_default.parse("教学楼A101"), no_default.parse("图书馆101"));
+    assert_eq!(no_default.walk_minutes(&a, &b), None);
+}
+
+#[test]
+fn test_walking_config_validation() {
+    let mut invalid = config();
+    invalid.walking[0].to = " ".to_string();
+    assert!(invalid.validate().is_err());
+    assert!(LocationParser::new(invalid).is_err());
+
+    let text = r#"
+        cross_campus_minutes = 25
+
+        [[walking]]
+        from = "教学楼A"
+        to = "图书馆"
+        minutes = 8
+    "#;
+    let parsed = LocationConfig::from_toml(text).unwrap();
+    assert_eq!(parsed.cross_campus_minutes, Some(25));
+    assert_eq!(parsed.walking[0].minutes, 8);
+}
+
+#[test]
+fn test_walk_warnings_for_back_to_back_lessons() {
+    let parser = set_locations(config()).unwrap();
+    let lessons = vec![
+        lesson(&parser, "高等数学", 1, ("08:00", "09:40"), "教学楼A101"),
+        // 同楼不提醒
+        lesson(&parser, "大学英语", 3, ("09:50", "11:30"), "教学楼A305"),
+        // 午休足够走到东校区
+        lesson(&parser, "体育", 5, ("14:00", "15:40"), "东校区体育馆101"),
+        // 10 分钟课间跨校区来不及
+        lesson(&parser, "大学物理", 7, ("15:50", "17:30"), "实验楼201"),
+        lesson(&parser, "线上讲座", 9, ("17:35", "18:20"), "线上"),
+    ];
+
+    let warnings = walk_warnings("2024-10-08", 2, &lessons);
+    assert_eq!(warnings.len(), 1);
+    let warning = &warnings[0];
+    assert_eq!((warning.from_lesson, warning.to_lesson), (5, 7));
+    assert_eq!((warning.from_course.as_str(), warning.to_course.as_str()), ("体育", "大学物理"));
+    assert_eq!((warning.break_minutes, warning.walk_minutes), (10, 30));
+    assert_eq!(warning.from.campus.as_deref(), Some("东校区"));
+    assert_eq!((warning.date.as_str(), warning.weekday), ("2024-10-08", 2));
+
+    set_locations(LocationConfig::builtin()).unwrap();
+}
@@ -0,0 +1,79 @@
+// tests/webhook_test.rs
+// Webhook 推送地址的内网地址拦截与重定向测试（不依赖学校服务器）
+use backend::services::webhook::{check_resolved, delivery_client, is_disallowed_ip, resolve_target, validate_url};
+use std::io::{Read, Write};
+use std::net::{IpAddr, SocketAddr, TcpListener};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[test]
+fn test_rejects_mapped_link_local_and_cgnat_addresses() {
+    // IPv4 映射的 IPv6 地址
+    assert!(validate_url("http://[::ffff:127.0.0.1]/hook").is_err());
+    assert!(validate_url("http://[::ffff:10.0.0.1]/hook").is_err());
+    assert!(validate_url("http://[::ffff:169.254.169.254]/hook").is_err());
+    // 链路本地、运营商级 NAT、0.0.0.0/8
+    assert!(validate_url("http://[fe80::1]/hook").is_err());
+    assert!(validate_url("http://169.254.169.254/latest/meta-data").is_err());
+    assert!(validate_url("http://100.64.0.1/hook").is_err());
+    assert!(validate_url("http://100.127.255.254/hook").is_err());
+    assert!(validate_url("http://0.1.2.3/hook").is_err());
+    assert!(validate_url("http://[64:ff9b::7f00:1]/hook").is_err());
+    assert!(validate_url("http://LOCALHOST./hook").is_err());
+
+    assert!(validate_url("http://100.128.0.1/hook").is_ok());
+    assert!(validate_url("http://[::ffff:8.8.8.8]/hook").is_ok());
+    assert!(validate_url("http://[2001:db8::1]/hook").is_ok());
+    assert!(!is_disallowed_ip("93.184.216.34".parse().unwrap()));
+}
+
+#[test]
+fn test_resolved_addresses_are_checked() {
+    let public: IpAddr = "93.184.216.34".parse().unwrap();
+    let private: IpAddr = "10.0.0.8".parse().unwrap();
+    let mapped: IpAddr = "::ffff:192.168.0.1".parse().unwrap();
+
+    assert_eq!(check_resolved(&[public]).unwrap(), public);
+    // 只要有一个解析结果是内网地址就拒绝
+    assert!(check_resolved(&[public, private]).is_err());
+    assert!(check_resolved(&[mapped]).is_err());
+    assert!(check_resolved(&[]).is_err());
+}
+
+#[tokio::test]
+async fn test_resolve_target_rejects_literal_private_addresses() {
+    let url = "http://[::ffff:127.0.0.1]:8080/hook".parse().unwrap();
+    assert!(resolve_target(&url).await.is_err());
+
+    let url = "https://[2001:db8::1]/hook".parse().unwrap();
+    assert_eq!(resolve_target(&url).await.unwrap(), "[2001:db8::1]:443".parse::<SocketAddr>().unwrap());
+}
+
+#[tokio::test]
+async fn test_delivery_does_not_follow_redirects() {
+    // 本地服务把所有请求重定向到元数据地址，并统计收到的请求数
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf);
+            counter.fetch_add(1, Ordering::SeqCst);
+            let response = format!(
+                "HTTP/1.1 302 Found\r\nLocation: http://{}/latest/meta-data\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                addr
+            );
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+
+    // 域名固定连接到检查过的地址，不再自行解析
+    let url = format!("http://hooks.example.test:{}/hook", addr.port()).parse().unwrap();
+    let resp = delivery_client(&url, addr).unwrap().post(url).body("{}").send().await.unwrap();
+
+    assert_eq!(resp.status().as_u16(), 302);
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}